@@ -0,0 +1,153 @@
+// Command-line access to the IdeaNode database without starting the app.
+//
+//   ideanode-cli categories
+//   ideanode-cli add Ideas "Try the new cache" --content "measure first"
+//   ideanode-cli search 'tag:infra done:false' --output json
+//   ideanode-cli completions zsh > _ideanode-cli
+
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use ideanode_lib::cli::{self, Command, ExportFormat, Format};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(name = "ideanode-cli", version, about = "IdeaNode from the terminal")]
+struct Args {
+    /// Database file [default: the app's ideanode.sqlite3]
+    #[arg(long, global = true, env = "IDEANODE_DB")]
+    db: Option<PathBuf>,
+
+    /// Output format
+    #[arg(long, short, global = true, value_enum, default_value_t = OutputArg::Table)]
+    output: OutputArg,
+
+    #[command(subcommand)]
+    command: CommandArg,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OutputArg {
+    Table,
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportArg {
+    Json,
+    Markdown,
+}
+
+#[derive(Subcommand)]
+enum CommandArg {
+    /// List categories in board order
+    Categories {
+        /// Include archived categories
+        #[arg(long)]
+        archived: bool,
+    },
+    /// List memos, of one category or all
+    Memos {
+        /// Category id or title
+        #[arg(long, short)]
+        category: Option<String>,
+        /// Include archived memos and categories
+        #[arg(long)]
+        archived: bool,
+    },
+    /// Add a memo at the end of a category
+    Add {
+        /// Category id or title
+        category: String,
+        title: String,
+        /// Body text; each line becomes a paragraph
+        #[arg(long)]
+        content: Option<String>,
+        /// YYYY-MM-DD [default: today]
+        #[arg(long)]
+        date: Option<String>,
+        /// Text color, e.g. #ff0000
+        #[arg(long)]
+        color: Option<String>,
+    },
+    /// Search memos, e.g. 'tag:infra done:false "exact phrase" -draft'
+    Search {
+        /// Query; several arguments are joined with spaces
+        #[arg(required = true)]
+        query: Vec<String>,
+        #[arg(long)]
+        limit: Option<u32>,
+    },
+    /// Mark todo memos done
+    Done {
+        #[arg(required = true)]
+        memo_ids: Vec<String>,
+        /// Mark them not done instead
+        #[arg(long)]
+        undo: bool,
+    },
+    /// Export every category and memo, archived ones included
+    Export {
+        #[arg(long, short, value_enum, default_value_t = ExportArg::Json)]
+        format: ExportArg,
+    },
+    /// Print a shell completion script
+    Completions { shell: Shell },
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let command = match args.command {
+        CommandArg::Categories { archived } => Command::Categories { archived },
+        CommandArg::Memos { category, archived } => Command::Memos { category, archived },
+        CommandArg::Add {
+            category,
+            title,
+            content,
+            date,
+            color,
+        } => Command::Add {
+            category,
+            title,
+            content,
+            date,
+            color,
+        },
+        CommandArg::Search { query, limit } => Command::Search {
+            query: query.join(" "),
+            limit,
+        },
+        CommandArg::Done { memo_ids, undo } => Command::Done { memo_ids, undo },
+        CommandArg::Export { format } => Command::Export {
+            format: match format {
+                ExportArg::Json => ExportFormat::Json,
+                ExportArg::Markdown => ExportFormat::Markdown,
+            },
+        },
+        CommandArg::Completions { shell } => {
+            clap_complete::generate(
+                shell,
+                &mut Args::command(),
+                "ideanode-cli",
+                &mut std::io::stdout(),
+            );
+            return ExitCode::SUCCESS;
+        }
+    };
+    let format = match args.output {
+        OutputArg::Table => Format::Table,
+        OutputArg::Json => Format::Json,
+    };
+
+    match cli::run(args.db, format, command) {
+        Ok(out) => {
+            println!("{out}");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
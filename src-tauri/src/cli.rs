@@ -0,0 +1,368 @@
+// Work behind the `ideanode-cli` binary (`src/bin/ideanode-cli.rs`), which
+// parses arguments and calls `run`. It opens the app's database as a `Store`
+// without starting Tauri, so a memo added here goes through the same code as
+// one added in the app. Output is a table for
+// people or JSON for scripts.
+
+use crate::db;
+use crate::error::{AppError, AppResult, Entity};
+use crate::models::{
+    BulkReport, Category, CategoryWithMemos, CreateMemoInput, Memo, QueryMemosInput,
+    SetMemosTodoDoneInput,
+};
+use crate::stats;
+use crate::store::Store;
+use std::path::PathBuf;
+
+pub(crate) const DEFAULT_MEMO_COLOR: &str = "#ffffff";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Table,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Markdown,
+}
+
+#[derive(Debug, Clone)]
+pub enum Command {
+    /// Categories in board order.
+    Categories { archived: bool },
+    /// Memos of one category (id or title), or of all of them.
+    Memos {
+        category: Option<String>,
+        archived: bool,
+    },
+    /// Adds a memo at the end of a category. `content` is plain text.
+    Add {
+        category: String,
+        title: String,
+        content: Option<String>,
+        date: Option<String>,
+        color: Option<String>,
+    },
+    /// Search query, as in `query_memos`.
+    Search { query: String, limit: Option<u32> },
+    /// Marks todo memos done, or not done with `undo`.
+    Done { memo_ids: Vec<String>, undo: bool },
+    /// The whole board, archived items included. `format` overrides the
+    /// table/JSON choice.
+    Export { format: ExportFormat },
+}
+
+/// Runs `command` against the database at `db_path`, or the app's own
+/// database. Returns what to print, or the error message to print.
+pub fn run(db_path: Option<PathBuf>, format: Format, command: Command) -> Result<String, String> {
+    execute(db_path, format, command).map_err(|err| match format {
+        Format::Json => serde_json::to_string(&err).unwrap_or_else(|_| err.to_string()),
+        Format::Table => format!("오류: {} ({err})", err.localized_message()),
+    })
+}
+
+fn execute(db_path: Option<PathBuf>, format: Format, command: Command) -> AppResult<String> {
+    let mut store = open_existing(db_path)?;
+
+    match command {
+        Command::Categories { archived } => {
+            let categories: Vec<Category> = store
+                .list_all_categories_with_memos()?
+                .into_iter()
+                .map(|item| item.category)
+                .filter(|category| archived || !category.archived)
+                .collect();
+            render(format, categories.as_slice(), categories_table)
+        }
+        Command::Memos { category, archived } => {
+            let board = store.list_all_categories_with_memos()?;
+            let only = match category {
+                Some(key) => Some(resolve_category(&store, &key)?.id),
+                None => None,
+            };
+            let memos: Vec<Memo> = board
+                .into_iter()
+                .filter(|item| match &only {
+                    Some(id) => item.category.id == *id,
+                    None => archived || !item.category.archived,
+                })
+                .flat_map(|item| item.memos)
+                .filter(|memo| archived || !memo.archived)
+                .collect();
+            render(format, memos.as_slice(), |memos| memos_table(&store, memos))
+        }
+        Command::Add {
+            category,
+            title,
+            content,
+            date,
+            color,
+        } => {
+            let category = resolve_category(&store, &category)?;
+            let memo = store.create_memo(CreateMemoInput {
+                category_id: category.id,
+                emoji: None,
+                title,
+                color: color.unwrap_or_else(|| DEFAULT_MEMO_COLOR.to_string()),
+                date_ymd: date,
+                content_md: content.as_deref().map(text_to_html).unwrap_or_default(),
+            })?;
+            render(format, &memo, |memo| {
+                memos_table(&store, std::slice::from_ref(memo))
+            })
+        }
+        Command::Search { query, limit } => {
+            let memos = store.query_memos(QueryMemosInput { query, limit })?;
+            render(format, memos.as_slice(), |memos| memos_table(&store, memos))
+        }
+        Command::Done { memo_ids, undo } => {
+            let report = store.set_memos_todo_done(SetMemosTodoDoneInput {
+                memo_ids,
+                todo_done: !undo,
+            })?;
+            render(format, &report, report_table)
+        }
+        Command::Export {
+            format: export_format,
+        } => {
+            let board = store.list_all_categories_with_memos()?;
+            match export_format {
+                ExportFormat::Json => Ok(to_json(&board)?),
+                ExportFormat::Markdown => Ok(markdown(&board)),
+            }
+        }
+    }
+}
+
+/// Opens the database at `db_path`, or the app's own one, without creating
+/// a new file when there is none.
+pub(crate) fn open_existing(db_path: Option<PathBuf>) -> AppResult<Store> {
+    let path = match db_path.or_else(db::default_db_path) {
+        Some(path) => path,
+        None => {
+            return Err(AppError::Internal(
+                "no data directory; pass the database path with --db".to_string(),
+            ))
+        }
+    };
+    if !path.exists() {
+        return Err(AppError::Internal(format!(
+            "database not found: {}",
+            path.display()
+        )));
+    }
+    Store::open(&path)
+}
+
+/// A category by id, or by title ignoring case. When several have the
+/// title, an active one is preferred; a tie is an error.
+pub(crate) fn resolve_category(store: &Store, key: &str) -> AppResult<Category> {
+    let key = key.trim();
+    let categories: Vec<Category> = store
+        .list_all_categories_with_memos()?
+        .into_iter()
+        .map(|item| item.category)
+        .collect();
+    if let Some(category) = categories.iter().find(|c| c.id == key) {
+        return Ok(category.clone());
+    }
+
+    let lower = key.to_lowercase();
+    let mut matches: Vec<&Category> = categories
+        .iter()
+        .filter(|c| c.title.to_lowercase() == lower)
+        .collect();
+    if matches.len() > 1 {
+        matches.retain(|c| !c.archived);
+    }
+    match matches.as_slice() {
+        [only] => Ok((*only).clone()),
+        [] => Err(AppError::not_found(Entity::Category, key)),
+        _ => Err(AppError::Internal(format!(
+            "several categories are titled \"{key}\"; use the id instead"
+        ))),
+    }
+}
+
+fn render<T: serde::Serialize + ?Sized>(
+    format: Format,
+    value: &T,
+    table: impl FnOnce(&T) -> String,
+) -> AppResult<String> {
+    match format {
+        Format::Json => to_json(value),
+        Format::Table => Ok(table(value)),
+    }
+}
+
+fn to_json<T: serde::Serialize + ?Sized>(value: &T) -> AppResult<String> {
+    serde_json::to_string_pretty(value).map_err(|e| AppError::Internal(e.to_string()))
+}
+
+/// Plain text → TipTap HTML: one paragraph per line, escaped.
+pub(crate) fn text_to_html(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            let escaped = line
+                .replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;");
+            format!("<p>{escaped}</p>")
+        })
+        .collect()
+}
+
+fn categories_table(categories: &[Category]) -> String {
+    let rows = categories
+        .iter()
+        .map(|c| {
+            vec![
+                c.id.clone(),
+                format!("{} {}", c.emoji, c.title).trim().to_string(),
+                c.kind.as_str().to_string(),
+                if c.archived { "archived" } else { "" }.to_string(),
+            ]
+        })
+        .collect();
+    table(&["ID", "TITLE", "KIND", ""], rows)
+}
+
+fn memos_table(store: &Store, memos: &[Memo]) -> String {
+    let titles: std::collections::HashMap<String, String> = store
+        .list_all_categories_with_memos()
+        .map(|board| {
+            board
+                .into_iter()
+                .map(|item| (item.category.id, item.category.title))
+                .collect()
+        })
+        .unwrap_or_default();
+    let rows = memos
+        .iter()
+        .map(|m| {
+            let state = match (m.todo_done, m.archived) {
+                (true, true) => "done, archived",
+                (true, false) => "done",
+                (false, true) => "archived",
+                (false, false) => "",
+            };
+            vec![
+                m.id.clone(),
+                titles.get(&m.category_id).cloned().unwrap_or_default(),
+                m.date_ymd.clone(),
+                format!("{} {}", m.emoji, m.title).trim().to_string(),
+                state.to_string(),
+            ]
+        })
+        .collect();
+    table(&["ID", "CATEGORY", "DATE", "TITLE", ""], rows)
+}
+
+fn report_table(report: &BulkReport) -> String {
+    let rows = report
+        .items
+        .iter()
+        .map(|item| {
+            vec![
+                item.id.clone(),
+                match &item.error {
+                    None => "ok".to_string(),
+                    Some(err) => err.localized_message(),
+                },
+            ]
+        })
+        .collect();
+    format!(
+        "{}\n{} succeeded, {} failed",
+        table(&["ID", "RESULT"], rows),
+        report.succeeded,
+        report.failed
+    )
+}
+
+/// Left-aligned columns separated by two spaces. Hangul and other wide
+/// characters count as two columns, as terminals draw them.
+fn table(headers: &[&str], rows: Vec<Vec<String>>) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|h| display_width(h)).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(display_width(cell));
+        }
+    }
+
+    let line = |cells: Vec<&str>| {
+        let mut out = String::new();
+        for (i, (cell, width)) in cells.iter().zip(&widths).enumerate() {
+            out.push_str(cell);
+            if i + 1 < cells.len() {
+                out.push_str(&" ".repeat(width - display_width(cell) + 2));
+            }
+        }
+        out.trim_end().to_string()
+    };
+
+    let mut lines = vec![line(headers.to_vec())];
+    for row in &rows {
+        lines.push(line(row.iter().map(String::as_str).collect()));
+    }
+    lines.join("\n")
+}
+
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| match c as u32 {
+            0x1100..=0x115F
+            | 0x2E80..=0xA4CF
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6
+            | 0x1F300..=0x1FAFF
+            | 0x20000..=0x3FFFD => 2,
+            _ => 1,
+        })
+        .sum()
+}
+
+/// The board as Markdown: a heading per category and a list item per memo,
+/// with todo state as a checkbox and the body's text indented below it.
+pub(crate) fn markdown(board: &[CategoryWithMemos]) -> String {
+    let mut out = String::new();
+    for item in board {
+        let category = &item.category;
+        out.push_str(&format!(
+            "## {}{}\n\n",
+            format!("{} {}", category.emoji, category.title).trim(),
+            if category.archived { " (archived)" } else { "" }
+        ));
+        for memo in &item.memos {
+            let checkbox = if category.is_todo {
+                if memo.todo_done {
+                    "[x] "
+                } else {
+                    "[ ] "
+                }
+            } else {
+                ""
+            };
+            out.push_str(&format!(
+                "- {checkbox}{} ({}){}\n",
+                format!("{} {}", memo.emoji, memo.title).trim(),
+                memo.date_ymd,
+                if memo.archived { " (archived)" } else { "" }
+            ));
+            let text = stats::plain_text(&memo.content_md)
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+            if !text.is_empty() {
+                out.push_str(&format!("  {text}\n"));
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
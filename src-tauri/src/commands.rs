@@ -1,470 +1,451 @@
-use crate::db::{get_memo_category_and_position, next_position, now_timestamp_ms, DbState};
+// Tauri commands. Each one locks the database and calls the `Store` method
+// of the same name, where it is documented.
+
+use crate::db::DbState;
+use crate::error::AppResult;
+use crate::http_api::{self, HttpApiState};
 use crate::models::{
-    AppSettings, Category, CategoryWithMemos, CreateCategoryInput, CreateMemoInput, Memo,
-    MoveMemoInput, ReorderCategoriesInput, ReorderMemosInput, SetBackgroundColorInput,
-    SetCategoryArchivedInput, SetCategoryCollapsedInput, UpdateCategoryInput, UpdateMemoInput,
+    AppSettings, Archive, BulkReport, Category, CategoryWithMemos, CreateCategoryInput,
+    CreateMemoFromTemplateInput, CreateMemoInput, CreateSmartCategoryInput, DateRangeInput,
+    DayCount, DeleteMemosInput, DuplicateCategoryInput, DuplicateMemoInput, HttpApiStatus,
+    IntegrityReport, ListArchiveInput, Memo, MonthInput, MoveMemoInput, MoveMemoToInput,
+    MoveMemosInput, OpenJournalEntryInput, QueryMemosInput, RecentlyCompletedInput,
+    ReorderCategoriesInput, ReorderMemosInput, RepairReport, SaveMemoAsTemplateInput,
+    SetBackgroundColorInput, SetCategoryArchivedInput, SetCategoryAutoArchiveInput,
+    SetCategoryCollapsedInput, SetCategoryDefaultTemplateInput, SetCategorySortInput,
+    SetHttpApiInput, SetMemoArchivedInput, SetMemoPinnedInput, SetMemoStarredInput,
+    SetMemosColorInput, SetMemosDateInput, SetMemosTodoDoneInput, SettingsFile, SmartCategory,
+    Statistics, StatisticsInput, Template, TodoDayCount, UpdateCategoryInput, UpdateCategoryResult,
+    UpdateMemoInput, UpdateMemoResult, UpdateSettingsInput, UpdateSmartCategoryInput,
 };
-use rusqlite::{params, OptionalExtension};
-use uuid::Uuid;
-
-fn row_to_category(row: &rusqlite::Row<'_>) -> rusqlite::Result<Category> {
-    Ok(Category {
-        id: row.get(0)?,
-        emoji: row.get(1)?,
-        title: row.get(2)?,
-        color: row.get(3)?,
-        position: row.get(4)?,
-        archived: row.get::<_, i64>(5)? != 0,
-        is_todo: row.get::<_, i64>(6)? != 0,
-        is_collapsed: row.get::<_, i64>(7)? != 0,
-        created_at: row.get(8)?,
-        updated_at: row.get(9)?,
-    })
-}
-
-fn row_to_memo(row: &rusqlite::Row<'_>) -> rusqlite::Result<Memo> {
-    Ok(Memo {
-        id: row.get(0)?,
-        category_id: row.get(1)?,
-        emoji: row.get(2)?,
-        title: row.get(3)?,
-        color: row.get(4)?,
-        date_ymd: row.get(5)?,
-        content_md: row.get(6)?,
-        todo_done: row.get::<_, i64>(7)? != 0,
-        position: row.get(8)?,
-        created_at: row.get(9)?,
-        updated_at: row.get(10)?,
-    })
-}
 
 #[tauri::command]
-pub fn list_categories_with_memos(state: tauri::State<'_, DbState>) -> Result<Vec<CategoryWithMemos>, String> {
-    let conn = state
-        .conn
-        .lock()
-        .map_err(|_| "db mutex poisoned".to_string())?;
-
-    let mut stmt = conn
-        .prepare(
-            "SELECT id, emoji, title, color, position, archived, is_todo, is_collapsed, created_at, updated_at
-             FROM categories
-             ORDER BY position ASC",
-        )
-        .map_err(|e| format!("query categories error: {e}"))?;
-
-    let categories_iter = stmt
-        .query_map([], row_to_category)
-        .map_err(|e| format!("map categories error: {e}"))?;
-
-    let mut out: Vec<CategoryWithMemos> = Vec::new();
-    for cat in categories_iter {
-        let category = cat.map_err(|e| format!("read category error: {e}"))?;
-
-        let mut memo_stmt = conn
-            .prepare(
-                "SELECT id, category_id, emoji, title, color, date_ymd, content_md, todo_done, position, created_at, updated_at
-                 FROM memos
-                 WHERE category_id = ?1
-                 ORDER BY position ASC",
-            )
-            .map_err(|e| format!("query memos error: {e}"))?;
-
-        let memo_iter = memo_stmt
-            .query_map(params![&category.id], row_to_memo)
-            .map_err(|e| format!("map memos error: {e}"))?;
-
-        let mut memos: Vec<Memo> = Vec::new();
-        for m in memo_iter {
-            memos.push(m.map_err(|e| format!("read memo error: {e}"))?);
-        }
-
-        out.push(CategoryWithMemos { category, memos });
-    }
-
-    Ok(out)
+pub fn list_categories_with_memos(
+    state: tauri::State<'_, DbState>,
+) -> AppResult<Vec<CategoryWithMemos>> {
+    state.lock()?.list_categories_with_memos()
 }
 
 #[tauri::command]
 pub fn create_category(
     state: tauri::State<'_, DbState>,
     input: CreateCategoryInput,
-) -> Result<Category, String> {
-    let mut conn = state
-        .conn
-        .lock()
-        .map_err(|_| "db mutex poisoned".to_string())?;
-
-    let tx = conn
-        .transaction()
-        .map_err(|e| format!("tx begin error: {e}"))?;
-
-    let id = Uuid::new_v4().to_string();
-    let ts = now_timestamp_ms();
-    let position = next_position(&tx, "categories", None)?;
-
-    tx.execute(
-        "INSERT INTO categories (id, emoji, title, color, position, archived, is_todo, is_collapsed, created_at, updated_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, 0, ?6, 0, ?7, ?8)",
-        params![
-            &id,
-            input.emoji.as_deref().unwrap_or(""),
-            &input.title,
-            &input.color,
-            position,
-            if input.is_todo { 1 } else { 0 },
-            ts,
-            ts
-        ],
-    )
-    .map_err(|e| format!("insert category error: {e}"))?;
-
-    let category = tx
-        .query_row(
-            "SELECT id, emoji, title, color, position, archived, is_todo, is_collapsed, created_at, updated_at
-             FROM categories WHERE id = ?1",
-            params![&id],
-            row_to_category,
-        )
-        .map_err(|e| format!("fetch category error: {e}"))?;
-
-    tx.commit().map_err(|e| format!("tx commit error: {e}"))?;
-    Ok(category)
+) -> AppResult<Category> {
+    state.lock()?.create_category(input)
 }
 
 #[tauri::command]
 pub fn update_category(
     state: tauri::State<'_, DbState>,
     input: UpdateCategoryInput,
-) -> Result<Category, String> {
-    let conn = state
-        .conn
-        .lock()
-        .map_err(|_| "db mutex poisoned".to_string())?;
-    let ts = now_timestamp_ms();
-
-    conn.execute(
-        "UPDATE categories SET emoji = ?1, title = ?2, color = ?3, updated_at = ?4 WHERE id = ?5",
-        params![&input.emoji, &input.title, &input.color, ts, &input.id],
-    )
-    .map_err(|e| format!("update category error: {e}"))?;
-
-    conn.query_row(
-        "SELECT id, emoji, title, color, position, archived, is_todo, is_collapsed, created_at, updated_at
-         FROM categories WHERE id = ?1",
-        params![&input.id],
-        row_to_category,
-    )
-    .map_err(|e| format!("fetch category error: {e}"))
+) -> AppResult<UpdateCategoryResult> {
+    state.lock()?.update_category(input)
 }
 
 #[tauri::command]
 pub fn set_category_archived(
     state: tauri::State<'_, DbState>,
     input: SetCategoryArchivedInput,
-) -> Result<Category, String> {
-    let conn = state
-        .conn
-        .lock()
-        .map_err(|_| "db mutex poisoned".to_string())?;
-    let ts = now_timestamp_ms();
-    let archived_int: i64 = if input.archived { 1 } else { 0 };
-
-    conn.execute(
-        "UPDATE categories SET archived = ?1, updated_at = ?2 WHERE id = ?3",
-        params![archived_int, ts, &input.id],
-    )
-    .map_err(|e| format!("set archived error: {e}"))?;
-
-    conn.query_row(
-        "SELECT id, emoji, title, color, position, archived, is_todo, is_collapsed, created_at, updated_at
-         FROM categories WHERE id = ?1",
-        params![&input.id],
-        row_to_category,
-    )
-    .map_err(|e| format!("fetch category error: {e}"))
+) -> AppResult<Category> {
+    state.lock()?.set_category_archived(input)
 }
 
 #[tauri::command]
 pub fn set_category_collapsed(
     state: tauri::State<'_, DbState>,
     input: SetCategoryCollapsedInput,
-) -> Result<Category, String> {
-    let conn = state
-        .conn
-        .lock()
-        .map_err(|_| "db mutex poisoned".to_string())?;
-    let ts = now_timestamp_ms();
-    let collapsed_int: i64 = if input.is_collapsed { 1 } else { 0 };
-
-    conn.execute(
-        "UPDATE categories SET is_collapsed = ?1, updated_at = ?2 WHERE id = ?3",
-        params![collapsed_int, ts, &input.id],
-    )
-    .map_err(|e| format!("set collapsed error: {e}"))?;
-
-    conn.query_row(
-        "SELECT id, emoji, title, color, position, archived, is_todo, is_collapsed, created_at, updated_at
-         FROM categories WHERE id = ?1",
-        params![&input.id],
-        row_to_category,
-    )
-    .map_err(|e| format!("fetch category error: {e}"))
+) -> AppResult<Category> {
+    state.lock()?.set_category_collapsed(input)
 }
 
 #[tauri::command]
-pub fn delete_category(state: tauri::State<'_, DbState>, id: String) -> Result<(), String> {
-    let conn = state
-        .conn
-        .lock()
-        .map_err(|_| "db mutex poisoned".to_string())?;
+pub fn set_category_sort(
+    state: tauri::State<'_, DbState>,
+    input: SetCategorySortInput,
+) -> AppResult<Category> {
+    state.lock()?.set_category_sort(input)
+}
 
-    conn.execute("DELETE FROM categories WHERE id = ?1", params![&id])
-        .map_err(|e| format!("delete category error: {e}"))?;
+#[tauri::command]
+pub fn set_category_auto_archive(
+    state: tauri::State<'_, DbState>,
+    input: SetCategoryAutoArchiveInput,
+) -> AppResult<Category> {
+    state.lock()?.set_category_auto_archive(input)
+}
 
-    Ok(())
+#[tauri::command]
+pub fn delete_category(state: tauri::State<'_, DbState>, id: String) -> AppResult<()> {
+    state.lock()?.delete_category(&id)
 }
 
 #[tauri::command]
 pub fn reorder_categories(
     state: tauri::State<'_, DbState>,
     input: ReorderCategoriesInput,
-) -> Result<(), String> {
-    let mut conn = state
-        .conn
-        .lock()
-        .map_err(|_| "db mutex poisoned".to_string())?;
-    let tx = conn
-        .transaction()
-        .map_err(|e| format!("tx begin error: {e}"))?;
-
-    let ts = now_timestamp_ms();
-    for (idx, id) in input.ordered_ids.iter().enumerate() {
-        tx.execute(
-            "UPDATE categories SET position = ?1, updated_at = ?2 WHERE id = ?3",
-            params![idx as i64, ts, id],
-        )
-        .map_err(|e| format!("reorder category error: {e}"))?;
-    }
+) -> AppResult<()> {
+    state.lock()?.reorder_categories(input)
+}
 
-    tx.commit().map_err(|e| format!("tx commit error: {e}"))?;
-    Ok(())
+#[tauri::command]
+pub fn reorder_memos(state: tauri::State<'_, DbState>, input: ReorderMemosInput) -> AppResult<()> {
+    state.lock()?.reorder_memos(input)
 }
 
+/// Same as `get_settings`; kept for existing callers.
 #[tauri::command]
-pub fn reorder_memos(
+pub fn get_app_settings(state: tauri::State<'_, DbState>) -> AppResult<AppSettings> {
+    state.lock()?.get_settings()
+}
+
+#[tauri::command]
+pub fn set_background_color(
     state: tauri::State<'_, DbState>,
-    input: ReorderMemosInput,
-) -> Result<(), String> {
-    let mut conn = state
-        .conn
-        .lock()
-        .map_err(|_| "db mutex poisoned".to_string())?;
-    let tx = conn
-        .transaction()
-        .map_err(|e| format!("tx begin error: {e}"))?;
+    input: SetBackgroundColorInput,
+) -> AppResult<()> {
+    state.lock()?.set_background_color(input)
+}
 
-    let ts = now_timestamp_ms();
-    for (idx, memo_id) in input.ordered_ids.iter().enumerate() {
-        tx.execute(
-            "UPDATE memos
-             SET position = ?1, updated_at = ?2
-             WHERE id = ?3 AND category_id = ?4",
-            params![idx as i64, ts, memo_id, &input.category_id],
-        )
-        .map_err(|e| format!("reorder memos error: {e}"))?;
+#[tauri::command]
+pub fn get_settings(state: tauri::State<'_, DbState>) -> AppResult<AppSettings> {
+    state.lock()?.get_settings()
+}
+
+#[tauri::command]
+pub fn update_settings(
+    state: tauri::State<'_, DbState>,
+    input: UpdateSettingsInput,
+) -> AppResult<AppSettings> {
+    state.lock()?.update_settings(input)
+}
+
+fn http_api_status(db: &DbState, api: &HttpApiState) -> AppResult<HttpApiStatus> {
+    let settings = db.lock()?.get_settings()?;
+    let slot = api.0.lock()?;
+    Ok(HttpApiStatus {
+        enabled: settings.http_api_enabled,
+        running: slot.server.is_some(),
+        url: slot
+            .server
+            .as_ref()
+            .map(|server| format!("http://{}", server.addr())),
+        port: settings.http_api_port,
+        token: settings.http_api_token,
+        error: slot.error.clone(),
+    })
+}
+
+#[tauri::command]
+pub fn get_http_api_status(
+    state: tauri::State<'_, DbState>,
+    api: tauri::State<'_, HttpApiState>,
+) -> AppResult<HttpApiStatus> {
+    http_api_status(&state, &api)
+}
+
+/// Turns the local HTTP API on or off, or moves it to another port. The
+/// setting is saved even if the server can't start; the returned status
+/// says why in `error`.
+#[tauri::command]
+pub fn set_http_api(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, DbState>,
+    api: tauri::State<'_, HttpApiState>,
+    input: SetHttpApiInput,
+) -> AppResult<HttpApiStatus> {
+    let mut values = serde_json::Map::new();
+    values.insert("http_api_enabled".to_string(), input.enabled.into());
+    if let Some(port) = input.port {
+        values.insert("http_api_port".to_string(), port.into());
     }
+    state
+        .lock()?
+        .update_settings(UpdateSettingsInput { values })?;
+    let _ = http_api::apply_settings(&app, &state, &api);
+    http_api_status(&state, &api)
+}
 
-    tx.commit().map_err(|e| format!("tx commit error: {e}"))?;
-    Ok(())
+/// Replaces the HTTP API token; the old one is refused from the next request.
+#[tauri::command]
+pub fn regenerate_http_api_token(
+    state: tauri::State<'_, DbState>,
+    api: tauri::State<'_, HttpApiState>,
+) -> AppResult<HttpApiStatus> {
+    let mut values = serde_json::Map::new();
+    values.insert(
+        "http_api_token".to_string(),
+        http_api::generate_token().into(),
+    );
+    state
+        .lock()?
+        .update_settings(UpdateSettingsInput { values })?;
+    http_api_status(&state, &api)
 }
 
 #[tauri::command]
-pub fn get_app_settings(state: tauri::State<'_, DbState>) -> Result<AppSettings, String> {
-    let conn = state
-        .conn
-        .lock()
-        .map_err(|_| "db mutex poisoned".to_string())?;
+pub fn export_settings(state: tauri::State<'_, DbState>) -> AppResult<SettingsFile> {
+    state.lock()?.export_settings()
+}
 
-    let bg: Option<String> = conn
-        .query_row(
-            "SELECT value FROM settings WHERE key = 'background_color'",
-            [],
-            |row| row.get(0),
-        )
-        .optional()
-        .map_err(|e| format!("get settings error: {e}"))?;
+#[tauri::command]
+pub fn import_settings(
+    state: tauri::State<'_, DbState>,
+    input: SettingsFile,
+) -> AppResult<AppSettings> {
+    state.lock()?.import_settings(input)
+}
 
-    Ok(AppSettings {
-        background_color: bg.unwrap_or_else(|| "".to_string()),
-    })
+#[tauri::command]
+pub fn create_memo(state: tauri::State<'_, DbState>, input: CreateMemoInput) -> AppResult<Memo> {
+    state.lock()?.create_memo(input)
 }
 
 #[tauri::command]
-pub fn set_background_color(
+pub fn update_memo(
     state: tauri::State<'_, DbState>,
-    input: SetBackgroundColorInput,
-) -> Result<(), String> {
-    let conn = state
-        .conn
-        .lock()
-        .map_err(|_| "db mutex poisoned".to_string())?;
-
-    conn.execute(
-        "INSERT INTO settings(key, value) VALUES('background_color', ?1)
-         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
-        params![&input.background_color],
-    )
-    .map_err(|e| format!("set settings error: {e}"))?;
-
-    Ok(())
-}
-
-#[tauri::command]
-pub fn create_memo(state: tauri::State<'_, DbState>, input: CreateMemoInput) -> Result<Memo, String> {
-    let mut conn = state
-        .conn
-        .lock()
-        .map_err(|_| "db mutex poisoned".to_string())?;
-    let tx = conn
-        .transaction()
-        .map_err(|e| format!("tx begin error: {e}"))?;
-
-    let id = Uuid::new_v4().to_string();
-    let ts = now_timestamp_ms();
-    let position = next_position(&tx, "memos", Some(("category_id", &input.category_id)))?;
-
-    tx.execute(
-        "INSERT INTO memos (id, category_id, emoji, title, color, date_ymd, content_md, todo_done, position, created_at, updated_at)
-         VALUES (
-          ?1, ?2, ?3, ?4, ?5,
-           COALESCE(NULLIF(?6, ''), strftime('%Y-%m-%d', ?9/1000, 'unixepoch', 'localtime')),
-          ?7, 0, ?8, ?9, ?10
-         )",
-        params![
-            &id,
-            &input.category_id,
-            input.emoji.as_deref().unwrap_or(""),
-            &input.title,
-            &input.color,
-            input.date_ymd.as_deref(),
-            &input.content_md,
-            position,
-            ts,
-            ts
-        ],
-    )
-    .map_err(|e| format!("insert memo error: {e}"))?;
-
-    let memo = tx
-        .query_row(
-            "SELECT id, category_id, emoji, title, color, date_ymd, content_md, todo_done, position, created_at, updated_at
-             FROM memos WHERE id = ?1",
-            params![&id],
-            row_to_memo,
-        )
-        .map_err(|e| format!("fetch memo error: {e}"))?;
-
-    tx.commit().map_err(|e| format!("tx commit error: {e}"))?;
-    Ok(memo)
-}
-
-#[tauri::command]
-pub fn update_memo(state: tauri::State<'_, DbState>, input: UpdateMemoInput) -> Result<Memo, String> {
-    let conn = state
-        .conn
-        .lock()
-        .map_err(|_| "db mutex poisoned".to_string())?;
-    let ts = now_timestamp_ms();
-
-    conn.execute(
-        "UPDATE memos
-         SET emoji = ?1, title = ?2, color = ?3, date_ymd = ?4, content_md = ?5, todo_done = ?6, updated_at = ?7
-         WHERE id = ?8",
-        params![
-            &input.emoji,
-            &input.title,
-            &input.color,
-            &input.date_ymd,
-            &input.content_md,
-            if input.todo_done { 1 } else { 0 },
-            ts,
-            &input.id
-        ],
-    )
-    .map_err(|e| format!("update memo error: {e}"))?;
-
-    conn.query_row(
-        "SELECT id, category_id, emoji, title, color, date_ymd, content_md, todo_done, position, created_at, updated_at
-         FROM memos WHERE id = ?1",
-        params![&input.id],
-        row_to_memo,
-    )
-    .map_err(|e| format!("fetch memo error: {e}"))
-}
-
-#[tauri::command]
-pub fn delete_memo(state: tauri::State<'_, DbState>, id: String) -> Result<(), String> {
-    let mut conn = state
-        .conn
-        .lock()
-        .map_err(|_| "db mutex poisoned".to_string())?;
-
-    let tx = conn
-        .transaction()
-        .map_err(|e| format!("tx begin error: {e}"))?;
-
-    // Compact positions in the category after delete.
-    if let Some((cat_id, pos)) = get_memo_category_and_position(&tx, &id)? {
-        tx.execute("DELETE FROM memos WHERE id = ?1", params![&id])
-            .map_err(|e| format!("delete memo error: {e}"))?;
-        tx.execute(
-            "UPDATE memos SET position = position - 1
-             WHERE category_id = ?1 AND position > ?2",
-            params![cat_id, pos],
-        )
-        .map_err(|e| format!("compact positions error: {e}"))?;
-    }
+    input: UpdateMemoInput,
+) -> AppResult<UpdateMemoResult> {
+    state.lock()?.update_memo(input)
+}
 
-    tx.commit().map_err(|e| format!("tx commit error: {e}"))?;
-    Ok(())
+#[tauri::command]
+pub fn delete_memo(state: tauri::State<'_, DbState>, id: String) -> AppResult<()> {
+    state.lock()?.delete_memo(&id)
 }
 
 #[tauri::command]
-pub fn move_memo(state: tauri::State<'_, DbState>, input: MoveMemoInput) -> Result<(), String> {
-    let mut conn = state
-        .conn
-        .lock()
-        .map_err(|_| "db mutex poisoned".to_string())?;
+pub fn move_memo(state: tauri::State<'_, DbState>, input: MoveMemoInput) -> AppResult<()> {
+    state.lock()?.move_memo(input)
+}
 
-    let tx = conn
-        .transaction()
-        .map_err(|e| format!("tx begin error: {e}"))?;
+#[tauri::command]
+pub fn move_memo_to(state: tauri::State<'_, DbState>, input: MoveMemoToInput) -> AppResult<Memo> {
+    state.lock()?.move_memo_to(input)
+}
 
-    let Some((from_category_id, from_pos)) = get_memo_category_and_position(&tx, &input.memo_id)? else {
-        return Err("memo not found".to_string());
-    };
+#[tauri::command]
+pub fn move_memos(
+    state: tauri::State<'_, DbState>,
+    input: MoveMemosInput,
+) -> AppResult<BulkReport> {
+    state.lock()?.move_memos(input)
+}
 
-    // Remove gap in source category.
-    tx.execute(
-        "UPDATE memos SET position = position - 1
-         WHERE category_id = ?1 AND position > ?2",
-        params![&from_category_id, from_pos],
-    )
-    .map_err(|e| format!("compact source positions error: {e}"))?;
+#[tauri::command]
+pub fn delete_memos(
+    state: tauri::State<'_, DbState>,
+    input: DeleteMemosInput,
+) -> AppResult<BulkReport> {
+    state.lock()?.delete_memos(input)
+}
 
-    // Append to target category.
-    let new_pos = next_position(&tx, "memos", Some(("category_id", &input.to_category_id)))?;
-    let ts = now_timestamp_ms();
+#[tauri::command]
+pub fn set_memos_todo_done(
+    state: tauri::State<'_, DbState>,
+    input: SetMemosTodoDoneInput,
+) -> AppResult<BulkReport> {
+    state.lock()?.set_memos_todo_done(input)
+}
 
-    tx.execute(
-        "UPDATE memos SET category_id = ?1, position = ?2, updated_at = ?3 WHERE id = ?4",
-        params![&input.to_category_id, new_pos, ts, &input.memo_id],
-    )
-    .map_err(|e| format!("move memo error: {e}"))?;
+#[tauri::command]
+pub fn set_memos_color(
+    state: tauri::State<'_, DbState>,
+    input: SetMemosColorInput,
+) -> AppResult<BulkReport> {
+    state.lock()?.set_memos_color(input)
+}
 
-    tx.commit().map_err(|e| format!("tx commit error: {e}"))?;
-    Ok(())
+#[tauri::command]
+pub fn set_memos_date(
+    state: tauri::State<'_, DbState>,
+    input: SetMemosDateInput,
+) -> AppResult<BulkReport> {
+    state.lock()?.set_memos_date(input)
 }
 
+#[tauri::command]
+pub fn set_memo_pinned(
+    state: tauri::State<'_, DbState>,
+    input: SetMemoPinnedInput,
+) -> AppResult<Memo> {
+    state.lock()?.set_memo_pinned(input)
+}
+
+#[tauri::command]
+pub fn set_memo_starred(
+    state: tauri::State<'_, DbState>,
+    input: SetMemoStarredInput,
+) -> AppResult<Memo> {
+    state.lock()?.set_memo_starred(input)
+}
 
+#[tauri::command]
+pub fn list_favorites(state: tauri::State<'_, DbState>) -> AppResult<Vec<Memo>> {
+    state.lock()?.list_favorites()
+}
+
+#[tauri::command]
+pub fn list_recently_completed(
+    state: tauri::State<'_, DbState>,
+    input: RecentlyCompletedInput,
+) -> AppResult<Vec<Memo>> {
+    state.lock()?.list_recently_completed(input)
+}
+
+#[tauri::command]
+pub fn set_memo_archived(
+    state: tauri::State<'_, DbState>,
+    input: SetMemoArchivedInput,
+) -> AppResult<Memo> {
+    state.lock()?.set_memo_archived(input)
+}
+
+#[tauri::command]
+pub fn list_archive(
+    state: tauri::State<'_, DbState>,
+    input: ListArchiveInput,
+) -> AppResult<Archive> {
+    state.lock()?.list_archive(input)
+}
+
+#[tauri::command]
+pub fn query_memos(
+    state: tauri::State<'_, DbState>,
+    input: QueryMemosInput,
+) -> AppResult<Vec<Memo>> {
+    state.lock()?.query_memos(input)
+}
+
+#[tauri::command]
+pub fn duplicate_memo(
+    state: tauri::State<'_, DbState>,
+    input: DuplicateMemoInput,
+) -> AppResult<Memo> {
+    state.lock()?.duplicate_memo(input)
+}
+
+#[tauri::command]
+pub fn duplicate_category(
+    state: tauri::State<'_, DbState>,
+    input: DuplicateCategoryInput,
+) -> AppResult<CategoryWithMemos> {
+    state.lock()?.duplicate_category(input)
+}
+
+#[tauri::command]
+pub fn list_templates(state: tauri::State<'_, DbState>) -> AppResult<Vec<Template>> {
+    state.lock()?.list_templates()
+}
+
+#[tauri::command]
+pub fn save_memo_as_template(
+    state: tauri::State<'_, DbState>,
+    input: SaveMemoAsTemplateInput,
+) -> AppResult<Template> {
+    state.lock()?.save_memo_as_template(input)
+}
+
+#[tauri::command]
+pub fn delete_template(state: tauri::State<'_, DbState>, id: String) -> AppResult<()> {
+    state.lock()?.delete_template(&id)
+}
+
+#[tauri::command]
+pub fn create_memo_from_template(
+    state: tauri::State<'_, DbState>,
+    input: CreateMemoFromTemplateInput,
+) -> AppResult<Memo> {
+    state.lock()?.create_memo_from_template(input)
+}
+
+#[tauri::command]
+pub fn set_category_default_template(
+    state: tauri::State<'_, DbState>,
+    input: SetCategoryDefaultTemplateInput,
+) -> AppResult<Category> {
+    state.lock()?.set_category_default_template(input)
+}
+
+#[tauri::command]
+pub fn list_smart_categories(state: tauri::State<'_, DbState>) -> AppResult<Vec<SmartCategory>> {
+    state.lock()?.list_smart_categories()
+}
+
+#[tauri::command]
+pub fn create_smart_category(
+    state: tauri::State<'_, DbState>,
+    input: CreateSmartCategoryInput,
+) -> AppResult<SmartCategory> {
+    state.lock()?.create_smart_category(input)
+}
+
+#[tauri::command]
+pub fn update_smart_category(
+    state: tauri::State<'_, DbState>,
+    input: UpdateSmartCategoryInput,
+) -> AppResult<SmartCategory> {
+    state.lock()?.update_smart_category(input)
+}
+
+#[tauri::command]
+pub fn delete_smart_category(state: tauri::State<'_, DbState>, id: String) -> AppResult<()> {
+    state.lock()?.delete_smart_category(&id)
+}
+
+#[tauri::command]
+pub fn reorder_smart_categories(
+    state: tauri::State<'_, DbState>,
+    input: ReorderCategoriesInput,
+) -> AppResult<()> {
+    state.lock()?.reorder_smart_categories(input)
+}
+
+#[tauri::command]
+pub fn open_journal_entry(
+    state: tauri::State<'_, DbState>,
+    input: OpenJournalEntryInput,
+) -> AppResult<Memo> {
+    state.lock()?.open_journal_entry(input)
+}
+
+#[tauri::command]
+pub fn list_memos_in_range(
+    state: tauri::State<'_, DbState>,
+    input: DateRangeInput,
+) -> AppResult<Vec<Memo>> {
+    state.lock()?.list_memos_in_range(input)
+}
+
+#[tauri::command]
+pub fn count_memos_by_day(
+    state: tauri::State<'_, DbState>,
+    input: MonthInput,
+) -> AppResult<Vec<DayCount>> {
+    state.lock()?.count_memos_by_day(input)
+}
+
+#[tauri::command]
+pub fn count_todos_by_day(
+    state: tauri::State<'_, DbState>,
+    input: DateRangeInput,
+) -> AppResult<Vec<TodoDayCount>> {
+    state.lock()?.count_todos_by_day(input)
+}
+
+#[tauri::command]
+pub fn get_statistics(
+    state: tauri::State<'_, DbState>,
+    input: StatisticsInput,
+) -> AppResult<Statistics> {
+    state.lock()?.get_statistics(input)
+}
+
+#[tauri::command]
+pub fn check_database(state: tauri::State<'_, DbState>) -> AppResult<IntegrityReport> {
+    state.lock()?.check_database()
+}
+
+#[tauri::command]
+pub fn repair_database(state: tauri::State<'_, DbState>) -> AppResult<RepairReport> {
+    state.lock()?.repair_database()
+}
@@ -1,10 +1,33 @@
+use crate::error::{AppError, AppResult};
+use crate::http_api::{ChangeEvent, DATA_CHANGED_EVENT};
+use crate::migrations;
+use crate::models::MemoRevision;
+use crate::rank;
+use crate::smart;
+use crate::stats;
+use crate::store::{archive_completed, Store};
+use rusqlite::functions::FunctionFlags;
 use rusqlite::{params, Connection, OptionalExtension};
-use std::path::PathBuf;
-use std::sync::Mutex;
-use tauri::Manager;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+pub const DB_FILE_NAME: &str = "ideanode.sqlite3";
+/// `identifier` in `tauri.conf.json`.
+const APP_IDENTIFIER: &str = "com.woody.ideanode";
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct DbState {
-    pub conn: Mutex<Connection>,
+    pub store: Mutex<Store>,
+    /// The database file, for servers that open their own connection.
+    pub path: PathBuf,
+}
+
+impl DbState {
+    pub fn lock(&self) -> AppResult<MutexGuard<'_, Store>> {
+        Ok(self.store.lock()?)
+    }
 }
 
 fn now_ms() -> i64 {
@@ -15,171 +38,197 @@ fn now_ms() -> i64 {
         .as_millis() as i64
 }
 
-pub fn init_db(app: &tauri::AppHandle) -> Result<DbState, String> {
+pub fn init_db(app: &tauri::AppHandle) -> AppResult<DbState> {
     let app_data_dir = app
         .path()
         .app_data_dir()
-        .map_err(|e| format!("app_data_dir error: {e}"))?;
+        .map_err(|e| AppError::Internal(format!("app_data_dir error: {e}")))?;
 
-    std::fs::create_dir_all(&app_data_dir)
-        .map_err(|e| format!("create_dir_all error: {e}"))?;
+    std::fs::create_dir_all(&app_data_dir)?;
 
-    let db_path: PathBuf = app_data_dir.join("ideanode.sqlite3");
-    let conn = Connection::open(db_path).map_err(|e| format!("db open error: {e}"))?;
-
-    // Important: ensure FK constraints are enforced.
-    conn.execute_batch("PRAGMA foreign_keys = ON;")
-        .map_err(|e| format!("pragma error: {e}"))?;
-
-    migrate(&conn)?;
+    let db_path: PathBuf = app_data_dir.join(DB_FILE_NAME);
+    let store = Store::open(&db_path)?;
 
     Ok(DbState {
-        conn: Mutex::new(conn),
+        store: Mutex::new(store),
+        path: db_path,
     })
 }
 
-fn migrate(conn: &Connection) -> Result<(), String> {
-    let mut current_version: i64 = conn
-        .query_row("PRAGMA user_version;", [], |row| row.get(0))
-        .map_err(|e| format!("read user_version error: {e}"))?;
-
-    loop {
-        match current_version {
-            0 => {
-                conn.execute_batch(
-                    r#"
-                    BEGIN;
-                    CREATE TABLE IF NOT EXISTS categories (
-                      id TEXT PRIMARY KEY,
-                      emoji TEXT NOT NULL DEFAULT '',
-                      title TEXT NOT NULL,
-                      color TEXT NOT NULL,
-                      position INTEGER NOT NULL,
-                      archived INTEGER NOT NULL DEFAULT 0,
-                      is_todo INTEGER NOT NULL DEFAULT 0,
-                      is_collapsed INTEGER NOT NULL DEFAULT 0,
-                      created_at INTEGER NOT NULL,
-                      updated_at INTEGER NOT NULL
-                    );
-
-                    CREATE TABLE IF NOT EXISTS memos (
-                      id TEXT PRIMARY KEY,
-                      category_id TEXT NOT NULL,
-                      emoji TEXT NOT NULL DEFAULT '',
-                      title TEXT NOT NULL,
-                      color TEXT NOT NULL,
-                      date_ymd TEXT NOT NULL,
-                      content_md TEXT NOT NULL,
-                      todo_done INTEGER NOT NULL DEFAULT 0,
-                      position INTEGER NOT NULL,
-                      created_at INTEGER NOT NULL,
-                      updated_at INTEGER NOT NULL,
-                      FOREIGN KEY(category_id) REFERENCES categories(id) ON DELETE CASCADE
-                    );
-
-                    CREATE TABLE IF NOT EXISTS settings (
-                      key TEXT PRIMARY KEY,
-                      value TEXT NOT NULL
-                    );
-
-                    CREATE INDEX IF NOT EXISTS idx_categories_position ON categories(position);
-                    CREATE INDEX IF NOT EXISTS idx_memos_category_position ON memos(category_id, position);
-
-                    PRAGMA user_version = 5;
-                    COMMIT;
-                    "#,
-                )
-                .map_err(|e| format!("migration v0->v5 error: {e}"))?;
-                current_version = 5;
-            }
-            1 => {
-                conn.execute_batch(
-                    r#"
-                    BEGIN;
-                    ALTER TABLE memos ADD COLUMN date_ymd TEXT NOT NULL DEFAULT '';
-                    UPDATE memos
-                    SET date_ymd = strftime('%Y-%m-%d', created_at/1000, 'unixepoch', 'localtime')
-                    WHERE date_ymd = '';
-                    PRAGMA user_version = 2;
-                    COMMIT;
-                    "#,
-                )
-                .map_err(|e| format!("migration v1->v2 error: {e}"))?;
-                current_version = 2;
-            }
-            2 => {
-                conn.execute_batch(
-                    r#"
-                    BEGIN;
-                    ALTER TABLE categories ADD COLUMN emoji TEXT NOT NULL DEFAULT '';
-                    CREATE TABLE IF NOT EXISTS settings (
-                      key TEXT PRIMARY KEY,
-                      value TEXT NOT NULL
-                    );
-                    PRAGMA user_version = 3;
-                    COMMIT;
-                    "#,
-                )
-                .map_err(|e| format!("migration v2->v3 error: {e}"))?;
-                current_version = 3;
-            }
-            3 => {
-                conn.execute_batch(
-                    r#"
-                    BEGIN;
-                    ALTER TABLE memos ADD COLUMN emoji TEXT NOT NULL DEFAULT '';
-                    PRAGMA user_version = 4;
-                    COMMIT;
-                    "#,
-                )
-                .map_err(|e| format!("migration v3->v4 error: {e}"))?;
-                current_version = 4;
-            }
-            4 => {
-                conn.execute_batch(
-                    r#"
-                    BEGIN;
-                    ALTER TABLE categories ADD COLUMN archived INTEGER NOT NULL DEFAULT 0;
-                    ALTER TABLE categories ADD COLUMN is_todo INTEGER NOT NULL DEFAULT 0;
-                    ALTER TABLE memos ADD COLUMN todo_done INTEGER NOT NULL DEFAULT 0;
-                    PRAGMA user_version = 5;
-                    COMMIT;
-                    "#,
-                )
-                .map_err(|e| format!("migration v4->v5 error: {e}"))?;
-                current_version = 5;
-            }
-            _ => break,
-        }
-    }
+/// Where the app keeps its database: Tauri's app data directory, which is
+/// the platform data directory plus the bundle identifier. For tools that
+/// open the database without Tauri.
+pub fn default_db_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(APP_IDENTIFIER).join(DB_FILE_NAME))
+}
 
+/// Opens the database file and brings it up to date. The app and the
+/// command-line tool may have it open at the same time, so writers wait for
+/// each other instead of failing right away.
+pub fn open_db(path: &Path) -> AppResult<Connection> {
+    let conn = Connection::open(path)?;
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    prepare(&conn)?;
+    Ok(conn)
+}
+
+/// Sets up a freshly opened connection: pragmas, SQL functions, migrations.
+pub fn prepare(conn: &Connection) -> AppResult<()> {
+    // Important: ensure FK constraints are enforced.
+    conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+    register_functions(conn)?;
+
+    migrations::run(conn)?;
+    rebalance_ranks(conn)?;
+    archive_completed(conn, now_ms())?;
     Ok(())
 }
 
-pub fn next_position(conn: &Connection, table: &str, where_clause: Option<(&str, &str)>) -> Result<i64, String> {
-    let sql = match where_clause {
-        Some((col, _)) => format!("SELECT COALESCE(MAX(position), -1) + 1 FROM {table} WHERE {col} = ?1"),
-        None => format!("SELECT COALESCE(MAX(position), -1) + 1 FROM {table}"),
+/// SQL functions used by search queries (`query::compile`). SQLite's own
+/// `lower()` and `LIKE` only fold ASCII, and bodies are HTML.
+/// - `fold(text)`: lowercased text
+/// - `memo_text(title, content_md)`: lowercased title and visible body text
+/// - `memo_has_tag(title, content_md, tag)`: whether the memo has `#tag`
+fn register_functions(conn: &Connection) -> AppResult<()> {
+    let flags = FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC;
+    let memo_text = |title: &str, content_md: &str| {
+        format!("{title}\n{}", stats::plain_text(content_md)).to_lowercase()
     };
 
-    let pos: i64 = match where_clause {
-        Some((_col, val)) => conn
-            .query_row(&sql, params![val], |row| row.get(0))
-            .map_err(|e| format!("next_position error: {e}"))?,
+    conn.create_scalar_function("fold", 1, flags, |ctx| {
+        Ok(ctx.get::<String>(0)?.to_lowercase())
+    })?;
+    conn.create_scalar_function("memo_text", 2, flags, move |ctx| {
+        Ok(memo_text(&ctx.get::<String>(0)?, &ctx.get::<String>(1)?))
+    })?;
+    conn.create_scalar_function("memo_has_tag", 3, flags, move |ctx| {
+        let text = memo_text(&ctx.get::<String>(0)?, &ctx.get::<String>(1)?);
+        Ok(smart::tags(&text).contains(&ctx.get::<String>(2)?))
+    })?;
+    Ok(())
+}
+
+/// Gives every row of one list (a table, optionally filtered by one column)
+/// evenly spaced ranks in `order_by` order. Returns the number of rows.
+pub fn write_spread_ranks(
+    conn: &Connection,
+    table: &str,
+    where_clause: Option<(&str, &str)>,
+    order_by: &str,
+) -> AppResult<usize> {
+    let ids: Vec<String> = match where_clause {
+        Some((col, val)) => conn
+            .prepare(&format!(
+                "SELECT id FROM {table} WHERE {col} = ?1 ORDER BY {order_by}"
+            ))?
+            .query_map(params![val], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?,
         None => conn
-            .query_row(&sql, [], |row| row.get(0))
-            .map_err(|e| format!("next_position error: {e}"))?,
+            .prepare(&format!("SELECT id FROM {table} ORDER BY {order_by}"))?
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?,
     };
 
-    Ok(pos)
+    let sql = format!("UPDATE {table} SET rank = ?1 WHERE id = ?2");
+    for (id, rank) in ids.iter().zip(rank::spread(ids.len())) {
+        conn.execute(&sql, params![rank, id])?;
+    }
+    Ok(ids.len())
+}
+
+/// Rank that sorts after every row of the list.
+pub fn next_rank(
+    conn: &Connection,
+    table: &str,
+    where_clause: Option<(&str, &str)>,
+) -> AppResult<String> {
+    let last: Option<String> = match where_clause {
+        Some((col, val)) => conn.query_row(
+            &format!("SELECT MAX(rank) FROM {table} WHERE {col} = ?1"),
+            params![val],
+            |row| row.get(0),
+        )?,
+        None => conn.query_row(&format!("SELECT MAX(rank) FROM {table}"), [], |row| {
+            row.get(0)
+        })?,
+    };
+
+    Ok(rank::rank_between(last.as_deref(), None))
+}
+
+/// How often the background maintenance looks for over-long ranks and done
+/// memos to auto-archive.
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+/// Rewrites any list whose ranks have grown past `rank::REBALANCE_LEN` with
+/// short, evenly spaced ones. Order and `updated_at` are left untouched.
+/// Returns the number of rows rewritten.
+pub fn rebalance_ranks(conn: &Connection) -> AppResult<usize> {
+    let tx = conn.unchecked_transaction()?;
+    let max_len = rank::REBALANCE_LEN as i64;
+    let mut rewritten = 0;
+
+    let longest: Option<i64> =
+        tx.query_row("SELECT MAX(LENGTH(rank)) FROM categories", [], |row| {
+            row.get(0)
+        })?;
+    if longest.unwrap_or(0) > max_len {
+        rewritten += write_spread_ranks(&tx, "categories", None, "rank ASC, id ASC")?;
+    }
+
+    let longest: Option<i64> = tx.query_row(
+        "SELECT MAX(LENGTH(rank)) FROM smart_categories",
+        [],
+        |row| row.get(0),
+    )?;
+    if longest.unwrap_or(0) > max_len {
+        rewritten += write_spread_ranks(&tx, "smart_categories", None, "rank ASC, id ASC")?;
+    }
+
+    let category_ids: Vec<String> = tx
+        .prepare(
+            "SELECT category_id FROM memos GROUP BY category_id HAVING MAX(LENGTH(rank)) > ?1",
+        )?
+        .query_map(params![max_len], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    for category_id in &category_ids {
+        rewritten += write_spread_ranks(
+            &tx,
+            "memos",
+            Some(("category_id", category_id)),
+            "rank ASC, id ASC",
+        )?;
+    }
+
+    tx.commit()?;
+    Ok(rewritten)
+}
+
+/// Runs `rebalance_ranks` and `archive_completed` every `MAINTENANCE_INTERVAL`
+/// for the app's lifetime. Windows are told to refresh when memos were
+/// archived, since that changes the board.
+pub fn spawn_maintenance(app: tauri::AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(MAINTENANCE_INTERVAL);
+        let state = app.state::<DbState>();
+        let archived = match state.lock() {
+            Ok(store) => {
+                let _ = rebalance_ranks(store.conn());
+                archive_completed(store.conn(), now_ms()).unwrap_or(0)
+            }
+            Err(_) => 0,
+        };
+        if archived > 0 {
+            let _ = app.emit(DATA_CHANGED_EVENT, ChangeEvent::from_app());
+        }
+    });
 }
 
 #[allow(dead_code)]
-pub fn touch_updated_at(conn: &Connection, table: &str, id: &str) -> Result<(), String> {
+pub fn touch_updated_at(conn: &Connection, table: &str, id: &str) -> AppResult<()> {
     let ts = now_ms();
     let sql = format!("UPDATE {table} SET updated_at = ?1 WHERE id = ?2");
-    conn.execute(&sql, params![ts, id])
-        .map_err(|e| format!("touch_updated_at error: {e}"))?;
+    conn.execute(&sql, params![ts, id])?;
     Ok(())
 }
 
@@ -187,17 +236,77 @@ pub fn now_timestamp_ms() -> i64 {
     now_ms()
 }
 
-pub fn get_memo_category_and_position(
-    conn: &Connection,
-    memo_id: &str,
-) -> Result<Option<(String, i64)>, String> {
-    conn.query_row(
-        "SELECT category_id, position FROM memos WHERE id = ?1",
-        params![memo_id],
-        |row| Ok((row.get(0)?, row.get(1)?)),
-    )
-    .optional()
-    .map_err(|e| format!("get memo meta error: {e}"))
+/// Today's date as `YYYY-MM-DD` in local time, as SQLite computes it for
+/// memos created without a date.
+pub fn today_ymd(conn: &Connection) -> AppResult<String> {
+    Ok(conn.query_row(
+        "SELECT strftime('%Y-%m-%d', 'now', 'localtime')",
+        [],
+        |row| row.get(0),
+    )?)
+}
+
+pub fn get_memo_category(conn: &Connection, memo_id: &str) -> AppResult<Option<String>> {
+    Ok(conn
+        .query_row(
+            "SELECT category_id FROM memos WHERE id = ?1",
+            params![memo_id],
+            |row| row.get(0),
+        )
+        .optional()?)
 }
 
+/// How many past versions of each memo are kept as merge bases.
+const MEMO_REVISIONS_KEPT: i64 = 20;
+
+/// Snapshots the memo's current fields under its current version, notes the
+/// local day in `memo_activity` if this is an edit rather than the creation,
+/// and drops revisions older than `MEMO_REVISIONS_KEPT`. The statistics read
+/// edit days from `memo_activity`, so pruning doesn't change them.
+pub fn record_memo_revision(conn: &Connection, memo_id: &str) -> AppResult<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO memo_revisions (memo_id, version, emoji, title, color, date_ymd, content_md, todo_done, created_at)
+         SELECT id, version, emoji, title, color, date_ymd, content_md, todo_done, updated_at
+         FROM memos WHERE id = ?1",
+        params![memo_id],
+    )?;
+
+    conn.execute(
+        "INSERT OR IGNORE INTO memo_activity (memo_id, day)
+         SELECT id, strftime('%Y-%m-%d', updated_at / 1000, 'unixepoch', 'localtime')
+         FROM memos WHERE id = ?1 AND version > 1",
+        params![memo_id],
+    )?;
+
+    conn.execute(
+        "DELETE FROM memo_revisions
+         WHERE memo_id = ?1 AND version <= (SELECT version FROM memos WHERE id = ?1) - ?2",
+        params![memo_id, MEMO_REVISIONS_KEPT],
+    )?;
+
+    Ok(())
+}
 
+pub fn get_memo_revision(
+    conn: &Connection,
+    memo_id: &str,
+    version: i64,
+) -> AppResult<Option<MemoRevision>> {
+    Ok(conn
+        .query_row(
+            "SELECT emoji, title, color, date_ymd, content_md, todo_done
+             FROM memo_revisions WHERE memo_id = ?1 AND version = ?2",
+            params![memo_id, version],
+            |row| {
+                Ok(MemoRevision {
+                    emoji: row.get(0)?,
+                    title: row.get(1)?,
+                    color: row.get(2)?,
+                    date_ymd: row.get(3)?,
+                    content_md: row.get(4)?,
+                    todo_done: row.get::<_, i64>(5)? != 0,
+                })
+            },
+        )
+        .optional()?)
+}
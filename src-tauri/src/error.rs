@@ -0,0 +1,271 @@
+use rusqlite::ErrorCode;
+use serde::ser::{Serialize, Serializer};
+
+pub type AppResult<T> = Result<T, AppError>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Entity {
+    Category,
+    Memo,
+    Setting,
+    Template,
+    SmartCategory,
+}
+
+impl std::fmt::Display for Entity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Entity::Category => "category",
+            Entity::Memo => "memo",
+            Entity::Setting => "setting",
+            Entity::Template => "template",
+            Entity::SmartCategory => "smart_category",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConstraintKind {
+    ForeignKey,
+    Unique,
+    NotNull,
+    Check,
+    Other,
+}
+
+impl std::fmt::Display for ConstraintKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ConstraintKind::ForeignKey => "foreign_key",
+            ConstraintKind::Unique => "unique",
+            ConstraintKind::NotNull => "not_null",
+            ConstraintKind::Check => "check",
+            ConstraintKind::Other => "other",
+        })
+    }
+}
+
+/// Range `start..end` within an input string, in UTF-16 code units so the
+/// frontend can slice the same string with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// One rejected input field, e.g. `{ field: "date_ymd", code: "invalid_date" }`.
+/// `span` points into the field's text when the problem is in part of it,
+/// as with search queries.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub code: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub span: Option<Span>,
+}
+
+/// Error returned by every command. Serialized to the frontend as
+/// `{ code, entity, ids, reason, fields, message, detail }`, where `code` is stable,
+/// `message` is the user-facing (Korean) text and `detail` is for logs.
+#[derive(Debug, thiserror::Error)]
+pub enum AppError {
+    #[error("{entity} not found: {id}")]
+    NotFound { entity: Entity, id: String },
+
+    #[error("invalid input: {}", .0.iter().map(|f| f.field.as_str()).collect::<Vec<_>>().join(", "))]
+    Validation(Vec<FieldError>),
+
+    /// The request is well-formed but the current data doesn't allow it;
+    /// `reason` is a stable sub-code such as `category_archived`.
+    #[error("precondition failed ({reason}): {}", ids.join(", "))]
+    PreconditionFailed {
+        entity: Entity,
+        ids: Vec<String>,
+        reason: &'static str,
+    },
+
+    #[error("{kind} constraint violation: {detail}")]
+    Constraint {
+        kind: ConstraintKind,
+        detail: String,
+    },
+
+    /// The database file can't be used by this build; `reason` is
+    /// `newer_version`, `migration_changed` or `schema_mismatch`.
+    #[error("schema error ({reason}): {detail}")]
+    Schema {
+        reason: &'static str,
+        detail: String,
+    },
+
+    #[error("database is locked: {0}")]
+    Locked(String),
+
+    #[error("database error: {0}")]
+    Database(rusqlite::Error),
+
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl AppError {
+    pub fn not_found(entity: Entity, id: impl Into<String>) -> Self {
+        AppError::NotFound {
+            entity,
+            id: id.into(),
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::NotFound { .. } => "not_found",
+            AppError::Validation(_) => "validation",
+            AppError::PreconditionFailed { .. } => "precondition_failed",
+            AppError::Constraint { .. } => "constraint_violation",
+            AppError::Schema { .. } => "schema",
+            AppError::Locked(_) => "locked",
+            AppError::Database(_) => "database",
+            AppError::Io(_) => "io",
+            AppError::Internal(_) => "internal",
+        }
+    }
+
+    fn entity(&self) -> Option<Entity> {
+        match self {
+            AppError::NotFound { entity, .. } => Some(*entity),
+            AppError::PreconditionFailed { entity, .. } => Some(*entity),
+            _ => None,
+        }
+    }
+
+    fn ids(&self) -> Vec<String> {
+        match self {
+            AppError::NotFound { id, .. } => vec![id.clone()],
+            AppError::PreconditionFailed { ids, .. } => ids.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// User-facing message in the UI language.
+    pub fn localized_message(&self) -> String {
+        match self {
+            AppError::NotFound { entity, .. } => match entity {
+                Entity::Category => "카테고리를 찾을 수 없습니다.",
+                Entity::Memo => "메모를 찾을 수 없습니다.",
+                Entity::Setting => "설정을 찾을 수 없습니다.",
+                Entity::Template => "템플릿을 찾을 수 없습니다.",
+                Entity::SmartCategory => "스마트 카테고리를 찾을 수 없습니다.",
+            }
+            .to_string(),
+            AppError::Validation(fields) => match fields.as_slice() {
+                [only] => only.message.clone(),
+                _ => "입력값을 확인해 주세요.".to_string(),
+            },
+            AppError::PreconditionFailed { reason, .. } => match *reason {
+                "category_archived" => "보관된 카테고리에는 메모를 추가하거나 옮길 수 없습니다.",
+                "category_sorted" => "정렬 기준이 지정된 카테고리는 직접 순서를 바꿀 수 없습니다.",
+                "journal_ordered_by_date" => "일지 카테고리의 메모는 날짜순으로 정렬됩니다.",
+                "not_a_journal" => "일지 카테고리가 아닙니다.",
+                "port_in_use" => "포트가 이미 사용 중입니다. 다른 포트를 지정해 주세요.",
+                "not_a_permutation" => {
+                    "정렬 목록이 현재 항목과 맞지 않습니다. 새로고침 후 다시 시도해 주세요."
+                }
+                _ => "현재 상태에서는 요청을 처리할 수 없습니다.",
+            }
+            .to_string(),
+            AppError::Constraint { kind, .. } => match kind {
+                ConstraintKind::ForeignKey => "연결된 항목이 없어 저장할 수 없습니다.",
+                ConstraintKind::Unique => "이미 존재하는 항목입니다.",
+                ConstraintKind::NotNull => "필수 값이 비어 있습니다.",
+                ConstraintKind::Check => "허용되지 않는 값입니다.",
+                ConstraintKind::Other => "데이터 제약 조건을 위반했습니다.",
+            }
+            .to_string(),
+            AppError::Schema { reason, .. } => match *reason {
+                "newer_version" => {
+                    "더 새로운 버전의 앱에서 사용한 데이터베이스입니다. 앱을 업데이트해 주세요."
+                }
+                "migration_changed" => "데이터베이스 변경 기록이 현재 앱과 맞지 않습니다.",
+                _ => "데이터베이스 구조가 예상과 다릅니다. 업데이트 전 백업은 데이터 폴더에 있습니다.",
+            }
+            .to_string(),
+            AppError::Locked(_) => {
+                "데이터베이스가 사용 중입니다. 잠시 후 다시 시도해 주세요.".to_string()
+            }
+            AppError::Database(_) => "데이터베이스 오류가 발생했습니다.".to_string(),
+            AppError::Io(_) => "파일을 읽거나 쓰지 못했습니다.".to_string(),
+            AppError::Internal(_) => "알 수 없는 오류가 발생했습니다.".to_string(),
+        }
+    }
+}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(err: rusqlite::Error) -> Self {
+        if let rusqlite::Error::SqliteFailure(ffi_err, msg) = &err {
+            let detail = msg.clone().unwrap_or_else(|| ffi_err.to_string());
+            match ffi_err.code {
+                ErrorCode::ConstraintViolation => {
+                    let kind = match ffi_err.extended_code {
+                        rusqlite::ffi::SQLITE_CONSTRAINT_FOREIGNKEY => ConstraintKind::ForeignKey,
+                        rusqlite::ffi::SQLITE_CONSTRAINT_UNIQUE
+                        | rusqlite::ffi::SQLITE_CONSTRAINT_PRIMARYKEY => ConstraintKind::Unique,
+                        rusqlite::ffi::SQLITE_CONSTRAINT_NOTNULL => ConstraintKind::NotNull,
+                        rusqlite::ffi::SQLITE_CONSTRAINT_CHECK => ConstraintKind::Check,
+                        _ => ConstraintKind::Other,
+                    };
+                    return AppError::Constraint { kind, detail };
+                }
+                ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked => {
+                    return AppError::Locked(detail);
+                }
+                _ => {}
+            }
+        }
+        AppError::Database(err)
+    }
+}
+
+impl<T> From<std::sync::PoisonError<T>> for AppError {
+    fn from(_: std::sync::PoisonError<T>) -> Self {
+        AppError::Internal("db mutex poisoned".to_string())
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(serde::Serialize)]
+        struct Payload<'a> {
+            code: &'static str,
+            entity: Option<Entity>,
+            ids: Vec<String>,
+            reason: Option<&'static str>,
+            fields: &'a [FieldError],
+            message: String,
+            detail: String,
+        }
+
+        Payload {
+            code: self.code(),
+            entity: self.entity(),
+            ids: self.ids(),
+            reason: match self {
+                AppError::PreconditionFailed { reason, .. } => Some(reason),
+                AppError::Schema { reason, .. } => Some(reason),
+                _ => None,
+            },
+            fields: match self {
+                AppError::Validation(fields) => fields,
+                _ => &[],
+            },
+            message: self.localized_message(),
+            detail: self.to_string(),
+        }
+        .serialize(serializer)
+    }
+}
@@ -0,0 +1,736 @@
+// Optional local HTTP API for editor plugins, launchers and scripts.
+//
+// The server listens on 127.0.0.1 only and every request needs
+// `Authorization: Bearer <token>` with the `http_api_token` setting, read per
+// request so a regenerated token applies at once. Bodies and responses are
+// the same JSON as the Tauri commands; errors are the `AppError` payload.
+//
+//   GET  /api/categories           list_categories_with_memos
+//   POST /api/categories           create_category
+//   PUT  /api/categories/{id}      update_category
+//   POST /api/memos                create_memo
+//   GET  /api/memos/{id}           the memo
+//   PUT  /api/memos/{id}           update_memo
+//   GET  /api/search?q=..&limit=N  query_memos
+//   GET  /api/events               server-sent events, `change` per write
+//
+// It has its own connection to the database file, so a slow client never
+// holds the app's lock. HTTP/1.1 with one request per connection is enough
+// for local tools; each connection gets a thread, and at most
+// `MAX_CONNECTIONS` are served at once while the rest wait to be accepted.
+
+use crate::db::DbState;
+use crate::error::{AppError, AppResult, Entity, FieldError};
+use crate::models::{
+    CreateCategoryInput, CreateMemoInput, QueryMemosInput, UpdateCategoryInput,
+    UpdateCategoryResult, UpdateMemoInput, UpdateMemoResult, UpdateSettingsInput,
+};
+use crate::settings;
+use crate::store::Store;
+use serde::Serialize;
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tauri::{Emitter, Listener, Manager};
+
+/// Event the windows emit after changing data, and listen to for refreshes.
+pub const DATA_CHANGED_EVENT: &str = "ideanode:data_changed";
+
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+/// Memo bodies may be up to a million characters of UTF-8.
+const MAX_BODY_BYTES: usize = 8 * 1024 * 1024;
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+/// Connections served at once, event streams included. Further ones wait in
+/// the listen backlog until one closes.
+const MAX_CONNECTIONS: usize = 32;
+/// Comment line sent to idle event streams, which also notices clients that
+/// went away.
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// A change to the data, as sent on `/api/events`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangeEvent {
+    /// `app` for edits made in the app, `http` for API writes.
+    pub source: &'static str,
+    /// The API write, e.g. `create_memo`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+}
+
+impl ChangeEvent {
+    pub fn from_app() -> Self {
+        ChangeEvent {
+            source: "app",
+            action: None,
+            id: None,
+        }
+    }
+}
+
+/// Called after each API write, so the app can refresh.
+pub type OnChange = Arc<dyn Fn(&ChangeEvent) + Send + Sync>;
+
+#[derive(Default)]
+struct Subscribers(Mutex<Vec<Sender<String>>>);
+
+impl Subscribers {
+    fn subscribe(&self) -> Receiver<String> {
+        let (tx, rx) = mpsc::channel();
+        if let Ok(mut subscribers) = self.0.lock() {
+            subscribers.push(tx);
+        }
+        rx
+    }
+
+    fn publish(&self, event: &ChangeEvent) {
+        let Ok(data) = serde_json::to_string(event) else {
+            return;
+        };
+        if let Ok(mut subscribers) = self.0.lock() {
+            subscribers.retain(|tx| tx.send(data.clone()).is_ok());
+        }
+    }
+
+    /// Ends every stream.
+    fn close(&self) {
+        if let Ok(mut subscribers) = self.0.lock() {
+            subscribers.clear();
+        }
+    }
+}
+
+struct Shared {
+    db: DbState,
+    port: u16,
+    subscribers: Subscribers,
+    on_change: OnChange,
+    stopped: AtomicBool,
+    /// Connections being served, up to `MAX_CONNECTIONS`.
+    connections: Mutex<usize>,
+    connection_closed: Condvar,
+}
+
+impl Shared {
+    /// Waits for a free connection slot. `None` once the server is stopping.
+    fn wait_for_slot(self: &Arc<Self>) -> Option<ConnectionSlot> {
+        let mut connections = self.connections.lock().ok()?;
+        while *connections >= MAX_CONNECTIONS && !self.stopped.load(Ordering::SeqCst) {
+            connections = self.connection_closed.wait(connections).ok()?;
+        }
+        if self.stopped.load(Ordering::SeqCst) {
+            return None;
+        }
+        *connections += 1;
+        Some(ConnectionSlot(Arc::clone(self)))
+    }
+}
+
+/// A taken connection slot, given back on drop.
+struct ConnectionSlot(Arc<Shared>);
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        if let Ok(mut connections) = self.0.connections.lock() {
+            *connections -= 1;
+        }
+        self.0.connection_closed.notify_one();
+    }
+}
+
+/// A running server; dropping it stops the server.
+pub struct HttpApi {
+    addr: SocketAddr,
+    shared: Arc<Shared>,
+    accept: Option<JoinHandle<()>>,
+}
+
+impl HttpApi {
+    /// Opens `db_path` and starts listening on `127.0.0.1:port` (0 picks a
+    /// free port).
+    pub fn start(db_path: &Path, port: u16, on_change: OnChange) -> AppResult<HttpApi> {
+        let store = Store::open(db_path)?;
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port)).map_err(|err| {
+            if err.kind() == std::io::ErrorKind::AddrInUse {
+                AppError::PreconditionFailed {
+                    entity: Entity::Setting,
+                    ids: vec!["http_api_port".to_string()],
+                    reason: "port_in_use",
+                }
+            } else {
+                err.into()
+            }
+        })?;
+        let addr = listener.local_addr()?;
+        let shared = Arc::new(Shared {
+            db: DbState {
+                store: Mutex::new(store),
+                path: db_path.to_path_buf(),
+            },
+            port: addr.port(),
+            subscribers: Subscribers::default(),
+            on_change,
+            stopped: AtomicBool::new(false),
+            connections: Mutex::new(0),
+            connection_closed: Condvar::new(),
+        });
+
+        let accept_shared = Arc::clone(&shared);
+        let accept = std::thread::spawn(move || {
+            while let Some(slot) = accept_shared.wait_for_slot() {
+                let Ok((stream, _)) = listener.accept() else {
+                    continue;
+                };
+                if accept_shared.stopped.load(Ordering::SeqCst) {
+                    break;
+                }
+                std::thread::spawn(move || handle_connection(&slot.0, stream));
+            }
+        });
+
+        Ok(HttpApi {
+            addr,
+            shared,
+            accept: Some(accept),
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Sends a change made outside the API to event subscribers.
+    pub fn publish(&self, event: &ChangeEvent) {
+        self.shared.subscribers.publish(event);
+    }
+}
+
+impl Drop for HttpApi {
+    fn drop(&mut self) {
+        self.shared.stopped.store(true, Ordering::SeqCst);
+        self.shared.subscribers.close();
+        // Wake the accept loop, whether it waits for a slot or a connection,
+        // so it sees the flag. Notifying under the lock means a check of the
+        // flag can't slip in between.
+        {
+            let _connections = self.shared.connections.lock();
+            self.shared.connection_closed.notify_all();
+        }
+        let _ = TcpStream::connect(self.addr);
+        if let Some(accept) = self.accept.take() {
+            let _ = accept.join();
+        }
+    }
+}
+
+/// The app's server, if the API is enabled, and why it last failed to start.
+#[derive(Default)]
+pub struct HttpApiState(pub Mutex<HttpApiSlot>);
+
+#[derive(Default)]
+pub struct HttpApiSlot {
+    pub server: Option<HttpApi>,
+    /// User-facing message.
+    pub error: Option<String>,
+}
+
+/// Starts, restarts or stops the app's server to match the settings,
+/// generating a token the first time the API is enabled. Writes through the
+/// API are announced to the app windows as `DATA_CHANGED_EVENT`.
+pub fn apply_settings(app: &tauri::AppHandle, db: &DbState, api: &HttpApiState) -> AppResult<()> {
+    let settings = {
+        let mut store = db.lock()?;
+        let settings = store.get_settings()?;
+        if settings.http_api_enabled && settings.http_api_token.is_empty() {
+            let mut values = serde_json::Map::new();
+            values.insert("http_api_token".to_string(), generate_token().into());
+            store.update_settings(UpdateSettingsInput { values })?
+        } else {
+            settings
+        }
+    };
+
+    let mut slot = api.0.lock()?;
+    slot.server = None;
+    slot.error = None;
+    if !settings.http_api_enabled {
+        return Ok(());
+    }
+    let app = app.clone();
+    let on_change: OnChange = Arc::new(move |event: &ChangeEvent| {
+        let _ = app.emit(DATA_CHANGED_EVENT, event);
+    });
+    match HttpApi::start(&db.path, settings.http_api_port as u16, on_change) {
+        Ok(server) => {
+            slot.server = Some(server);
+            Ok(())
+        }
+        Err(err) => {
+            slot.error = Some(err.localized_message());
+            Err(err)
+        }
+    }
+}
+
+/// Forwards the windows' change notifications to event subscribers. Events
+/// the API itself emitted carry `source: "http"` and were already sent.
+pub fn forward_app_changes(app: &tauri::AppHandle) {
+    let handle = app.clone();
+    app.listen_any(DATA_CHANGED_EVENT, move |event| {
+        let from_api = serde_json::from_str::<Value>(event.payload())
+            .is_ok_and(|payload| payload["source"] == "http");
+        if from_api {
+            return;
+        }
+        let api = handle.state::<HttpApiState>();
+        if let Ok(slot) = api.inner().0.lock() {
+            if let Some(server) = &slot.server {
+                server.publish(&ChangeEvent::from_app());
+            }
+        }
+    });
+}
+
+/// A new random token for `http_api_token`.
+pub fn generate_token() -> String {
+    let token = format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+    debug_assert_eq!(token.len(), settings::TOKEN_LEN);
+    token
+}
+
+struct Request {
+    method: String,
+    path: String,
+    query: Vec<(String, String)>,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn query(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+struct Response {
+    status: u16,
+    body: String,
+}
+
+impl Response {
+    fn json(status: u16, value: &impl Serialize) -> Response {
+        Response {
+            status,
+            body: serde_json::to_string(value).unwrap_or_else(|_| "null".to_string()),
+        }
+    }
+
+    /// Error outside the commands (routing, auth), shaped like `AppError`.
+    fn error(status: u16, code: &str, message: &str) -> Response {
+        Response::json(
+            status,
+            &serde_json::json!({
+                "code": code,
+                "entity": null,
+                "ids": [],
+                "reason": null,
+                "fields": [],
+                "message": message,
+                "detail": code,
+            }),
+        )
+    }
+
+    fn from_error(err: &AppError) -> Response {
+        let status = match err {
+            AppError::NotFound { .. } => 404,
+            AppError::Validation(_) => 422,
+            AppError::PreconditionFailed { .. } | AppError::Constraint { .. } => 409,
+            AppError::Locked(_) => 503,
+            _ => 500,
+        };
+        Response::json(status, err)
+    }
+}
+
+fn handle_connection(shared: &Shared, stream: TcpStream) {
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(READ_TIMEOUT));
+    let Ok(write_half) = stream.try_clone() else {
+        return;
+    };
+    let mut reader = BufReader::new(stream);
+    let mut writer = write_half;
+
+    // The body is only read once the caller has been let in.
+    let mut request = match read_head(&mut reader) {
+        Ok(Some(request)) => request,
+        Ok(None) => return,
+        Err(response) => {
+            let _ = write_response(&mut writer, &response);
+            return;
+        }
+    };
+    if let Err(response) =
+        check_access(shared, &request).and_then(|()| read_body(&mut reader, &mut request))
+    {
+        let _ = write_response(&mut writer, &response);
+        return;
+    }
+
+    if request.method == "GET" && request.path == "/api/events" {
+        stream_events(shared, &mut writer);
+        return;
+    }
+    let response = route(shared, &request).unwrap_or_else(|err| Response::from_error(&err));
+    let _ = write_response(&mut writer, &response);
+}
+
+fn bad_request() -> Response {
+    Response::error(400, "bad_request", "잘못된 요청입니다.")
+}
+
+/// Reads the request line and headers, leaving `body` empty. `Ok(None)` is a
+/// connection closed before sending anything, such as the wake-up connection
+/// from `drop`.
+fn read_head(reader: &mut impl BufRead) -> Result<Option<Request>, Response> {
+    let mut head_bytes = 0;
+    let mut read_line = |reader: &mut dyn BufRead| -> Result<String, Response> {
+        let mut line = String::new();
+        let n = reader
+            .take((MAX_HEAD_BYTES - head_bytes) as u64 + 1)
+            .read_line(&mut line)
+            .map_err(|_| bad_request())?;
+        head_bytes += n;
+        if head_bytes > MAX_HEAD_BYTES {
+            return Err(Response::error(
+                431,
+                "headers_too_large",
+                "요청 헤더가 너무 큽니다.",
+            ));
+        }
+        Ok(line.trim_end_matches(['\r', '\n']).to_string())
+    };
+
+    let request_line = read_line(reader)?;
+    if request_line.is_empty() {
+        return Ok(None);
+    }
+    let mut parts = request_line.split(' ');
+    let (Some(method), Some(target), Some(_version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(bad_request());
+    };
+
+    let mut headers = Vec::new();
+    loop {
+        let line = read_line(reader)?;
+        if line.is_empty() {
+            break;
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            return Err(bad_request());
+        };
+        headers.push((name.trim().to_string(), value.trim().to_string()));
+    }
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    Ok(Some(Request {
+        method: method.to_string(),
+        path: percent_decode(path),
+        query: query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (
+                    percent_decode(&key.replace('+', " ")),
+                    percent_decode(&value.replace('+', " ")),
+                )
+            })
+            .collect(),
+        headers,
+        body: Vec::new(),
+    }))
+}
+
+/// Reads the `Content-Length` body of a request from `read_head`.
+fn read_body(reader: &mut impl BufRead, request: &mut Request) -> Result<(), Response> {
+    let length: usize = match request.header("content-length") {
+        Some(value) => value.parse().map_err(|_| bad_request())?,
+        None => 0,
+    };
+    if length > MAX_BODY_BYTES {
+        return Err(Response::error(
+            413,
+            "body_too_large",
+            "요청 본문이 너무 큽니다.",
+        ));
+    }
+    request.body = vec![0; length];
+    reader
+        .read_exact(&mut request.body)
+        .map_err(|_| bad_request())
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Only local tools may call the API: the `Host` must be this machine (a web
+/// page can't reach it through DNS rebinding), and the bearer token must
+/// match.
+fn check_access(shared: &Shared, request: &Request) -> Result<(), Response> {
+    let host = request.header("host").unwrap_or("");
+    let allowed = ["127.0.0.1", "localhost"]
+        .iter()
+        .any(|name| host == *name || host == format!("{name}:{}", shared.port));
+    if !allowed {
+        return Err(Response::error(
+            403,
+            "forbidden_host",
+            "이 주소로는 접근할 수 없습니다.",
+        ));
+    }
+
+    let token = shared
+        .db
+        .lock()
+        .and_then(|store| store.get_settings())
+        .map(|settings| settings.http_api_token)
+        .map_err(|err| Response::from_error(&err))?;
+    let given = request
+        .header("authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .unwrap_or("");
+    if token.is_empty() || !same_secret(given.trim(), &token) {
+        return Err(Response::error(
+            401,
+            "unauthorized",
+            "인증 토큰이 올바르지 않습니다.",
+        ));
+    }
+    Ok(())
+}
+
+/// Compares without stopping at the first differing byte.
+fn same_secret(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |diff, (x, y)| diff | (x ^ y))
+            == 0
+}
+
+fn route(shared: &Shared, request: &Request) -> AppResult<Response> {
+    let segments: Vec<&str> = request
+        .path
+        .trim_matches('/')
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect();
+    let Some(rest) = segments.strip_prefix(&["api"]) else {
+        return Ok(not_found());
+    };
+
+    match (request.method.as_str(), rest) {
+        ("GET", ["categories"]) => {
+            let board = shared.db.lock()?.list_categories_with_memos()?;
+            Ok(Response::json(200, &board))
+        }
+        ("POST", ["categories"]) => {
+            let input: CreateCategoryInput = parse_body(request, None)?;
+            let category = shared.db.lock()?.create_category(input)?;
+            changed(shared, "create_category", &category.id);
+            Ok(Response::json(201, &category))
+        }
+        ("PUT", ["categories", id]) => {
+            let input: UpdateCategoryInput = parse_body(request, Some(id))?;
+            let result = shared.db.lock()?.update_category(input)?;
+            if let UpdateCategoryResult::Saved { category } = &result {
+                changed(shared, "update_category", &category.id);
+            }
+            Ok(Response::json(200, &result))
+        }
+        ("POST", ["memos"]) => {
+            let input: CreateMemoInput = parse_body(request, None)?;
+            let memo = shared.db.lock()?.create_memo(input)?;
+            changed(shared, "create_memo", &memo.id);
+            Ok(Response::json(201, &memo))
+        }
+        ("GET", ["memos", id]) => {
+            let memo = shared.db.lock()?.get_memo(id)?;
+            Ok(Response::json(200, &memo))
+        }
+        ("PUT", ["memos", id]) => {
+            let input: UpdateMemoInput = parse_body(request, Some(id))?;
+            let result = shared.db.lock()?.update_memo(input)?;
+            match &result {
+                UpdateMemoResult::Saved { memo } | UpdateMemoResult::Merged { memo } => {
+                    changed(shared, "update_memo", &memo.id);
+                }
+                UpdateMemoResult::Conflict { .. } => {}
+            }
+            Ok(Response::json(200, &result))
+        }
+        ("GET", ["search"]) => {
+            let limit = match request.query("limit") {
+                Some(limit) => Some(limit.parse().map_err(|_| {
+                    invalid_input(
+                        "limit",
+                        "invalid_value",
+                        "limit은 0 이상의 정수여야 합니다.",
+                    )
+                })?),
+                None => None,
+            };
+            let input = QueryMemosInput {
+                query: request.query("q").unwrap_or("").to_string(),
+                limit,
+            };
+            let memos = shared.db.lock()?.query_memos(input)?;
+            Ok(Response::json(200, &memos))
+        }
+        (_, ["categories"] | ["categories", _] | ["memos"] | ["memos", _] | ["search"]) => Ok(
+            Response::error(405, "method_not_allowed", "지원하지 않는 메서드입니다."),
+        ),
+        _ => Ok(not_found()),
+    }
+}
+
+fn not_found() -> Response {
+    Response::error(404, "no_route", "없는 경로입니다.")
+}
+
+fn invalid_input(field: &str, code: &str, message: &str) -> AppError {
+    AppError::Validation(vec![FieldError {
+        field: field.to_string(),
+        code: code.to_string(),
+        message: message.to_string(),
+        span: None,
+    }])
+}
+
+/// The JSON body as a command input; `id` from the path wins over one in
+/// the body.
+fn parse_body<T: serde::de::DeserializeOwned>(request: &Request, id: Option<&str>) -> AppResult<T> {
+    let mut value: Value = serde_json::from_slice(&request.body).map_err(|e| {
+        invalid_input(
+            "body",
+            "invalid_json",
+            &format!("JSON 본문을 읽을 수 없습니다: {e}"),
+        )
+    })?;
+    if let (Some(id), Value::Object(map)) = (id, &mut value) {
+        map.insert("id".to_string(), Value::String(id.to_string()));
+    }
+    serde_json::from_value(value).map_err(|e| {
+        invalid_input(
+            "body",
+            "invalid_input",
+            &format!("요청 본문이 올바르지 않습니다: {e}"),
+        )
+    })
+}
+
+fn changed(shared: &Shared, action: &'static str, id: &str) {
+    let event = ChangeEvent {
+        source: "http",
+        action: Some(action),
+        id: Some(id.to_string()),
+    };
+    shared.subscribers.publish(&event);
+    (shared.on_change)(&event);
+}
+
+fn stream_events(shared: &Shared, writer: &mut TcpStream) {
+    let events = shared.subscribers.subscribe();
+    let head = "HTTP/1.1 200 OK\r\n\
+                Content-Type: text/event-stream\r\n\
+                Cache-Control: no-cache\r\n\
+                Connection: keep-alive\r\n\r\n\
+                : connected\n\n";
+    if writer.write_all(head.as_bytes()).is_err() {
+        return;
+    }
+    loop {
+        let chunk = match events.recv_timeout(KEEPALIVE_INTERVAL) {
+            Ok(data) => format!("event: change\ndata: {data}\n\n"),
+            Err(RecvTimeoutError::Timeout) => ": keepalive\n\n".to_string(),
+            Err(RecvTimeoutError::Disconnected) => return,
+        };
+        if writer.write_all(chunk.as_bytes()).is_err() || writer.flush().is_err() {
+            return;
+        }
+    }
+}
+
+fn write_response(writer: &mut impl Write, response: &Response) -> std::io::Result<()> {
+    let reason = match response.status {
+        200 => "OK",
+        201 => "Created",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        413 => "Payload Too Large",
+        422 => "Unprocessable Entity",
+        431 => "Request Header Fields Too Large",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    };
+    let mut head = format!(
+        "HTTP/1.1 {} {reason}\r\nContent-Type: application/json; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        response.body.len()
+    );
+    if response.status == 401 {
+        head.push_str("WWW-Authenticate: Bearer\r\n");
+    }
+    head.push_str("\r\n");
+    writer.write_all(head.as_bytes())?;
+    writer.write_all(response.body.as_bytes())?;
+    writer.flush()
+}
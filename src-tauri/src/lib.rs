@@ -1,6 +1,21 @@
+pub mod cli;
 mod commands;
 mod db;
+mod error;
+mod http_api;
+pub mod mcp;
+mod merge;
+mod migrations;
 mod models;
+mod query;
+mod rank;
+mod settings;
+mod smart;
+mod sort;
+mod stats;
+mod store;
+mod template;
+mod validation;
 
 pub use db::DbState;
 use tauri::Manager;
@@ -11,6 +26,16 @@ pub fn run() {
         .setup(|app| {
             let db_state = db::init_db(app.handle())?;
             app.manage(db_state);
+            app.manage(http_api::HttpApiState::default());
+            // A port that is taken shouldn't keep the app from starting; the
+            // status command reports it.
+            let _ = http_api::apply_settings(
+                app.handle(),
+                &app.state::<DbState>(),
+                &app.state::<http_api::HttpApiState>(),
+            );
+            http_api::forward_app_changes(app.handle());
+            db::spawn_maintenance(app.handle().clone());
             Ok(())
         })
         .plugin(tauri_plugin_opener::init())
@@ -22,13 +47,54 @@ pub fn run() {
             commands::update_category,
             commands::set_category_archived,
             commands::set_category_collapsed,
+            commands::set_category_sort,
+            commands::set_category_auto_archive,
             commands::delete_category,
             commands::reorder_categories,
             commands::reorder_memos,
             commands::create_memo,
             commands::update_memo,
             commands::delete_memo,
-            commands::move_memo
+            commands::move_memo,
+            commands::move_memo_to,
+            commands::move_memos,
+            commands::delete_memos,
+            commands::set_memos_todo_done,
+            commands::list_recently_completed,
+            commands::set_memo_pinned,
+            commands::set_memo_starred,
+            commands::list_favorites,
+            commands::set_memo_archived,
+            commands::list_archive,
+            commands::query_memos,
+            commands::list_smart_categories,
+            commands::create_smart_category,
+            commands::update_smart_category,
+            commands::delete_smart_category,
+            commands::reorder_smart_categories,
+            commands::set_memos_color,
+            commands::set_memos_date,
+            commands::duplicate_memo,
+            commands::duplicate_category,
+            commands::list_templates,
+            commands::save_memo_as_template,
+            commands::delete_template,
+            commands::create_memo_from_template,
+            commands::set_category_default_template,
+            commands::open_journal_entry,
+            commands::list_memos_in_range,
+            commands::count_memos_by_day,
+            commands::count_todos_by_day,
+            commands::get_statistics,
+            commands::get_settings,
+            commands::update_settings,
+            commands::get_http_api_status,
+            commands::set_http_api,
+            commands::regenerate_http_api_token,
+            commands::export_settings,
+            commands::import_settings,
+            commands::check_database,
+            commands::repair_database
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -2,5 +2,9 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "--mcp") {
+        std::process::exit(ideanode_lib::mcp::run_stdio(&args));
+    }
     ideanode_lib::run()
 }
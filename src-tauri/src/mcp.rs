@@ -0,0 +1,473 @@
+// Model Context Protocol server, started with `ideanode --mcp`, so local
+// coding assistants can read and write the board:
+//
+//   { "mcpServers": { "ideanode": { "command": "ideanode", "args": ["--mcp"] } } }
+//
+// Messages are JSON-RPC 2.0, one per line on stdin and stdout; logs go to
+// stderr. The database is opened as in `ideanode-cli` (`--db <path>`, then
+// `IDEANODE_DB`, then the app's own file) and every tool calls the same
+// `Store` methods as the app.
+//
+//   tools      list_categories, search_memos, read_memo, create_memo
+//   resources  ideanode://categories/{id}, a category as Markdown
+//
+// Memo bodies are given to the assistant as plain text. Changes made here
+// reach an open app window on its next refresh, as with the CLI.
+
+use crate::cli;
+use crate::error::AppError;
+use crate::models::{CategoryWithMemos, CreateMemoInput, Memo, QueryMemosInput};
+use crate::stats;
+use crate::store::Store;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+const RESOURCE_PREFIX: &str = "ideanode://categories/";
+/// Newest first; a client asking for another version gets the first one.
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// Unknown resource URI, as the MCP specification suggests.
+const RESOURCE_NOT_FOUND: i64 = -32002;
+
+/// Entry point for `ideanode --mcp [--db <path>]`; `args` are the process
+/// arguments after the program name. Returns the exit code.
+pub fn run_stdio(args: &[String]) -> i32 {
+    let db_path = args
+        .iter()
+        .position(|arg| arg == "--db")
+        .and_then(|i| args.get(i + 1))
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("IDEANODE_DB").map(PathBuf::from));
+    let store = match cli::open_existing(db_path) {
+        Ok(store) => store,
+        Err(err) => {
+            eprintln!("ideanode mcp: {err}");
+            return 1;
+        }
+    };
+
+    let mut server = Server::new(store);
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout();
+    for line in stdin.lock().lines() {
+        let Ok(line) = line else { break };
+        if let Some(reply) = server.handle_line(&line) {
+            if writeln!(stdout, "{reply}")
+                .and_then(|()| stdout.flush())
+                .is_err()
+            {
+                break;
+            }
+        }
+    }
+    0
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        RpcError {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+pub struct Server {
+    store: Store,
+}
+
+impl Server {
+    pub fn new(store: Store) -> Self {
+        Server { store }
+    }
+
+    /// Handles one line of input: a request, a notification or a batch.
+    /// Returns the reply to write, if any.
+    pub fn handle_line(&mut self, line: &str) -> Option<String> {
+        if line.trim().is_empty() {
+            return None;
+        }
+        let reply = match serde_json::from_str::<Value>(line) {
+            Ok(Value::Array(batch)) if !batch.is_empty() => {
+                let replies: Vec<Value> = batch
+                    .into_iter()
+                    .filter_map(|message| self.handle(message))
+                    .collect();
+                if replies.is_empty() {
+                    return None;
+                }
+                Value::Array(replies)
+            }
+            Ok(message) => self.handle(message)?,
+            Err(e) => error_reply(
+                Value::Null,
+                RpcError::new(PARSE_ERROR, format!("invalid JSON: {e}")),
+            ),
+        };
+        Some(reply.to_string())
+    }
+
+    fn handle(&mut self, message: Value) -> Option<Value> {
+        let id = message.get("id").cloned();
+        let method = message.get("method").and_then(Value::as_str);
+        let (Some(method), Some("2.0")) = (method, message.get("jsonrpc").and_then(Value::as_str))
+        else {
+            // A response from the client; this server sends no requests.
+            if message.get("result").is_some() || message.get("error").is_some() {
+                return None;
+            }
+            return Some(error_reply(
+                id.unwrap_or(Value::Null),
+                RpcError::new(INVALID_REQUEST, "not a JSON-RPC 2.0 request"),
+            ));
+        };
+        // Notifications (`notifications/initialized`, cancellations) need no
+        // reply and change nothing here.
+        let id = id?;
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+        let result = match method {
+            "initialize" => Ok(initialize(&params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tools() })),
+            "tools/call" => self.call_tool(params),
+            "resources/list" => self.list_resources(),
+            "resources/read" => self.read_resource(params),
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("unknown method: {method}"),
+            )),
+        };
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(err) => error_reply(id, err),
+        })
+    }
+
+    fn call_tool(&mut self, params: Value) -> Result<Value, RpcError> {
+        #[derive(Deserialize)]
+        struct Call {
+            name: String,
+            #[serde(default)]
+            arguments: Value,
+        }
+        let call: Call = serde_json::from_value(params)
+            .map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))?;
+        let arguments = match call.arguments {
+            Value::Null => json!({}),
+            arguments => arguments,
+        };
+
+        let outcome = match call.name.as_str() {
+            "list_categories" => arguments_as(arguments).and_then(|a| self.list_categories(a)),
+            "search_memos" => arguments_as(arguments).and_then(|a| self.search_memos(a)),
+            "read_memo" => arguments_as(arguments).and_then(|a| self.read_memo(a)),
+            "create_memo" => arguments_as(arguments).and_then(|a| self.create_memo(a)),
+            name => {
+                return Err(RpcError::new(
+                    INVALID_PARAMS,
+                    format!("unknown tool: {name}"),
+                ))
+            }
+        };
+        // Failures are tool results, so the assistant sees why and can retry.
+        Ok(match outcome {
+            Ok(value) => json!({
+                "content": [{ "type": "text", "text": pretty(&value) }],
+                "isError": false,
+            }),
+            Err(message) => json!({
+                "content": [{ "type": "text", "text": message }],
+                "isError": true,
+            }),
+        })
+    }
+
+    fn list_categories(&self, args: ListCategoriesArgs) -> Result<Value, String> {
+        let board = self
+            .store
+            .list_all_categories_with_memos()
+            .map_err(tool_error)?;
+        Ok(board
+            .iter()
+            .filter(|item| args.archived || !item.category.archived)
+            .map(|item| {
+                let c = &item.category;
+                json!({
+                    "id": c.id,
+                    "title": c.title,
+                    "emoji": c.emoji,
+                    "kind": c.kind.as_str(),
+                    "archived": c.archived,
+                    "memo_count": item.memos.iter().filter(|m| args.archived || !m.archived).count(),
+                    "resource": format!("{RESOURCE_PREFIX}{}", c.id),
+                })
+            })
+            .collect())
+    }
+
+    fn search_memos(&self, args: SearchMemosArgs) -> Result<Value, String> {
+        let memos = self
+            .store
+            .query_memos(QueryMemosInput {
+                query: args.query,
+                limit: args.limit,
+            })
+            .map_err(tool_error)?;
+        let board = self
+            .store
+            .list_all_categories_with_memos()
+            .map_err(tool_error)?;
+        Ok(memos.iter().map(|memo| memo_json(memo, &board)).collect())
+    }
+
+    fn read_memo(&self, args: ReadMemoArgs) -> Result<Value, String> {
+        let memo = self.store.get_memo(args.id.trim()).map_err(tool_error)?;
+        let board = self
+            .store
+            .list_all_categories_with_memos()
+            .map_err(tool_error)?;
+        Ok(memo_json(&memo, &board))
+    }
+
+    fn create_memo(&mut self, args: CreateMemoArgs) -> Result<Value, String> {
+        let category = cli::resolve_category(&self.store, &args.category).map_err(tool_error)?;
+        let memo = self
+            .store
+            .create_memo(CreateMemoInput {
+                category_id: category.id,
+                emoji: args.emoji,
+                title: args.title,
+                color: args
+                    .color
+                    .unwrap_or_else(|| cli::DEFAULT_MEMO_COLOR.to_string()),
+                date_ymd: args.date,
+                content_md: args
+                    .content
+                    .as_deref()
+                    .map(cli::text_to_html)
+                    .unwrap_or_default(),
+            })
+            .map_err(tool_error)?;
+        let board = self
+            .store
+            .list_all_categories_with_memos()
+            .map_err(tool_error)?;
+        Ok(memo_json(&memo, &board))
+    }
+
+    fn list_resources(&self) -> Result<Value, RpcError> {
+        let board = self
+            .store
+            .list_all_categories_with_memos()
+            .map_err(internal)?;
+        let resources: Vec<Value> = board
+            .iter()
+            .filter(|item| !item.category.archived)
+            .map(|item| {
+                let c = &item.category;
+                json!({
+                    "uri": format!("{RESOURCE_PREFIX}{}", c.id),
+                    "name": c.title,
+                    "title": format!("{} {}", c.emoji, c.title).trim(),
+                    "description": format!("Memos of the \"{}\" category", c.title),
+                    "mimeType": "text/markdown",
+                })
+            })
+            .collect();
+        Ok(json!({ "resources": resources }))
+    }
+
+    fn read_resource(&self, params: Value) -> Result<Value, RpcError> {
+        let uri = params
+            .get("uri")
+            .and_then(Value::as_str)
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, "missing uri"))?;
+        let not_found = || RpcError::new(RESOURCE_NOT_FOUND, format!("resource not found: {uri}"));
+        let id = uri.strip_prefix(RESOURCE_PREFIX).ok_or_else(not_found)?;
+        let board = self
+            .store
+            .list_all_categories_with_memos()
+            .map_err(internal)?;
+        let item = board
+            .into_iter()
+            .find(|item| item.category.id == id)
+            .ok_or_else(not_found)?;
+        Ok(json!({
+            "contents": [{
+                "uri": uri,
+                "mimeType": "text/markdown",
+                "text": cli::markdown(std::slice::from_ref(&item)),
+            }]
+        }))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ListCategoriesArgs {
+    #[serde(default)]
+    archived: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SearchMemosArgs {
+    query: String,
+    limit: Option<u32>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ReadMemoArgs {
+    id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CreateMemoArgs {
+    category: String,
+    title: String,
+    content: Option<String>,
+    date: Option<String>,
+    color: Option<String>,
+    emoji: Option<String>,
+}
+
+fn initialize(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = PROTOCOL_VERSIONS
+        .iter()
+        .find(|v| Some(**v) == requested)
+        .unwrap_or(&PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": {}, "resources": {} },
+        "serverInfo": { "name": "ideanode", "version": env!("CARGO_PKG_VERSION") },
+        "instructions": "IdeaNode is a board of categories holding memos. Use list_categories \
+            to find a category, search_memos to find memos and create_memo to add one.",
+    })
+}
+
+fn tools() -> Value {
+    json!([
+        {
+            "name": "list_categories",
+            "description": "Lists the categories of the board in order, with their memo counts.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "archived": { "type": "boolean", "description": "Include archived categories and memos" }
+                }
+            }
+        },
+        {
+            "name": "search_memos",
+            "description": "Searches memos. Words must all match the title or body; filters: \
+                tag:x, done:true|false, pinned:, starred:, archived:, date:>=2026-01-01 or \
+                date:today-7..today, cat:\"Category\", color:#ff0000, title:x; \"exact phrase\"; \
+                -term excludes. Archived memos are left out unless the query has archived:.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string" },
+                    "limit": { "type": "integer", "minimum": 0 }
+                },
+                "required": ["query"]
+            }
+        },
+        {
+            "name": "read_memo",
+            "description": "Reads one memo by id, with its body as plain text and as HTML.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "id": { "type": "string" } },
+                "required": ["id"]
+            }
+        },
+        {
+            "name": "create_memo",
+            "description": "Adds a memo at the end of a category.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "category": { "type": "string", "description": "Category id or title" },
+                    "title": { "type": "string" },
+                    "content": { "type": "string", "description": "Plain text; each line becomes a paragraph" },
+                    "date": { "type": "string", "description": "YYYY-MM-DD, default today" },
+                    "color": { "type": "string", "description": "Text color such as #ff0000" },
+                    "emoji": { "type": "string" }
+                },
+                "required": ["category", "title"]
+            }
+        }
+    ])
+}
+
+fn arguments_as<T: serde::de::DeserializeOwned>(arguments: Value) -> Result<T, String> {
+    serde_json::from_value(arguments).map_err(|e| format!("invalid arguments: {e}"))
+}
+
+/// The memo for an assistant: the body as plain text next to the stored
+/// HTML, and the category's title.
+fn memo_json(memo: &Memo, board: &[CategoryWithMemos]) -> Value {
+    let category = board
+        .iter()
+        .find(|item| item.category.id == memo.category_id)
+        .map(|item| item.category.title.as_str())
+        .unwrap_or_default();
+    json!({
+        "id": memo.id,
+        "category_id": memo.category_id,
+        "category": category,
+        "emoji": memo.emoji,
+        "title": memo.title,
+        "date": memo.date_ymd,
+        "color": memo.color,
+        "text": stats::plain_text(&memo.content_md).trim(),
+        "content_html": memo.content_md,
+        "todo_done": memo.todo_done,
+        "pinned": memo.pinned,
+        "starred": memo.starred,
+        "archived": memo.archived,
+        "updated_at": memo.updated_at,
+    })
+}
+
+fn tool_error(err: AppError) -> String {
+    match &err {
+        AppError::Validation(fields) => fields
+            .iter()
+            .map(|f| format!("{}: {}", f.field, f.message))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => format!("{} ({err})", err.localized_message()),
+    }
+}
+
+fn internal(err: AppError) -> RpcError {
+    RpcError::new(-32603, err.to_string())
+}
+
+fn error_reply(id: Value, err: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": err.code, "message": err.message },
+    })
+}
+
+fn pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
@@ -0,0 +1,331 @@
+// Three-way merge for memo edits.
+//
+// `content_md` is TipTap HTML, which usually has no newlines at all, so the
+// text is split into chunks ending at a newline or a closing tag. That keeps
+// edits in different paragraphs/list items from touching the same chunk.
+
+use crate::models::MemoRevision;
+
+/// Upper bound on base*side chunk pairs for the LCS table; larger docs are
+/// reported as a conflict instead of merged.
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+fn split_chunks(text: &str) -> Vec<&str> {
+    let bytes = text.as_bytes();
+    let mut out = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let end = if bytes[i] == b'\n' {
+            Some(i + 1)
+        } else if bytes[i] == b'<' && bytes.get(i + 1) == Some(&b'/') {
+            text[i..].find('>').map(|off| i + off + 1)
+        } else {
+            None
+        };
+        match end {
+            Some(end) => {
+                out.push(&text[start..end]);
+                start = end;
+                i = end;
+            }
+            None => i += 1,
+        }
+    }
+    if start < bytes.len() {
+        out.push(&text[start..]);
+    }
+    out
+}
+
+/// For each base chunk, the index of the matching chunk in `other` (if any),
+/// taken from a longest common subsequence.
+fn lcs_matches(base: &[&str], other: &[&str]) -> Option<Vec<Option<usize>>> {
+    let (n, m) = (base.len(), other.len());
+    if n.saturating_mul(m) > MAX_LCS_CELLS {
+        return None;
+    }
+
+    let w = m + 1;
+    let mut table = vec![0u32; (n + 1) * w];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            table[i * w + j] = if base[i] == other[j] {
+                table[(i + 1) * w + j + 1] + 1
+            } else {
+                table[(i + 1) * w + j].max(table[i * w + j + 1])
+            };
+        }
+    }
+
+    let mut matches = vec![None; n];
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if base[i] == other[j] {
+            matches[i] = Some(j);
+            i += 1;
+            j += 1;
+        } else if table[(i + 1) * w + j] >= table[i * w + j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    Some(matches)
+}
+
+fn resolve_chunk<'a>(
+    base: &[&'a str],
+    ours: &[&'a str],
+    theirs: &[&'a str],
+    out: &mut Vec<&'a str>,
+) -> bool {
+    if ours == base {
+        out.extend_from_slice(theirs);
+    } else if theirs == base || ours == theirs {
+        out.extend_from_slice(ours);
+    } else {
+        return false;
+    }
+    true
+}
+
+/// Merges `ours` and `theirs`, both derived from `base`.
+/// Returns `None` when both sides changed the same region differently.
+pub fn merge_text(base: &str, ours: &str, theirs: &str) -> Option<String> {
+    if ours == theirs || theirs == base {
+        return Some(ours.to_string());
+    }
+    if ours == base {
+        return Some(theirs.to_string());
+    }
+
+    let base_c = split_chunks(base);
+    let ours_c = split_chunks(ours);
+    let theirs_c = split_chunks(theirs);
+    let ours_m = lcs_matches(&base_c, &ours_c)?;
+    let theirs_m = lcs_matches(&base_c, &theirs_c)?;
+
+    let mut out: Vec<&str> = Vec::new();
+    let (mut bi, mut oi, mut ti) = (0, 0, 0);
+    loop {
+        // Next base chunk that both sides kept unchanged.
+        let sync = (bi..base_c.len()).find_map(|j| match (ours_m[j], theirs_m[j]) {
+            (Some(o), Some(t)) => Some((j, o, t)),
+            _ => None,
+        });
+
+        let (bj, oj, tj) = sync.unwrap_or((base_c.len(), ours_c.len(), theirs_c.len()));
+        if !resolve_chunk(
+            &base_c[bi..bj],
+            &ours_c[oi..oj],
+            &theirs_c[ti..tj],
+            &mut out,
+        ) {
+            return None;
+        }
+
+        if sync.is_none() {
+            break;
+        }
+        out.push(base_c[bj]);
+        bi = bj + 1;
+        oi = oj + 1;
+        ti = tj + 1;
+    }
+
+    Some(out.concat())
+}
+
+/// Scalar field merge: whichever side moved away from `base` wins.
+/// Returns `None` when both moved to different values.
+fn merge_value<T: PartialEq + Clone>(base: &T, ours: &T, theirs: &T) -> Option<T> {
+    if ours == theirs || theirs == base {
+        Some(ours.clone())
+    } else if ours == base {
+        Some(theirs.clone())
+    } else {
+        None
+    }
+}
+
+fn merge_field<T: PartialEq + Clone>(
+    name: &str,
+    base: &T,
+    ours: &T,
+    theirs: &T,
+    conflicts: &mut Vec<String>,
+) -> T {
+    merge_value(base, ours, theirs).unwrap_or_else(|| {
+        conflicts.push(name.to_string());
+        ours.clone()
+    })
+}
+
+/// Merges a stale client edit (`ours`) with the current row (`theirs`).
+/// On failure returns the names of the conflicting fields.
+pub fn merge_memo(
+    base: &MemoRevision,
+    ours: &MemoRevision,
+    theirs: &MemoRevision,
+) -> Result<MemoRevision, Vec<String>> {
+    let mut conflicts = Vec::new();
+    let emoji = merge_field(
+        "emoji",
+        &base.emoji,
+        &ours.emoji,
+        &theirs.emoji,
+        &mut conflicts,
+    );
+    let title = merge_field(
+        "title",
+        &base.title,
+        &ours.title,
+        &theirs.title,
+        &mut conflicts,
+    );
+    let color = merge_field(
+        "color",
+        &base.color,
+        &ours.color,
+        &theirs.color,
+        &mut conflicts,
+    );
+    let date_ymd = merge_field(
+        "date_ymd",
+        &base.date_ymd,
+        &ours.date_ymd,
+        &theirs.date_ymd,
+        &mut conflicts,
+    );
+    let todo_done = merge_field(
+        "todo_done",
+        &base.todo_done,
+        &ours.todo_done,
+        &theirs.todo_done,
+        &mut conflicts,
+    );
+    let content_md = merge_text(&base.content_md, &ours.content_md, &theirs.content_md)
+        .unwrap_or_else(|| {
+            conflicts.push("content_md".to_string());
+            String::new()
+        });
+
+    if !conflicts.is_empty() {
+        return Err(conflicts);
+    }
+    Ok(MemoRevision {
+        emoji,
+        title,
+        color,
+        date_ymd,
+        content_md,
+        todo_done,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn revision(title: &str, color: &str, content_md: &str) -> MemoRevision {
+        MemoRevision {
+            emoji: String::new(),
+            title: title.to_string(),
+            color: color.to_string(),
+            date_ymd: "2026-01-01".to_string(),
+            content_md: content_md.to_string(),
+            todo_done: false,
+        }
+    }
+
+    #[test]
+    fn chunks_end_at_closing_tags_and_newlines() {
+        assert_eq!(
+            split_chunks("<p>a</p><p>b <b>c</b></p>"),
+            ["<p>a</p>", "<p>b <b>c</b>", "</p>"]
+        );
+        assert_eq!(split_chunks("one\ntwo\nthree"), ["one\n", "two\n", "three"]);
+        assert!(split_chunks("").is_empty());
+    }
+
+    #[test]
+    fn edits_to_different_paragraphs_are_combined() {
+        let base = "<p>a</p><p>b</p><p>c</p>";
+        let ours = "<p>A</p><p>b</p><p>c</p>";
+        let theirs = "<p>a</p><p>b</p><p>C</p>";
+        assert_eq!(
+            merge_text(base, ours, theirs).as_deref(),
+            Some("<p>A</p><p>b</p><p>C</p>")
+        );
+        assert_eq!(
+            merge_text("a\nb\nc", "A\nb\nc", "a\nb\nC").as_deref(),
+            Some("A\nb\nC")
+        );
+    }
+
+    #[test]
+    fn insertions_at_both_ends_are_combined() {
+        let base = "<p>a</p><p>b</p>";
+        let ours = "<p>x</p><p>a</p><p>b</p>";
+        let theirs = "<p>a</p><p>b</p><p>y</p>";
+        assert_eq!(
+            merge_text(base, ours, theirs).as_deref(),
+            Some("<p>x</p><p>a</p><p>b</p><p>y</p>")
+        );
+    }
+
+    #[test]
+    fn different_edits_to_the_same_paragraph_conflict() {
+        let base = "<p>a</p><p>b</p><p>c</p>";
+        let ours = "<p>a</p><p>mine</p><p>c</p>";
+        let theirs = "<p>a</p><p>yours</p><p>c</p>";
+        assert_eq!(merge_text(base, ours, theirs), None);
+    }
+
+    #[test]
+    fn the_same_edit_on_both_sides_is_kept_once() {
+        let base = "<p>a</p><p>b</p><p>c</p><p>d</p>";
+        let ours = "<p>a</p><p>X</p><p>c</p><p>D</p>";
+        let theirs = "<p>a</p><p>X</p><p>c</p><p>d</p>";
+        assert_eq!(
+            merge_text(base, ours, theirs).as_deref(),
+            Some("<p>a</p><p>X</p><p>c</p><p>D</p>")
+        );
+        assert_eq!(merge_text(base, ours, ours).as_deref(), Some(ours));
+    }
+
+    #[test]
+    fn empty_sides() {
+        // Only one side changed: that side wins, even when it is empty.
+        assert_eq!(merge_text("", "<p>a</p>", "").as_deref(), Some("<p>a</p>"));
+        assert_eq!(merge_text("", "", "<p>b</p>").as_deref(), Some("<p>b</p>"));
+        assert_eq!(merge_text("<p>a</p>", "<p>a</p>", "").as_deref(), Some(""));
+        assert_eq!(merge_text("", "", "").as_deref(), Some(""));
+        // Both wrote into an empty memo, or one cleared what the other edited.
+        assert_eq!(merge_text("", "<p>a</p>", "<p>b</p>"), None);
+        assert_eq!(merge_text("<p>a</p><p>b</p>", "", "<p>a</p><p>B</p>"), None);
+    }
+
+    #[test]
+    fn memo_fields_merge_independently() {
+        let base = revision("title", "#ffffff", "<p>a</p><p>b</p><p>c</p>");
+        let ours = revision("new title", "#ffffff", "<p>A</p><p>b</p><p>c</p>");
+        let theirs = revision("title", "#ff0000", "<p>a</p><p>b</p><p>C</p>");
+        let merged = merge_memo(&base, &ours, &theirs).unwrap();
+        assert_eq!(merged.title, "new title");
+        assert_eq!(merged.color, "#ff0000");
+        assert_eq!(merged.content_md, "<p>A</p><p>b</p><p>C</p>");
+    }
+
+    #[test]
+    fn memo_conflicts_name_every_field() {
+        let base = revision("title", "#ffffff", "<p>a</p>");
+        let ours = revision("mine", "#ffffff", "<p>x</p>");
+        let theirs = revision("yours", "#ffffff", "<p>y</p>");
+        assert_eq!(
+            merge_memo(&base, &ours, &theirs).unwrap_err(),
+            ["title", "content_md"]
+        );
+    }
+}
@@ -0,0 +1,757 @@
+// Schema migrations. `MIGRATIONS` lists every schema change in order; each
+// one runs once and is recorded in `schema_migrations` with a checksum, so a
+// migration edited after it shipped is caught instead of silently diverging.
+// `PRAGMA user_version` is kept in step for older builds and quick checks.
+//
+// Before migrating an existing file it is copied next to itself, and after
+// migrating the schema is compared with `EXPECTED_TABLES`/`EXPECTED_INDEXES`
+// before anything is committed.
+
+use crate::db;
+use crate::error::{AppError, AppResult};
+use rusqlite::{params, Connection};
+use std::collections::BTreeSet;
+
+struct Migration {
+    /// `user_version` after this migration.
+    version: i64,
+    name: &'static str,
+    step: Step,
+}
+
+enum Step {
+    Sql(&'static str),
+    /// For changes that need Rust code. Checksummed by name only.
+    Rust(fn(&Connection) -> AppResult<()>),
+}
+
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial schema",
+        step: Step::Sql(
+            r#"
+            CREATE TABLE categories (
+              id TEXT PRIMARY KEY,
+              title TEXT NOT NULL,
+              color TEXT NOT NULL,
+              position INTEGER NOT NULL,
+              is_collapsed INTEGER NOT NULL DEFAULT 0,
+              created_at INTEGER NOT NULL,
+              updated_at INTEGER NOT NULL
+            );
+            CREATE TABLE memos (
+              id TEXT PRIMARY KEY,
+              category_id TEXT NOT NULL,
+              title TEXT NOT NULL,
+              color TEXT NOT NULL,
+              content_md TEXT NOT NULL,
+              position INTEGER NOT NULL,
+              created_at INTEGER NOT NULL,
+              updated_at INTEGER NOT NULL,
+              FOREIGN KEY(category_id) REFERENCES categories(id) ON DELETE CASCADE
+            );
+            CREATE INDEX idx_categories_position ON categories(position);
+            CREATE INDEX idx_memos_category_position ON memos(category_id, position);
+            "#,
+        ),
+    },
+    Migration {
+        version: 2,
+        name: "memo dates",
+        step: Step::Sql(
+            r#"
+            ALTER TABLE memos ADD COLUMN date_ymd TEXT NOT NULL DEFAULT '';
+            UPDATE memos
+            SET date_ymd = strftime('%Y-%m-%d', created_at/1000, 'unixepoch', 'localtime')
+            WHERE date_ymd = '';
+            "#,
+        ),
+    },
+    Migration {
+        version: 3,
+        name: "category emoji and settings",
+        step: Step::Sql(
+            r#"
+            ALTER TABLE categories ADD COLUMN emoji TEXT NOT NULL DEFAULT '';
+            CREATE TABLE settings (
+              key TEXT PRIMARY KEY,
+              value TEXT NOT NULL
+            );
+            "#,
+        ),
+    },
+    Migration {
+        version: 4,
+        name: "memo emoji",
+        step: Step::Sql(
+            r#"
+            ALTER TABLE memos ADD COLUMN emoji TEXT NOT NULL DEFAULT '';
+            "#,
+        ),
+    },
+    Migration {
+        version: 5,
+        name: "archive and todos",
+        step: Step::Sql(
+            r#"
+            ALTER TABLE categories ADD COLUMN archived INTEGER NOT NULL DEFAULT 0;
+            ALTER TABLE categories ADD COLUMN is_todo INTEGER NOT NULL DEFAULT 0;
+            ALTER TABLE memos ADD COLUMN todo_done INTEGER NOT NULL DEFAULT 0;
+            "#,
+        ),
+    },
+    Migration {
+        version: 6,
+        name: "versions and memo revisions",
+        step: Step::Sql(
+            r#"
+            ALTER TABLE categories ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+            ALTER TABLE memos ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+            CREATE TABLE memo_revisions (
+              memo_id TEXT NOT NULL,
+              version INTEGER NOT NULL,
+              emoji TEXT NOT NULL,
+              title TEXT NOT NULL,
+              color TEXT NOT NULL,
+              date_ymd TEXT NOT NULL,
+              content_md TEXT NOT NULL,
+              todo_done INTEGER NOT NULL,
+              created_at INTEGER NOT NULL,
+              PRIMARY KEY(memo_id, version),
+              FOREIGN KEY(memo_id) REFERENCES memos(id) ON DELETE CASCADE
+            );
+            INSERT INTO memo_revisions (memo_id, version, emoji, title, color, date_ymd, content_md, todo_done, created_at)
+            SELECT id, version, emoji, title, color, date_ymd, content_md, todo_done, updated_at FROM memos;
+            "#,
+        ),
+    },
+    Migration {
+        version: 7,
+        name: "fractional ranks",
+        step: Step::Rust(positions_to_ranks),
+    },
+    Migration {
+        version: 8,
+        name: "templates",
+        step: Step::Sql(
+            r#"
+            CREATE TABLE templates (
+              id TEXT PRIMARY KEY,
+              name TEXT NOT NULL,
+              emoji TEXT NOT NULL DEFAULT '',
+              title TEXT NOT NULL,
+              color TEXT NOT NULL,
+              content_md TEXT NOT NULL,
+              created_at INTEGER NOT NULL,
+              updated_at INTEGER NOT NULL
+            );
+            ALTER TABLE categories ADD COLUMN default_template_id TEXT
+              REFERENCES templates(id) ON DELETE SET NULL;
+            "#,
+        ),
+    },
+    Migration {
+        version: 9,
+        name: "category kinds",
+        step: Step::Sql(
+            r#"
+            ALTER TABLE categories ADD COLUMN kind TEXT NOT NULL DEFAULT 'normal'
+              CHECK (kind IN ('normal', 'todo', 'journal'));
+            UPDATE categories SET kind = 'todo' WHERE is_todo <> 0;
+            ALTER TABLE categories DROP COLUMN is_todo;
+            "#,
+        ),
+    },
+    Migration {
+        version: 10,
+        name: "memo date index",
+        step: Step::Sql(
+            r#"
+            CREATE INDEX idx_memos_date ON memos(date_ymd);
+            "#,
+        ),
+    },
+    Migration {
+        version: 11,
+        name: "todo completion time",
+        // Done todos get their last edit time as the best guess.
+        step: Step::Sql(
+            r#"
+            ALTER TABLE memos ADD COLUMN completed_at INTEGER;
+            UPDATE memos SET completed_at = updated_at WHERE todo_done <> 0;
+            "#,
+        ),
+    },
+    Migration {
+        version: 12,
+        name: "json setting values",
+        // Setting values become JSON so they can hold more than strings.
+        step: Step::Sql(
+            r#"
+            UPDATE settings SET value = json_quote(value) WHERE key = 'background_color';
+            "#,
+        ),
+    },
+    Migration {
+        version: 13,
+        name: "category sort modes",
+        step: Step::Sql(
+            r#"
+            ALTER TABLE categories ADD COLUMN sort_mode TEXT NOT NULL DEFAULT 'manual'
+              CHECK (sort_mode IN ('manual', 'date_asc', 'date_desc', 'title', 'updated', 'created'));
+            ALTER TABLE categories ADD COLUMN done_last INTEGER NOT NULL DEFAULT 0;
+            "#,
+        ),
+    },
+    Migration {
+        version: 14,
+        name: "auto archive",
+        step: Step::Sql(
+            r#"
+            ALTER TABLE memos ADD COLUMN archived_at INTEGER;
+            ALTER TABLE categories ADD COLUMN auto_archive_days INTEGER
+              CHECK (auto_archive_days IS NULL OR auto_archive_days >= 0);
+            CREATE INDEX idx_memos_completed_at ON memos(completed_at);
+            "#,
+        ),
+    },
+    Migration {
+        version: 15,
+        name: "pinned and starred memos",
+        step: Step::Sql(
+            r#"
+            ALTER TABLE memos ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;
+            ALTER TABLE memos ADD COLUMN starred INTEGER NOT NULL DEFAULT 0;
+            CREATE INDEX idx_memos_starred ON memos(starred) WHERE starred <> 0;
+            "#,
+        ),
+    },
+    Migration {
+        version: 16,
+        name: "memo restore time",
+        step: Step::Sql(
+            r#"
+            ALTER TABLE memos ADD COLUMN restored_at INTEGER;
+            "#,
+        ),
+    },
+    Migration {
+        version: 17,
+        name: "smart categories",
+        step: Step::Sql(
+            r#"
+            CREATE TABLE smart_categories (
+              id TEXT PRIMARY KEY,
+              emoji TEXT NOT NULL DEFAULT '',
+              title TEXT NOT NULL,
+              color TEXT NOT NULL,
+              filter TEXT NOT NULL,
+              rank TEXT NOT NULL,
+              created_at INTEGER NOT NULL,
+              updated_at INTEGER NOT NULL
+            );
+            CREATE INDEX idx_smart_categories_rank ON smart_categories(rank);
+            "#,
+        ),
+    },
+    Migration {
+        version: 18,
+        name: "memo activity",
+        // Edit days for the statistics, so revisions can be pruned to the few
+        // that merging needs. Seeded from the revisions still on file.
+        step: Step::Sql(
+            r#"
+            CREATE TABLE memo_activity (
+              memo_id TEXT NOT NULL,
+              day TEXT NOT NULL,
+              PRIMARY KEY(memo_id, day),
+              FOREIGN KEY(memo_id) REFERENCES memos(id) ON DELETE CASCADE
+            );
+            INSERT OR IGNORE INTO memo_activity (memo_id, day)
+            SELECT memo_id, strftime('%Y-%m-%d', created_at / 1000, 'unixepoch', 'localtime')
+            FROM memo_revisions WHERE version > 1;
+            "#,
+        ),
+    },
+];
+
+/// Columns of every app table after the last migration, in any order.
+/// Update together with `MIGRATIONS`.
+const EXPECTED_TABLES: &[(&str, &[&str])] = &[
+    (
+        "categories",
+        &[
+            "id",
+            "emoji",
+            "title",
+            "color",
+            "archived",
+            "is_collapsed",
+            "created_at",
+            "updated_at",
+            "version",
+            "rank",
+            "default_template_id",
+            "kind",
+            "sort_mode",
+            "done_last",
+            "auto_archive_days",
+        ],
+    ),
+    (
+        "memos",
+        &[
+            "id",
+            "category_id",
+            "emoji",
+            "title",
+            "color",
+            "date_ymd",
+            "content_md",
+            "todo_done",
+            "created_at",
+            "updated_at",
+            "version",
+            "rank",
+            "completed_at",
+            "archived_at",
+            "pinned",
+            "starred",
+            "restored_at",
+        ],
+    ),
+    (
+        "memo_revisions",
+        &[
+            "memo_id",
+            "version",
+            "emoji",
+            "title",
+            "color",
+            "date_ymd",
+            "content_md",
+            "todo_done",
+            "created_at",
+        ],
+    ),
+    ("memo_activity", &["memo_id", "day"]),
+    ("settings", &["key", "value"]),
+    (
+        "templates",
+        &[
+            "id",
+            "name",
+            "emoji",
+            "title",
+            "color",
+            "content_md",
+            "created_at",
+            "updated_at",
+        ],
+    ),
+    (
+        "smart_categories",
+        &[
+            "id",
+            "emoji",
+            "title",
+            "color",
+            "filter",
+            "rank",
+            "created_at",
+            "updated_at",
+        ],
+    ),
+];
+
+const EXPECTED_INDEXES: &[&str] = &[
+    "idx_categories_rank",
+    "idx_memos_category_rank",
+    "idx_memos_date",
+    "idx_memos_completed_at",
+    "idx_memos_starred",
+    "idx_smart_categories_rank",
+];
+
+/// Schema version this build migrates to.
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+/// Brings the database up to `latest_version`. Fails without changing
+/// anything if the file comes from a newer build, if a recorded migration
+/// no longer matches its checksum, or if the migrated schema isn't the
+/// expected one.
+pub fn run(conn: &Connection) -> AppResult<()> {
+    let current: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    let recorded = recorded_version(conn)?;
+    let found = current.max(recorded);
+    if found > latest_version() {
+        return Err(AppError::Schema {
+            reason: "newer_version",
+            detail: format!(
+                "database is at schema v{found}, this build supports up to v{}",
+                latest_version()
+            ),
+        });
+    }
+
+    let pending: Vec<&Migration> = MIGRATIONS.iter().filter(|m| m.version > current).collect();
+    if current > 0 && !pending.is_empty() {
+        backup(conn, current)?;
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    tx.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS schema_migrations (
+          version INTEGER PRIMARY KEY,
+          name TEXT NOT NULL,
+          checksum TEXT NOT NULL,
+          applied_at INTEGER
+        );
+        "#,
+    )?;
+    // Databases from before this table existed: what `user_version` says
+    // was applied is recorded without a time.
+    for migration in MIGRATIONS.iter().filter(|m| m.version <= current) {
+        tx.execute(
+            "INSERT OR IGNORE INTO schema_migrations (version, name, checksum, applied_at)
+             VALUES (?1, ?2, ?3, NULL)",
+            params![migration.version, migration.name, checksum(migration)],
+        )?;
+    }
+    verify_checksums(&tx)?;
+
+    for migration in pending {
+        match migration.step {
+            Step::Sql(sql) => tx.execute_batch(sql)?,
+            Step::Rust(run) => run(&tx)?,
+        }
+        tx.execute(
+            "INSERT INTO schema_migrations (version, name, checksum, applied_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                migration.version,
+                migration.name,
+                checksum(migration),
+                db::now_timestamp_ms()
+            ],
+        )?;
+    }
+    tx.execute_batch(&format!("PRAGMA user_version = {};", latest_version()))?;
+
+    verify_schema(&tx)?;
+    tx.commit()?;
+    Ok(())
+}
+
+fn recorded_version(conn: &Connection) -> AppResult<i64> {
+    let exists: bool = conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'schema_migrations')",
+        [],
+        |row| row.get(0),
+    )?;
+    if !exists {
+        return Ok(0);
+    }
+    Ok(conn.query_row(
+        "SELECT COALESCE(MAX(version), 0) FROM schema_migrations",
+        [],
+        |row| row.get(0),
+    )?)
+}
+
+/// FNV-1a of the migration's SQL (or name, for Rust steps), as hex.
+fn checksum(migration: &Migration) -> String {
+    let text = match migration.step {
+        Step::Sql(sql) => sql,
+        Step::Rust(_) => migration.name,
+    };
+    let hash = text.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{hash:016x}")
+}
+
+fn verify_checksums(conn: &Connection) -> AppResult<()> {
+    let recorded: Vec<(i64, String)> = conn
+        .prepare("SELECT version, checksum FROM schema_migrations ORDER BY version")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+
+    let changed: Vec<String> = recorded
+        .iter()
+        .filter(|(version, sum)| {
+            MIGRATIONS
+                .iter()
+                .find(|m| m.version == *version)
+                .is_some_and(|m| checksum(m) != *sum)
+        })
+        .map(|(version, _)| format!("v{version}"))
+        .collect();
+    if !changed.is_empty() {
+        return Err(AppError::Schema {
+            reason: "migration_changed",
+            detail: format!(
+                "applied migrations differ from this build: {}",
+                changed.join(", ")
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Copies the database to `<file>.v<version>-<ms>.bak` next to it. In-memory
+/// databases have nothing to keep.
+fn backup(conn: &Connection, version: i64) -> AppResult<()> {
+    let Some(path) = conn.path().filter(|path| !path.is_empty()) else {
+        return Ok(());
+    };
+    let target = format!("{path}.v{version}-{}.bak", db::now_timestamp_ms());
+    conn.execute("VACUUM INTO ?1", params![target])?;
+    Ok(())
+}
+
+/// Compares the tables and indexes with `EXPECTED_TABLES` and
+/// `EXPECTED_INDEXES`; column order and defaults may differ between
+/// databases that took different upgrade paths.
+fn verify_schema(conn: &Connection) -> AppResult<()> {
+    let mut problems = Vec::new();
+
+    for (table, expected) in EXPECTED_TABLES {
+        let actual: BTreeSet<String> = conn
+            .prepare(&format!("SELECT name FROM pragma_table_info('{table}')"))?
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        if actual.is_empty() {
+            problems.push(format!("missing table {table}"));
+            continue;
+        }
+        let expected: BTreeSet<String> = expected.iter().map(|c| c.to_string()).collect();
+        for column in expected.difference(&actual) {
+            problems.push(format!("missing column {table}.{column}"));
+        }
+        for column in actual.difference(&expected) {
+            problems.push(format!("unexpected column {table}.{column}"));
+        }
+    }
+
+    for index in EXPECTED_INDEXES {
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'index' AND name = ?1)",
+            params![index],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            problems.push(format!("missing index {index}"));
+        }
+    }
+
+    if !problems.is_empty() {
+        return Err(AppError::Schema {
+            reason: "schema_mismatch",
+            detail: problems.join(", "),
+        });
+    }
+    Ok(())
+}
+
+/// v6 -> v7: integer `position` columns become fractional `rank` keys.
+/// The ranks are generated in Rust, so this one isn't a plain SQL batch.
+fn positions_to_ranks(conn: &Connection) -> AppResult<()> {
+    conn.execute_batch(
+        r#"
+        ALTER TABLE categories ADD COLUMN rank TEXT NOT NULL DEFAULT '';
+        ALTER TABLE memos ADD COLUMN rank TEXT NOT NULL DEFAULT '';
+        "#,
+    )?;
+
+    db::write_spread_ranks(conn, "categories", None, "position ASC, created_at ASC")?;
+    let category_ids: Vec<String> = conn
+        .prepare("SELECT id FROM categories")?
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    for category_id in &category_ids {
+        db::write_spread_ranks(
+            conn,
+            "memos",
+            Some(("category_id", category_id)),
+            "position ASC, created_at ASC",
+        )?;
+    }
+
+    conn.execute_batch(
+        r#"
+        DROP INDEX IF EXISTS idx_categories_position;
+        DROP INDEX IF EXISTS idx_memos_category_position;
+        ALTER TABLE categories DROP COLUMN position;
+        ALTER TABLE memos DROP COLUMN position;
+        CREATE INDEX idx_categories_rank ON categories(rank);
+        CREATE INDEX idx_memos_category_rank ON memos(category_id, rank);
+        "#,
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The v5 schema as the first release created it, with a few rows whose
+    /// positions don't match their insertion order.
+    const BASELINE_V5: &str = r##"
+        CREATE TABLE categories (
+          id TEXT PRIMARY KEY,
+          emoji TEXT NOT NULL DEFAULT '',
+          title TEXT NOT NULL,
+          color TEXT NOT NULL,
+          position INTEGER NOT NULL,
+          archived INTEGER NOT NULL DEFAULT 0,
+          is_todo INTEGER NOT NULL DEFAULT 0,
+          is_collapsed INTEGER NOT NULL DEFAULT 0,
+          created_at INTEGER NOT NULL,
+          updated_at INTEGER NOT NULL
+        );
+        CREATE TABLE memos (
+          id TEXT PRIMARY KEY,
+          category_id TEXT NOT NULL,
+          emoji TEXT NOT NULL DEFAULT '',
+          title TEXT NOT NULL,
+          color TEXT NOT NULL,
+          date_ymd TEXT NOT NULL,
+          content_md TEXT NOT NULL,
+          todo_done INTEGER NOT NULL DEFAULT 0,
+          position INTEGER NOT NULL,
+          created_at INTEGER NOT NULL,
+          updated_at INTEGER NOT NULL,
+          FOREIGN KEY(category_id) REFERENCES categories(id) ON DELETE CASCADE
+        );
+        CREATE TABLE settings (
+          key TEXT PRIMARY KEY,
+          value TEXT NOT NULL
+        );
+        INSERT INTO categories (id, title, color, position, is_todo, created_at, updated_at)
+        VALUES ('c1', 'Second', '#ffffff', 1, 0, 1, 1), ('c2', 'First', '#ffffff', 0, 1, 2, 2);
+        INSERT INTO memos (id, category_id, title, color, date_ymd, content_md, todo_done, position, created_at, updated_at)
+        VALUES ('m1', 'c1', 'b', '#ffffff', '2024-01-01', '<p>b</p>', 0, 1, 1, 1),
+               ('m2', 'c1', 'a', '#ffffff', '2024-01-01', '<p>a</p>', 0, 0, 2, 2),
+               ('m3', 'c2', 'done', '#ffffff', '2024-01-02', '', 1, 0, 3, 3);
+        INSERT INTO settings (key, value) VALUES ('background_color', '#000000');
+        PRAGMA user_version = 5;
+    "##;
+
+    const POSITION_INDEXES: &str = r#"
+        CREATE INDEX idx_categories_position ON categories(position);
+        CREATE INDEX idx_memos_category_position ON memos(category_id, position);
+    "#;
+
+    fn migrate(with_position_indexes: bool) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(BASELINE_V5).unwrap();
+        if with_position_indexes {
+            conn.execute_batch(POSITION_INDEXES).unwrap();
+        }
+        run(&conn).unwrap();
+        verify_schema(&conn).unwrap();
+        conn
+    }
+
+    fn column(conn: &Connection, sql: &str) -> Vec<String> {
+        conn.prepare(sql)
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn baseline_databases_migrate_to_the_expected_schema() {
+        // Files created at v0 have the position indexes; ones upgraded from
+        // v1-v4 never got them.
+        for with_position_indexes in [true, false] {
+            let conn = migrate(with_position_indexes);
+            let version: i64 = conn
+                .query_row("PRAGMA user_version", [], |row| row.get(0))
+                .unwrap();
+            assert_eq!(version, latest_version());
+
+            assert_eq!(
+                column(&conn, "SELECT title FROM categories ORDER BY rank"),
+                ["First", "Second"]
+            );
+            assert_eq!(
+                column(
+                    &conn,
+                    "SELECT title FROM memos WHERE category_id = 'c1' ORDER BY rank"
+                ),
+                ["a", "b"]
+            );
+            assert_eq!(
+                column(&conn, "SELECT kind FROM categories ORDER BY rank"),
+                ["todo", "normal"]
+            );
+            assert_eq!(
+                column(
+                    &conn,
+                    "SELECT value FROM settings WHERE key = 'background_color'"
+                ),
+                [r##""#000000""##]
+            );
+
+            // Every step is recorded: pre-existing ones without a time.
+            let recorded: Vec<(i64, bool)> = conn
+                .prepare("SELECT version, applied_at IS NOT NULL FROM schema_migrations ORDER BY version")
+                .unwrap()
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+                .unwrap()
+                .collect::<rusqlite::Result<_>>()
+                .unwrap();
+            let expected: Vec<(i64, bool)> = MIGRATIONS
+                .iter()
+                .map(|m| (m.version, m.version > 5))
+                .collect();
+            assert_eq!(recorded, expected);
+
+            // Running again is a no-op.
+            run(&conn).unwrap();
+        }
+    }
+
+    #[test]
+    fn new_databases_match_the_expected_schema() {
+        let conn = Connection::open_in_memory().unwrap();
+        run(&conn).unwrap();
+        verify_schema(&conn).unwrap();
+    }
+
+    #[test]
+    fn changed_or_newer_migrations_are_refused() {
+        let conn = migrate(true);
+        conn.execute(
+            "UPDATE schema_migrations SET checksum = 'x' WHERE version = 3",
+            [],
+        )
+        .unwrap();
+        assert!(matches!(
+            run(&conn),
+            Err(AppError::Schema {
+                reason: "migration_changed",
+                ..
+            })
+        ));
+
+        let conn = migrate(true);
+        conn.execute_batch(&format!("PRAGMA user_version = {}", latest_version() + 1))
+            .unwrap();
+        assert!(matches!(
+            run(&conn),
+            Err(AppError::Schema {
+                reason: "newer_version",
+                ..
+            })
+        ));
+    }
+}
@@ -1,4 +1,101 @@
+use crate::error::AppError;
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How a category's memos are ordered for display. Only `Manual` follows
+/// `rank`; the others are computed when listing. Stored as text in
+/// `categories.sort_mode`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortMode {
+    #[default]
+    Manual,
+    DateAsc,
+    DateDesc,
+    /// Title, in Korean dictionary order.
+    Title,
+    /// Most recently updated first.
+    Updated,
+    /// Newest first.
+    Created,
+}
+
+impl SortMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SortMode::Manual => "manual",
+            SortMode::DateAsc => "date_asc",
+            SortMode::DateDesc => "date_desc",
+            SortMode::Title => "title",
+            SortMode::Updated => "updated",
+            SortMode::Created => "created",
+        }
+    }
+}
+
+impl ToSql for SortMode {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(self.as_str().into())
+    }
+}
+
+impl FromSql for SortMode {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value.as_str()? {
+            "manual" => Ok(SortMode::Manual),
+            "date_asc" => Ok(SortMode::DateAsc),
+            "date_desc" => Ok(SortMode::DateDesc),
+            "title" => Ok(SortMode::Title),
+            "updated" => Ok(SortMode::Updated),
+            "created" => Ok(SortMode::Created),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
+
+/// What a category holds. Stored as text in `categories.kind`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CategoryKind {
+    #[default]
+    Normal,
+    /// Memos are checklist items with `todo_done`.
+    Todo,
+    /// One memo per day, ordered by `date_ymd` (newest first).
+    Journal,
+    /// A saved search from `smart_categories`; only ever returned by
+    /// listings, never stored in `categories`.
+    Smart,
+}
+
+impl CategoryKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CategoryKind::Normal => "normal",
+            CategoryKind::Todo => "todo",
+            CategoryKind::Journal => "journal",
+            CategoryKind::Smart => "smart",
+        }
+    }
+}
+
+impl ToSql for CategoryKind {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(self.as_str().into())
+    }
+}
+
+impl FromSql for CategoryKind {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value.as_str()? {
+            "normal" => Ok(CategoryKind::Normal),
+            "todo" => Ok(CategoryKind::Todo),
+            "journal" => Ok(CategoryKind::Journal),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Category {
@@ -7,9 +104,21 @@ pub struct Category {
     pub title: String,
     pub color: String,
     pub position: i64,
+    pub rank: String,
     pub archived: bool,
+    pub kind: CategoryKind,
+    /// Same as `kind == Todo`; kept for older callers.
     pub is_todo: bool,
     pub is_collapsed: bool,
+    /// Template new memos in this category start from.
+    pub default_template_id: Option<String>,
+    pub sort_mode: SortMode,
+    /// Show done todos below the rest, whatever the sort mode.
+    pub done_last: bool,
+    /// Done memos are archived this many days after completion; `None` keeps
+    /// them on the board.
+    pub auto_archive_days: Option<i64>,
+    pub version: i64,
     pub created_at: i64,
     pub updated_at: i64,
 }
@@ -24,11 +133,61 @@ pub struct Memo {
     pub date_ymd: String,
     pub content_md: String,
     pub todo_done: bool,
+    /// When `todo_done` last became true; `None` while not done.
+    pub completed_at: Option<i64>,
+    /// `archived_at.is_some()`; archived memos are left out of the board but
+    /// keep their rank.
+    pub archived: bool,
+    pub archived_at: Option<i64>,
+    /// Kept at the top of its category, in its own manual order.
+    pub pinned: bool,
+    /// Listed in favorites.
+    pub starred: bool,
     pub position: i64,
+    pub rank: String,
+    pub version: i64,
     pub created_at: i64,
     pub updated_at: i64,
 }
 
+/// The user-editable fields of a memo at one version, kept in `memo_revisions`
+/// as the base for three-way merges.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoRevision {
+    pub emoji: String,
+    pub title: String,
+    pub color: String,
+    pub date_ymd: String,
+    pub content_md: String,
+    pub todo_done: bool,
+}
+
+impl From<&Memo> for MemoRevision {
+    fn from(memo: &Memo) -> Self {
+        Self {
+            emoji: memo.emoji.clone(),
+            title: memo.title.clone(),
+            color: memo.color.clone(),
+            date_ymd: memo.date_ymd.clone(),
+            content_md: memo.content_md.clone(),
+            todo_done: memo.todo_done,
+        }
+    }
+}
+
+impl From<&UpdateMemoInput> for MemoRevision {
+    fn from(input: &UpdateMemoInput) -> Self {
+        Self {
+            emoji: input.emoji.clone(),
+            title: input.title.clone(),
+            color: input.color.clone(),
+            date_ymd: input.date_ymd.clone(),
+            content_md: input.content_md.clone(),
+            todo_done: input.todo_done,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CategoryWithMemos {
     pub category: Category,
@@ -40,7 +199,11 @@ pub struct CreateCategoryInput {
     pub emoji: Option<String>,
     pub title: String,
     pub color: String,
+    #[serde(default)]
     pub is_todo: bool,
+    /// Takes precedence over `is_todo` when set.
+    #[serde(default)]
+    pub kind: Option<CategoryKind>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -49,6 +212,19 @@ pub struct UpdateCategoryInput {
     pub emoji: String,
     pub title: String,
     pub color: String,
+    /// `None` keeps the current kind.
+    #[serde(default)]
+    pub kind: Option<CategoryKind>,
+    /// Version the client last saw. `None` skips the conflict check (last write wins).
+    #[serde(default)]
+    pub expected_version: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum UpdateCategoryResult {
+    Saved { category: Category },
+    Conflict { server: Category },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -74,9 +250,65 @@ pub struct ReorderMemosInput {
     pub ordered_ids: Vec<String>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Theme {
+    System,
+    Light,
+    Dark,
+}
+
+/// Every setting with its current (or default) value; see `settings::SETTINGS`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppSettings {
+    /// Empty means the app's default background.
     pub background_color: String,
+    pub theme: Theme,
+    pub font_size: i64,
+    /// Category preselected for new memos.
+    pub default_category_id: Option<String>,
+    pub editor_spellcheck: bool,
+    /// Serve the local HTTP API on 127.0.0.1.
+    pub http_api_enabled: bool,
+    pub http_api_port: i64,
+    /// Bearer token for the HTTP API; generated when the API is first enabled.
+    pub http_api_token: String,
+}
+
+/// The local HTTP API as the settings window shows it.
+#[derive(Debug, Clone, Serialize)]
+pub struct HttpApiStatus {
+    pub enabled: bool,
+    pub running: bool,
+    /// e.g. `http://127.0.0.1:27183`, while running.
+    pub url: Option<String>,
+    pub port: i64,
+    pub token: String,
+    /// Why an enabled server isn't running, e.g. the port is taken.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetHttpApiInput {
+    pub enabled: bool,
+    /// Keeps the current port when omitted.
+    #[serde(default)]
+    pub port: Option<i64>,
+}
+
+/// Partial update: only the given keys change; `null` resets one to its default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateSettingsInput {
+    pub values: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Settings as written to / read from an export file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsFile {
+    /// Always `SETTINGS_FILE_FORMAT`.
+    pub format: String,
+    pub version: u32,
+    pub settings: serde_json::Map<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -103,12 +335,433 @@ pub struct UpdateMemoInput {
     pub date_ymd: String,
     pub content_md: String,
     pub todo_done: bool,
+    /// Version the client last saw. `None` skips the conflict check (last write wins).
+    #[serde(default)]
+    pub expected_version: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum UpdateMemoResult {
+    /// Written as-is; the client was up to date.
+    Saved { memo: Memo },
+    /// The client was stale but its edits didn't overlap the server's, so both were kept.
+    Merged { memo: Memo },
+    /// Nothing was written. `fields` lists what both sides changed differently.
+    Conflict {
+        server: Memo,
+        base_version: i64,
+        fields: Vec<String>,
+    },
+}
+
+/// Where a moved memo lands in its target category. Checked in the order
+/// `after_id`, `before_id`, `index`; none of them means "at the end".
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoSlot {
+    /// Place directly after this memo of the target category.
+    #[serde(default)]
+    pub after_id: Option<String>,
+    /// Place directly before this memo of the target category.
+    #[serde(default)]
+    pub before_id: Option<String>,
+    /// 0-based index among the target category's other memos; clamped to the end.
+    #[serde(default)]
+    pub index: Option<usize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MoveMemoInput {
     pub memo_id: String,
     pub to_category_id: String,
+    #[serde(flatten)]
+    pub slot: MemoSlot,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoveMemoToInput {
+    pub memo_id: String,
+    pub category_id: String,
+    #[serde(flatten)]
+    pub slot: MemoSlot,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoveMemosInput {
+    pub memo_ids: Vec<String>,
+    pub to_category_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteMemosInput {
+    pub memo_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetMemosTodoDoneInput {
+    pub memo_ids: Vec<String>,
+    pub todo_done: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetMemosColorInput {
+    pub memo_ids: Vec<String>,
+    pub color: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetMemosDateInput {
+    pub memo_ids: Vec<String>,
+    pub date_ymd: String,
+}
+
+/// Outcome for one id of a bulk command; `error` is set when it was skipped.
+#[derive(Debug, Serialize)]
+pub struct BulkItemResult {
+    pub id: String,
+    pub ok: bool,
+    pub error: Option<AppError>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct BulkReport {
+    pub succeeded: usize,
+    pub failed: usize,
+    pub items: Vec<BulkItemResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateMemoInput {
+    pub id: String,
+    /// Copy as not done, regardless of the original's todo state.
+    #[serde(default)]
+    pub reset_todo: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateCategoryInput {
+    pub id: String,
+    /// Copy every memo as not done.
+    #[serde(default)]
+    pub reset_todo: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Template {
+    pub id: String,
+    pub name: String,
+    pub emoji: String,
+    pub title: String,
+    pub color: String,
+    pub content_md: String,
+    /// Custom `{{name}}` placeholders the user is asked to fill in.
+    pub prompts: Vec<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveMemoAsTemplateInput {
+    pub memo_id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateMemoFromTemplateInput {
+    pub template_id: String,
+    pub category_id: String,
+    #[serde(default)]
+    pub date_ymd: Option<String>,
+    /// Answers to the template's custom prompts, by name.
+    #[serde(default)]
+    pub values: HashMap<String, String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetCategoryDefaultTemplateInput {
+    pub category_id: String,
+    pub template_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenJournalEntryInput {
+    pub category_id: String,
+    /// Defaults to today.
+    #[serde(default)]
+    pub date_ymd: Option<String>,
+}
+
+/// Inclusive `YYYY-MM-DD` range.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DateRangeInput {
+    pub from_ymd: String,
+    pub to_ymd: String,
+    /// Also count memos of archived categories.
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonthInput {
+    /// `YYYY-MM`
+    pub month: String,
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayCount {
+    pub date_ymd: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TodoDayCount {
+    pub date_ymd: String,
+    pub open: i64,
+    pub done: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StatisticsInput {
+    /// Number of weeks in `weekly`, ending with the current one. Defaults to 12.
+    #[serde(default)]
+    pub weeks: Option<u32>,
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
+/// Activity in the local week starting on Monday `week_start`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeekActivity {
+    pub week_start: String,
+    pub created: i64,
+    /// Distinct memos edited after creation.
+    pub updated: i64,
+    /// Todos marked done.
+    pub completed: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StatsSummary {
+    pub memo_count: i64,
+    pub word_count: i64,
+    pub char_count: i64,
+    pub todo_total: i64,
+    pub todo_done: i64,
+    /// `todo_done / todo_total`; `None` without todos.
+    pub todo_completion_rate: Option<f64>,
+    pub weekly: Vec<WeekActivity>,
+    /// Most consecutive local days on which a memo was created or edited.
+    pub longest_streak_days: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryStats {
+    pub category_id: String,
+    pub title: String,
+    #[serde(flatten)]
+    pub stats: StatsSummary,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Statistics {
+    pub overall: StatsSummary,
+    pub categories: Vec<CategoryStats>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetCategorySortInput {
+    pub id: String,
+    pub sort_mode: SortMode,
+    #[serde(default)]
+    pub done_last: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetCategoryAutoArchiveInput {
+    pub id: String,
+    /// `None` turns auto-archiving off.
+    #[serde(default)]
+    pub days: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecentlyCompletedInput {
+    /// How many days back to look; defaults to 7.
+    #[serde(default)]
+    pub days: Option<u32>,
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetMemoPinnedInput {
+    pub id: String,
+    pub pinned: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetMemoStarredInput {
+    pub id: String,
+    pub starred: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetMemoArchivedInput {
+    pub id: String,
+    pub archived: bool,
+}
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListArchiveInput {
+    /// Matches category titles and memo titles and text, ignoring case.
+    #[serde(default)]
+    pub query: Option<String>,
+}
+
+/// What the archive window shows.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Archive {
+    /// Archived categories, with all of their memos.
+    pub categories: Vec<CategoryWithMemos>,
+    /// Archived memos of active categories, grouped by category.
+    pub memos: Vec<CategoryWithMemos>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QueryMemosInput {
+    /// Search query, e.g. `tag:infra done:false "exact phrase" -draft`; see
+    /// `query.rs` for the grammar.
+    pub query: String,
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+/// Which memos a smart category shows. Every field that is set must match;
+/// list fields match any of their values, except `tags`, which must all be
+/// present.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SmartFilter {
+    /// `#tag` words in the memo's title or text.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Appears in the title or text, ignoring case.
+    #[serde(default)]
+    pub text: Option<String>,
+    /// Inclusive `date_ymd` bounds: a date, or `today`, `week_start`,
+    /// `week_end`, `month_start` or `month_end` with an optional `+N`/`-N`.
+    #[serde(default)]
+    pub date_from: Option<String>,
+    #[serde(default)]
+    pub date_to: Option<String>,
+    /// Only todo memos that are (or aren't) done.
+    #[serde(default)]
+    pub done: Option<bool>,
+    #[serde(default)]
+    pub colors: Vec<String>,
+    /// Empty means every active category.
+    #[serde(default)]
+    pub category_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmartCategory {
+    pub id: String,
+    pub emoji: String,
+    pub title: String,
+    pub color: String,
+    pub filter: SmartFilter,
+    pub position: i64,
+    pub rank: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateSmartCategoryInput {
+    #[serde(default)]
+    pub emoji: Option<String>,
+    pub title: String,
+    pub color: String,
+    pub filter: SmartFilter,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateSmartCategoryInput {
+    pub id: String,
+    pub emoji: String,
+    pub title: String,
+    pub color: String,
+    pub filter: SmartFilter,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityIssueKind {
+    /// Reported by `PRAGMA integrity_check`.
+    Integrity,
+    /// A row pointing at a missing row, other than an orphaned memo.
+    ForeignKey,
+    /// Rows of one list sharing a rank, so their order is undefined.
+    DuplicateRank,
+    /// An empty or malformed rank.
+    InvalidRank,
+    /// A memo whose category no longer exists.
+    OrphanMemo,
+    InvalidDate,
+    EmptyField,
+}
+
+/// One problem found by `check_database`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityIssue {
+    pub kind: IntegrityIssueKind,
+    pub table: String,
+    /// Rows involved, when they can be identified.
+    pub ids: Vec<String>,
+    /// User-facing (Korean) description.
+    pub message: String,
+    /// Specifics for logs, e.g. SQLite's own messages.
+    pub detail: String,
+    /// Whether `repair_database` fixes it.
+    pub repairable: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    pub ok: bool,
+    pub issues: Vec<IntegrityIssue>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RepairAction {
+    /// Rebuilt every index after `integrity_check` complained.
+    Reindex,
+    /// Gave a list fresh, evenly spaced ranks in its current order.
+    Rerank,
+    /// Created the category that orphaned memos are moved into.
+    CreateCategory,
+    MoveMemo,
+    DeleteRevision,
+    DeleteActivity,
+    ClearDefaultTemplate,
+}
+
+/// One change made by `repair_database`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepairChange {
+    pub action: RepairAction,
+    pub table: String,
+    pub ids: Vec<String>,
+    /// User-facing (Korean) description.
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepairReport {
+    pub changes: Vec<RepairChange>,
+    /// What `check_database` still finds afterwards.
+    pub remaining: Vec<IntegrityIssue>,
+}
@@ -0,0 +1,547 @@
+// Search query language for `query_memos`.
+//
+// A query is a list of terms separated by spaces; a memo must match all of
+// them. A term is free text, a `"quoted phrase"` or a `field:value` filter,
+// and a leading `-` negates it:
+//
+//   tag:infra done:false date:>=2026-01-01 cat:"Backend" color:#ff0000 "exact phrase" -excluded
+//
+// Fields: `tag`, `done`, `pinned`, `starred`, `archived` (true/false),
+// `date` (`2026-01-01`, `>=today-7`, `week_start..week_end`; bounds as in
+// smart categories), `cat` (category title or id), `color` and `title`.
+// Text matches titles and the visible text of bodies, ignoring case.
+// Archived memos and categories are left out unless the query has an
+// `archived:` term.
+//
+// Parsing reports the range of the offending term in UTF-16 code units, the
+// indices JavaScript uses for the same string, and compiling
+// produces a `WHERE` clause with numbered parameters. Text and tags are
+// matched by the `fold`, `memo_text` and `memo_has_tag` SQL functions that
+// `db::register_functions` installs.
+
+use crate::error::Span;
+use crate::smart;
+use rusqlite::types::Value;
+
+#[derive(Debug, Clone)]
+pub struct QueryError {
+    pub span: Span,
+    pub code: &'static str,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cmp {
+    Eq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Cmp {
+    fn sql(self) -> &'static str {
+        match self {
+            Cmp::Eq => "=",
+            Cmp::Lt => "<",
+            Cmp::Le => "<=",
+            Cmp::Gt => ">",
+            Cmp::Ge => ">=",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Term {
+    /// Word or phrase in the title or body, lowercased.
+    Text(String),
+    Title(String),
+    Tag(String),
+    Done(bool),
+    Pinned(bool),
+    Starred(bool),
+    Archived(bool),
+    /// Resolved to `YYYY-MM-DD`.
+    Date(Cmp, String),
+    DateRange(String, String),
+    Category(String),
+    Color(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clause {
+    pub negated: bool,
+    pub term: Term,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Query {
+    pub clauses: Vec<Clause>,
+}
+
+/// A `WHERE` condition over `memos` with its parameters (`?1`, `?2`, ...).
+#[derive(Debug, Clone)]
+pub struct Compiled {
+    pub condition: String,
+    pub params: Vec<Value>,
+}
+
+const FIELDS: &[&str] = &[
+    "tag", "done", "pinned", "starred", "archived", "date", "cat", "color", "title",
+];
+
+/// Parses `input`; relative date bounds are resolved against `today`. Spans
+/// are UTF-16 offsets into `input`.
+pub fn parse(input: &str, today: &str) -> Result<Query, QueryError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut offsets = Vec::with_capacity(chars.len() + 1);
+    offsets.push(0);
+    for c in &chars {
+        offsets.push(offsets[offsets.len() - 1] + c.len_utf16());
+    }
+    let utf16 = |span: Span| Span {
+        start: offsets[span.start],
+        end: offsets[span.end],
+    };
+
+    match parse_chars(&chars, today) {
+        Ok(mut query) => {
+            for clause in &mut query.clauses {
+                clause.span = utf16(clause.span);
+            }
+            Ok(query)
+        }
+        Err(mut err) => {
+            err.span = utf16(err.span);
+            Err(err)
+        }
+    }
+}
+
+/// `parse` over `chars`, with spans as char indices.
+fn parse_chars(chars: &[char], today: &str) -> Result<Query, QueryError> {
+    let mut clauses = Vec::new();
+    let mut i = 0;
+    loop {
+        while i < chars.len() && chars[i].is_whitespace() {
+            i += 1;
+        }
+        if i >= chars.len() {
+            break;
+        }
+
+        let start = i;
+        let negated = chars[i] == '-';
+        if negated {
+            i += 1;
+            if i >= chars.len() || chars[i].is_whitespace() {
+                return Err(error(
+                    start,
+                    i,
+                    "missing_term",
+                    "'-' 뒤에 제외할 검색어가 필요합니다.".to_string(),
+                ));
+            }
+        }
+
+        let term = if chars[i] == '"' {
+            let text = read_quoted(chars, &mut i)?;
+            if text.trim().is_empty() {
+                return Err(error(
+                    start,
+                    i,
+                    "missing_term",
+                    "따옴표 안에 검색어가 필요합니다.".to_string(),
+                ));
+            }
+            Term::Text(text.to_lowercase())
+        } else {
+            let name_end = (i..chars.len())
+                .find(|&j| !(chars[j].is_ascii_alphabetic() || chars[j] == '_'))
+                .unwrap_or(chars.len());
+            if name_end > i && chars.get(name_end) == Some(&':') {
+                let name: String = chars[i..name_end].iter().collect::<String>().to_lowercase();
+                let name_span = (i, name_end);
+                if !FIELDS.contains(&name.as_str()) {
+                    return Err(error(
+                        name_span.0,
+                        name_span.1,
+                        "unknown_field",
+                        format!(
+                            "알 수 없는 필드 '{name}'입니다. 사용할 수 있는 필드: {}",
+                            FIELDS.join(", ")
+                        ),
+                    ));
+                }
+                i = name_end + 1;
+                let value_start = i;
+                let value = if chars.get(i) == Some(&'"') {
+                    read_quoted(chars, &mut i)?
+                } else {
+                    read_word(chars, &mut i)
+                };
+                if value.trim().is_empty() {
+                    return Err(error(
+                        start,
+                        i,
+                        "missing_value",
+                        format!("'{name}:' 뒤에 값이 필요합니다."),
+                    ));
+                }
+                field_term(&name, value.trim(), (value_start, i), today)?
+            } else {
+                Term::Text(read_word(chars, &mut i).to_lowercase())
+            }
+        };
+
+        if i < chars.len() && !chars[i].is_whitespace() {
+            return Err(error(
+                start,
+                i + 1,
+                "unexpected_character",
+                "검색어 사이에는 공백이 필요합니다.".to_string(),
+            ));
+        }
+        clauses.push(Clause {
+            negated,
+            term,
+            span: Span { start, end: i },
+        });
+    }
+    Ok(Query { clauses })
+}
+
+fn error(start: usize, end: usize, code: &'static str, message: String) -> QueryError {
+    QueryError {
+        span: Span { start, end },
+        code,
+        message,
+    }
+}
+
+fn read_word(chars: &[char], i: &mut usize) -> String {
+    let start = *i;
+    while *i < chars.len() && !chars[*i].is_whitespace() {
+        *i += 1;
+    }
+    chars[start..*i].iter().collect()
+}
+
+/// Reads `"..."` starting at the opening quote; `\"` and `\\` are escapes.
+fn read_quoted(chars: &[char], i: &mut usize) -> Result<String, QueryError> {
+    let start = *i;
+    let mut out = String::new();
+    *i += 1;
+    while *i < chars.len() {
+        match chars[*i] {
+            '"' => {
+                *i += 1;
+                return Ok(out);
+            }
+            '\\' if matches!(chars.get(*i + 1), Some('"' | '\\')) => {
+                out.push(chars[*i + 1]);
+                *i += 2;
+            }
+            c => {
+                out.push(c);
+                *i += 1;
+            }
+        }
+    }
+    Err(error(
+        start,
+        chars.len(),
+        "unterminated_quote",
+        "닫는 따옴표(\")가 없습니다.".to_string(),
+    ))
+}
+
+fn field_term(
+    name: &str,
+    value: &str,
+    (start, end): (usize, usize),
+    today: &str,
+) -> Result<Term, QueryError> {
+    let flag = || match value.to_lowercase().as_str() {
+        "true" | "yes" => Ok(true),
+        "false" | "no" => Ok(false),
+        _ => Err(error(
+            start,
+            end,
+            "invalid_value",
+            format!("'{name}:'의 값은 true 또는 false여야 합니다."),
+        )),
+    };
+    let date = |bound: &str| {
+        smart::resolve_date(bound, today).ok_or_else(|| {
+            error(
+                start,
+                end,
+                "invalid_date",
+                format!(
+                    "'{bound}'은(는) 올바른 날짜가 아닙니다. 예: 2026-01-01, today-7, week_start"
+                ),
+            )
+        })
+    };
+
+    Ok(match name {
+        "tag" => {
+            let tag = smart::normalize_tag(value);
+            if !tag.chars().any(char::is_alphabetic) {
+                return Err(error(
+                    start,
+                    end,
+                    "invalid_value",
+                    "태그에는 글자가 하나 이상 있어야 합니다.".to_string(),
+                ));
+            }
+            Term::Tag(tag)
+        }
+        "done" => Term::Done(flag()?),
+        "pinned" => Term::Pinned(flag()?),
+        "starred" => Term::Starred(flag()?),
+        "archived" => Term::Archived(flag()?),
+        "date" => {
+            if let Some((from, to)) = value.split_once("..") {
+                Term::DateRange(date(from)?, date(to)?)
+            } else {
+                let (cmp, bound) = [
+                    (">=", Cmp::Ge),
+                    ("<=", Cmp::Le),
+                    (">", Cmp::Gt),
+                    ("<", Cmp::Lt),
+                    ("=", Cmp::Eq),
+                ]
+                .iter()
+                .find_map(|(op, cmp)| value.strip_prefix(op).map(|rest| (*cmp, rest)))
+                .unwrap_or((Cmp::Eq, value));
+                Term::Date(cmp, date(bound)?)
+            }
+        }
+        "cat" => Term::Category(value.to_string()),
+        "color" => Term::Color(value.to_string()),
+        "title" => Term::Title(value.to_lowercase()),
+        _ => unreachable!("field names are checked against FIELDS"),
+    })
+}
+
+impl Query {
+    fn mentions_archived(&self) -> bool {
+        self.clauses
+            .iter()
+            .any(|clause| matches!(clause.term, Term::Archived(_)))
+    }
+}
+
+/// Compiles `query` to a condition over `memos`. Every value is a parameter.
+pub fn compile(query: &Query) -> Compiled {
+    let mut params: Vec<Value> = Vec::new();
+    let mut param = |value: Value| {
+        params.push(value);
+        format!("?{}", params.len())
+    };
+
+    let mut conditions: Vec<String> = Vec::new();
+    if !query.mentions_archived() {
+        conditions.push(
+            "memos.archived_at IS NULL
+             AND memos.category_id IN (SELECT id FROM categories WHERE archived = 0)"
+                .to_string(),
+        );
+    }
+    for clause in &query.clauses {
+        let condition = match &clause.term {
+            Term::Text(text) => format!(
+                "instr(memo_text(memos.title, memos.content_md), {}) > 0",
+                param(Value::Text(text.clone()))
+            ),
+            Term::Title(text) => format!(
+                "instr(fold(memos.title), {}) > 0",
+                param(Value::Text(text.clone()))
+            ),
+            Term::Tag(tag) => format!(
+                "memo_has_tag(memos.title, memos.content_md, {})",
+                param(Value::Text(tag.clone()))
+            ),
+            Term::Done(done) => format!(
+                "memos.todo_done = {}
+                 AND memos.category_id IN (SELECT id FROM categories WHERE kind = 'todo')",
+                param(Value::Integer(i64::from(*done)))
+            ),
+            Term::Pinned(pinned) => format!(
+                "memos.pinned = {}",
+                param(Value::Integer(i64::from(*pinned)))
+            ),
+            Term::Starred(starred) => format!(
+                "memos.starred = {}",
+                param(Value::Integer(i64::from(*starred)))
+            ),
+            Term::Archived(archived) => format!(
+                "(memos.archived_at IS NOT NULL
+                  OR memos.category_id IN (SELECT id FROM categories WHERE archived <> 0))
+                 = {}",
+                param(Value::Integer(i64::from(*archived)))
+            ),
+            Term::Date(cmp, date) => format!(
+                "memos.date_ymd {} {}",
+                cmp.sql(),
+                param(Value::Text(date.clone()))
+            ),
+            Term::DateRange(from, to) => format!(
+                "memos.date_ymd BETWEEN {} AND {}",
+                param(Value::Text(from.clone())),
+                param(Value::Text(to.clone()))
+            ),
+            Term::Category(category) => {
+                let p = param(Value::Text(category.clone()));
+                format!(
+                    "memos.category_id IN
+                     (SELECT id FROM categories WHERE id = {p} OR title = {p} COLLATE NOCASE)"
+                )
+            }
+            Term::Color(color) => format!(
+                "memos.color = {} COLLATE NOCASE",
+                param(Value::Text(color.clone()))
+            ),
+        };
+        conditions.push(if clause.negated {
+            format!("NOT ({condition})")
+        } else {
+            format!("({condition})")
+        });
+    }
+
+    Compiled {
+        condition: if conditions.is_empty() {
+            "1".to_string()
+        } else {
+            conditions.join(" AND ")
+        },
+        params,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TODAY: &str = "2026-01-15";
+
+    fn terms(input: &str) -> Vec<(bool, Term)> {
+        parse(input, TODAY)
+            .unwrap()
+            .clauses
+            .into_iter()
+            .map(|clause| (clause.negated, clause.term))
+            .collect()
+    }
+
+    /// Error code and span of a rejected query.
+    fn rejected(input: &str) -> (&'static str, (usize, usize)) {
+        let err = parse(input, TODAY).unwrap_err();
+        (err.code, (err.span.start, err.span.end))
+    }
+
+    #[test]
+    fn parses_terms_and_fields() {
+        assert_eq!(
+            terms(r#"Foo tag:#Infra done:no -"exact \"phrase\"" cat:"My List" color:#FF0000"#),
+            [
+                (false, Term::Text("foo".to_string())),
+                (false, Term::Tag("infra".to_string())),
+                (false, Term::Done(false)),
+                (true, Term::Text("exact \"phrase\"".to_string())),
+                (false, Term::Category("My List".to_string())),
+                (false, Term::Color("#FF0000".to_string())),
+            ]
+        );
+        assert!(terms("   ").is_empty());
+    }
+
+    #[test]
+    fn parses_dates_and_ranges() {
+        assert_eq!(
+            terms("date:>=today-7 date:2026-01-01 date:<week_start"),
+            [
+                (false, Term::Date(Cmp::Ge, "2026-01-08".to_string())),
+                (false, Term::Date(Cmp::Eq, "2026-01-01".to_string())),
+                (false, Term::Date(Cmp::Lt, "2026-01-12".to_string())),
+            ]
+        );
+        assert_eq!(
+            terms("date:2026-01-01..today"),
+            [(
+                false,
+                Term::DateRange("2026-01-01".to_string(), TODAY.to_string())
+            )]
+        );
+        // The span covers the whole value, not just the bad bound.
+        assert_eq!(rejected("date:2026-01-01..soon"), ("invalid_date", (5, 21)));
+        assert_eq!(rejected("date:..today"), ("invalid_date", (5, 12)));
+    }
+
+    #[test]
+    fn clause_spans_cover_each_term() {
+        let spans: Vec<(usize, usize)> = parse(r#"a  -tag:x "b c""#, TODAY)
+            .unwrap()
+            .clauses
+            .iter()
+            .map(|clause| (clause.span.start, clause.span.end))
+            .collect();
+        assert_eq!(spans, [(0, 1), (3, 9), (10, 15)]);
+    }
+
+    #[test]
+    fn errors_point_at_the_offending_text() {
+        assert_eq!(rejected("a nope:x"), ("unknown_field", (2, 6)));
+        assert_eq!(rejected(r#"title:"abc"#), ("unterminated_quote", (6, 10)));
+        assert_eq!(rejected(r#"a "b\""#), ("unterminated_quote", (2, 6)));
+        assert_eq!(rejected("-"), ("missing_term", (0, 1)));
+        assert_eq!(rejected("a - b"), ("missing_term", (2, 3)));
+        assert_eq!(rejected(r#"a "" b"#), ("missing_term", (2, 4)));
+        assert_eq!(rejected("tag: x"), ("missing_value", (0, 4)));
+        assert_eq!(rejected("done:maybe"), ("invalid_value", (5, 10)));
+        assert_eq!(rejected("tag:##"), ("invalid_value", (4, 6)));
+        assert_eq!(rejected(r#""a"b"#), ("unexpected_character", (0, 4)));
+    }
+
+    #[test]
+    fn spans_are_utf16_offsets() {
+        // Hangul is one UTF-16 unit per char; the emoji is two.
+        assert_eq!(rejected("한글 nope:x"), ("unknown_field", (3, 7)));
+        assert_eq!(rejected("😀 nope:x"), ("unknown_field", (3, 7)));
+        let clause = &parse("😀 a", TODAY).unwrap().clauses[1];
+        assert_eq!((clause.span.start, clause.span.end), (3, 4));
+    }
+
+    #[test]
+    fn negation_compiles_to_not() {
+        let compiled = compile(&parse("-tag:x title:y", TODAY).unwrap());
+        assert!(compiled.condition.starts_with("memos.archived_at IS NULL"));
+        assert!(compiled
+            .condition
+            .contains("AND NOT (memo_has_tag(memos.title, memos.content_md, ?1))"));
+        assert!(compiled
+            .condition
+            .ends_with("AND (instr(fold(memos.title), ?2) > 0)"));
+        assert_eq!(
+            compiled.params,
+            [Value::Text("x".to_string()), Value::Text("y".to_string())]
+        );
+    }
+
+    #[test]
+    fn archived_terms_lift_the_default_filter() {
+        let compiled = compile(&parse("-archived:true", TODAY).unwrap());
+        assert!(compiled
+            .condition
+            .starts_with("NOT ((memos.archived_at IS NOT NULL"));
+        assert_eq!(compiled.params, [Value::Integer(1)]);
+
+        let compiled = compile(&Query::default());
+        assert!(compiled.condition.starts_with("memos.archived_at IS NULL"));
+        assert!(compiled.params.is_empty());
+    }
+}
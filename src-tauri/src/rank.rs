@@ -0,0 +1,277 @@
+// Fractional ordering keys.
+//
+// Categories and memos are ordered by a `rank` string compared bytewise
+// (SQLite's default BINARY collation). Inserting between two items only needs
+// a key that sorts between their keys, so a move rewrites a single row.
+// Keys never end in the lowest digit, which guarantees there is always room
+// below any key.
+
+const DIGITS: &[u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+const BASE: usize = DIGITS.len();
+
+/// Lists whose longest key exceeds this are rewritten by the rebalancer.
+pub const REBALANCE_LEN: usize = 12;
+
+fn digit(c: u8) -> usize {
+    DIGITS.iter().position(|d| *d == c).unwrap_or(0)
+}
+
+/// Digits strictly between `a` (or the minimum when empty) and `b` (or the
+/// maximum when `None`). Requires `a < b` and no trailing zero digits.
+fn midpoint(a: &[usize], b: Option<&[usize]>) -> Vec<usize> {
+    if let Some(b) = b {
+        let shared = b
+            .iter()
+            .enumerate()
+            .take_while(|(i, d)| a.get(*i).copied().unwrap_or(0) == **d)
+            .count();
+        if shared > 0 {
+            let mut out = b[..shared].to_vec();
+            out.extend(midpoint(a.get(shared..).unwrap_or(&[]), Some(&b[shared..])));
+            return out;
+        }
+    }
+
+    let da = a.first().copied().unwrap_or(0);
+    let db = b.and_then(|b| b.first().copied()).unwrap_or(BASE);
+    if db > da + 1 {
+        return vec![(da + db).div_ceil(2)];
+    }
+    match b {
+        Some(b) if b.len() > 1 => vec![b[0]],
+        _ => {
+            let mut out = vec![da];
+            out.extend(midpoint(a.get(1..).unwrap_or(&[]), None));
+            out
+        }
+    }
+}
+
+/// A key that sorts strictly between `lower` and `upper`; `None` means open
+/// on that side. Invalid characters are treated as the lowest digit.
+pub fn rank_between(lower: Option<&str>, upper: Option<&str>) -> String {
+    let a: Vec<usize> = lower.unwrap_or("").bytes().map(digit).collect();
+    let b: Option<Vec<usize>> = upper.map(|u| u.bytes().map(digit).collect());
+    let b = b.filter(|b| !b.is_empty() && *b > a);
+    midpoint(&a, b.as_deref())
+        .into_iter()
+        .map(|d| DIGITS[d] as char)
+        .collect()
+}
+
+/// `n` evenly spaced, increasing keys of the shortest length that fits.
+pub fn spread(n: usize) -> Vec<String> {
+    let mut len = 1u32;
+    while (BASE as u128).pow(len) <= (n as u128) + 1 {
+        len += 1;
+    }
+    let step = (BASE as u128).pow(len) / (n as u128 + 1);
+
+    (1..=n as u128)
+        .map(|i| {
+            let mut value = i * step;
+            let mut buf = vec![b'0'; len as usize];
+            for slot in buf.iter_mut().rev() {
+                *slot = DIGITS[(value % BASE as u128) as usize];
+                value /= BASE as u128;
+            }
+            while buf.len() > 1 && buf.last() == Some(&b'0') {
+                buf.pop();
+            }
+            String::from_utf8(buf).unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Given the current ranks of a list in its new order, picks the fewest
+/// items to re-rank so the list sorts correctly again: items on a longest
+/// increasing run keep their rank, the rest get keys between their new
+/// neighbours. Returns `(index, new_rank)` pairs.
+pub fn reassign(ranks: &[&str]) -> Vec<(usize, String)> {
+    // Longest strictly increasing subsequence (patience sorting).
+    let mut tails: Vec<usize> = Vec::new();
+    let mut prev: Vec<Option<usize>> = vec![None; ranks.len()];
+    for (i, rank) in ranks.iter().enumerate() {
+        let slot = tails.partition_point(|&t| ranks[t] < *rank);
+        prev[i] = slot.checked_sub(1).map(|s| tails[s]);
+        if slot == tails.len() {
+            tails.push(i);
+        } else {
+            tails[slot] = i;
+        }
+    }
+    let mut keep = vec![false; ranks.len()];
+    let mut cursor = tails.last().copied();
+    while let Some(i) = cursor {
+        keep[i] = true;
+        cursor = prev[i];
+    }
+
+    let mut out = Vec::new();
+    let mut lower: Option<String> = None;
+    for i in 0..ranks.len() {
+        if keep[i] {
+            lower = Some(ranks[i].to_string());
+            continue;
+        }
+        let upper = (i + 1..ranks.len()).find(|&j| keep[j]).map(|j| ranks[j]);
+        let rank = rank_between(lower.as_deref(), upper);
+        lower = Some(rank.clone());
+        out.push((i, rank));
+    }
+    out
+}
+
+/// Whether `rank` is a key this module could have produced: non-empty,
+/// only `DIGITS`, and not ending in the lowest digit.
+pub fn is_valid(rank: &str) -> bool {
+    !rank.is_empty() && rank.bytes().all(|c| DIGITS.contains(&c)) && !rank.ends_with('0')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_between(lower: Option<&str>, upper: Option<&str>) -> String {
+        let rank = rank_between(lower, upper);
+        assert!(
+            is_valid(&rank),
+            "{lower:?}..{upper:?} gave invalid {rank:?}"
+        );
+        if let Some(lower) = lower {
+            assert!(lower < rank.as_str(), "{lower:?} !< {rank:?}");
+        }
+        if let Some(upper) = upper {
+            assert!(rank.as_str() < upper, "{rank:?} !< {upper:?}");
+        }
+        rank
+    }
+
+    /// Applies `reassign` and checks the result sorts and keeps the rest.
+    fn apply(ranks: &[&str]) -> (Vec<String>, usize) {
+        let changes = reassign(ranks);
+        let mut out: Vec<String> = ranks.iter().map(|r| r.to_string()).collect();
+        for (i, rank) in &changes {
+            out[*i] = rank.clone();
+        }
+        assert!(out.windows(2).all(|w| w[0] < w[1]), "{ranks:?} -> {out:?}");
+        assert!(out.iter().all(|r| is_valid(r)));
+        (out, changes.len())
+    }
+
+    #[test]
+    fn between_open_ends() {
+        assert_eq!(rank_between(None, None), "V");
+        assert_between(None, Some("V"));
+        assert_between(Some("V"), None);
+    }
+
+    #[test]
+    fn between_at_alphabet_edges() {
+        assert_eq!(assert_between(None, Some("1")), "0V");
+        assert_eq!(assert_between(None, Some("01")), "00V");
+        assert_eq!(assert_between(Some("z"), None), "zV");
+        assert_eq!(assert_between(Some("zzz"), None), "zzzV");
+        assert_between(Some("y"), Some("z"));
+        assert_between(Some("yz"), Some("z"));
+        assert_between(Some("zy"), Some("zz"));
+    }
+
+    #[test]
+    fn adjacent_ranks_get_longer() {
+        assert_eq!(assert_between(Some("a"), Some("b")), "aV");
+        assert_eq!(assert_between(Some("a"), Some("a1")), "a0V");
+        assert_between(Some("a"), Some("aV"));
+        assert_between(Some("aV"), Some("b"));
+        assert_between(Some("a1"), Some("a2"));
+    }
+
+    #[test]
+    fn repeated_inserts_stay_ordered() {
+        // Always inserting at the same spot is the worst case for key length.
+        let mut first = rank_between(None, None);
+        let mut last = first.clone();
+        for _ in 0..200 {
+            first = assert_between(None, Some(&first));
+            last = assert_between(Some(&last), None);
+        }
+        let (mut lower, upper) = ("a".to_string(), "b".to_string());
+        for _ in 0..200 {
+            lower = assert_between(Some(&lower), Some(&upper));
+        }
+    }
+
+    #[test]
+    fn bad_bounds_are_ignored() {
+        // An upper bound at or below the lower one is treated as open.
+        assert_between(Some("b"), None);
+        let rank = rank_between(Some("b"), Some("a"));
+        assert!(is_valid(&rank) && rank.as_str() > "b");
+        assert!(is_valid(&rank_between(Some("b"), Some(""))));
+    }
+
+    #[test]
+    fn spread_is_short_and_increasing() {
+        assert!(spread(0).is_empty());
+        // One digit covers up to 60 keys, two up to 62 * 62 - 2.
+        for (n, max_len) in [
+            (1, 1),
+            (2, 1),
+            (60, 1),
+            (62, 2),
+            (1000, 2),
+            (3842, 2),
+            (5000, 3),
+        ] {
+            let ranks = spread(n);
+            assert_eq!(ranks.len(), n);
+            assert!(ranks.windows(2).all(|w| w[0] < w[1]), "n = {n}");
+            assert!(ranks.iter().all(|r| is_valid(r)), "n = {n}");
+            let longest = ranks.iter().map(String::len).max().unwrap();
+            assert!(longest <= max_len, "n = {n}: {longest}");
+        }
+        assert_eq!(spread(1), ["V"]);
+    }
+
+    #[test]
+    fn reassign_keeps_a_sorted_list() {
+        assert!(reassign(&[]).is_empty());
+        assert!(reassign(&["1", "2", "3"]).is_empty());
+    }
+
+    #[test]
+    fn reassign_moves_only_what_it_must() {
+        // One item moved to the front or back: only that item changes.
+        let (out, changed) = apply(&["5", "1", "2", "3", "4"]);
+        assert_eq!(changed, 1);
+        assert_eq!(out[1..], ["1", "2", "3", "4"]);
+        let (out, changed) = apply(&["2", "3", "4", "5", "1"]);
+        assert_eq!(changed, 1);
+        assert_eq!(out[..4], ["2", "3", "4", "5"]);
+
+        // Two swapped neighbours in a longer list.
+        let (_, changed) = apply(&["1", "2", "4", "3", "5", "6"]);
+        assert_eq!(changed, 1);
+
+        // Reversed: only one item can keep its rank.
+        let (_, changed) = apply(&["4", "3", "2", "1"]);
+        assert_eq!(changed, 3);
+    }
+
+    #[test]
+    fn reassign_separates_equal_ranks() {
+        let (_, changed) = apply(&["a", "a", "a"]);
+        assert_eq!(changed, 2);
+        let (_, changed) = apply(&["1", "z", "z", "2"]);
+        assert_eq!(changed, 2);
+    }
+
+    #[test]
+    fn validity() {
+        assert!(is_valid("V"));
+        assert!(is_valid("a0V"));
+        assert!(!is_valid(""));
+        assert!(!is_valid("a0"));
+        assert!(!is_valid("a-b"));
+    }
+}
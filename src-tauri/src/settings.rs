@@ -0,0 +1,213 @@
+// Typed app settings on top of the key-value `settings` table.
+//
+// Every setting is declared once in `SETTINGS` with its key, type and
+// default. Values are stored as JSON text; a missing or unreadable row means
+// the default. `AppSettings` is the typed view the frontend gets back.
+
+use crate::error::{AppError, AppResult, FieldError};
+use crate::models::AppSettings;
+use crate::validation::is_valid_color;
+use rusqlite::{params, Connection};
+use serde_json::{Map, Value};
+
+pub enum SettingType {
+    /// A color as accepted by the color fields; empty means "app default".
+    Color,
+    Bool,
+    Int {
+        min: i64,
+        max: i64,
+    },
+    Choice(&'static [&'static str]),
+    /// Id of an existing category, or null.
+    CategoryId,
+    /// Secret of `TOKEN_LEN` lowercase hex digits; empty means "not set yet".
+    Token,
+}
+
+pub const TOKEN_LEN: usize = 64;
+
+pub struct SettingDef {
+    pub key: &'static str,
+    pub ty: SettingType,
+    /// JSON literal.
+    pub default: &'static str,
+}
+
+pub const SETTINGS: &[SettingDef] = &[
+    SettingDef {
+        key: "background_color",
+        ty: SettingType::Color,
+        default: r#""""#,
+    },
+    SettingDef {
+        key: "theme",
+        ty: SettingType::Choice(&["system", "light", "dark"]),
+        default: r#""system""#,
+    },
+    SettingDef {
+        key: "font_size",
+        ty: SettingType::Int { min: 10, max: 32 },
+        default: "14",
+    },
+    SettingDef {
+        key: "default_category_id",
+        ty: SettingType::CategoryId,
+        default: "null",
+    },
+    SettingDef {
+        key: "editor_spellcheck",
+        ty: SettingType::Bool,
+        default: "true",
+    },
+    SettingDef {
+        key: "http_api_enabled",
+        ty: SettingType::Bool,
+        default: "false",
+    },
+    SettingDef {
+        key: "http_api_port",
+        ty: SettingType::Int {
+            min: 1024,
+            max: 65535,
+        },
+        default: "27183",
+    },
+    SettingDef {
+        key: "http_api_token",
+        ty: SettingType::Token,
+        default: r#""""#,
+    },
+];
+
+fn find(key: &str) -> Option<&'static SettingDef> {
+    SETTINGS.iter().find(|def| def.key == key)
+}
+
+/// Whether `key` belongs in an exported settings file. Tokens are secrets of
+/// this install, so they are neither exported nor imported.
+pub fn is_exportable(key: &str) -> bool {
+    find(key).is_none_or(|def| !matches!(def.ty, SettingType::Token))
+}
+
+fn default_value(def: &SettingDef) -> Value {
+    serde_json::from_str(def.default).unwrap_or(Value::Null)
+}
+
+fn check(conn: &Connection, def: &SettingDef, value: &Value) -> Result<(), (&'static str, String)> {
+    let ok = match (&def.ty, value) {
+        (SettingType::Color, Value::String(s)) => s.is_empty() || is_valid_color(s),
+        (SettingType::Bool, Value::Bool(_)) => true,
+        (SettingType::Int { min, max }, Value::Number(n)) => {
+            n.as_i64().is_some_and(|n| (*min..=*max).contains(&n))
+        }
+        (SettingType::Choice(choices), Value::String(s)) => choices.contains(&s.as_str()),
+        (SettingType::Token, Value::String(s)) => {
+            s.is_empty()
+                || (s.len() == TOKEN_LEN
+                    && s.chars()
+                        .all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c)))
+        }
+        (SettingType::CategoryId, Value::Null) => true,
+        (SettingType::CategoryId, Value::String(id)) => {
+            let exists = conn
+                .query_row(
+                    "SELECT EXISTS(SELECT 1 FROM categories WHERE id = ?1)",
+                    params![id],
+                    |row| row.get::<_, bool>(0),
+                )
+                .unwrap_or(false);
+            if !exists {
+                return Err((
+                    "unknown_category",
+                    "존재하지 않는 카테고리입니다.".to_string(),
+                ));
+            }
+            true
+        }
+        _ => false,
+    };
+    if ok {
+        return Ok(());
+    }
+    let expected = match &def.ty {
+        SettingType::Color => "색상은 #hex, rgb(), 또는 프리셋 이름이어야 합니다.".to_string(),
+        SettingType::Bool => "참/거짓 값이어야 합니다.".to_string(),
+        SettingType::Int { min, max } => format!("{min}~{max} 사이의 정수여야 합니다."),
+        SettingType::Choice(choices) => format!("{} 중 하나여야 합니다.", choices.join(", ")),
+        SettingType::CategoryId => "카테고리 id 또는 null이어야 합니다.".to_string(),
+        SettingType::Token => format!("{TOKEN_LEN}자리 16진수 토큰이어야 합니다."),
+    };
+    Err(("invalid_setting", expected))
+}
+
+/// Current settings, with defaults for anything unset or unreadable.
+pub fn load(conn: &Connection) -> AppResult<AppSettings> {
+    let mut stmt = conn.prepare("SELECT key, value FROM settings")?;
+    let stored: Vec<(String, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+
+    let mut values = Map::new();
+    for def in SETTINGS {
+        let value = stored
+            .iter()
+            .find(|(key, _)| key == def.key)
+            .and_then(|(_, raw)| serde_json::from_str::<Value>(raw).ok())
+            .filter(|value| check(conn, def, value).is_ok())
+            .unwrap_or_else(|| default_value(def));
+        values.insert(def.key.to_string(), value);
+    }
+
+    serde_json::from_value(Value::Object(values))
+        .map_err(|e| AppError::Internal(format!("settings don't match AppSettings: {e}")))
+}
+
+/// Validates and saves `values`; `null` resets a setting to its default.
+/// Unknown keys are an error unless `skip_unknown` (used by import, so a
+/// file from a newer version still loads). Nothing is written on error.
+pub fn save(conn: &Connection, values: &Map<String, Value>, skip_unknown: bool) -> AppResult<()> {
+    let mut errors = Vec::new();
+    let mut writes = Vec::new();
+    for (key, value) in values {
+        let Some(def) = find(key) else {
+            if !skip_unknown {
+                errors.push(FieldError {
+                    field: key.clone(),
+                    code: "unknown_setting".to_string(),
+                    message: "알 수 없는 설정입니다.".to_string(),
+                    span: None,
+                });
+            }
+            continue;
+        };
+        if value.is_null() {
+            writes.push((def.key, None));
+            continue;
+        }
+        match check(conn, def, value) {
+            Ok(()) => writes.push((def.key, Some(value.to_string()))),
+            Err((code, message)) => errors.push(FieldError {
+                field: key.clone(),
+                code: code.to_string(),
+                message,
+                span: None,
+            }),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(AppError::Validation(errors));
+    }
+
+    for (key, raw) in writes {
+        match raw {
+            Some(raw) => conn.execute(
+                "INSERT INTO settings(key, value) VALUES(?1, ?2)
+                 ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+                params![key, raw],
+            )?,
+            None => conn.execute("DELETE FROM settings WHERE key = ?1", params![key])?,
+        };
+    }
+    Ok(())
+}
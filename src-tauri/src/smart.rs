@@ -0,0 +1,151 @@
+// Saved searches shown on the board as read-only "smart" categories.
+//
+// A filter is stored as JSON in `smart_categories.filter` and evaluated
+// against the board's memos every time it loads, so members are always
+// current and never copied. Date bounds may be relative to today
+// (`today-1`, `week_start`), which keeps cards like "overdue" or "this week"
+// from going stale.
+
+use crate::models::{CategoryKind, CategoryWithMemos, Memo, SmartFilter};
+use crate::stats;
+use crate::validation::is_valid_date_ymd;
+
+/// Largest `+N`/`-N` offset a relative date bound may carry.
+const MAX_OFFSET_DAYS: i64 = 36_500;
+
+/// Resolves a date bound to `YYYY-MM-DD`. A bound is either a date or one of
+/// `today`, `week_start`, `week_end`, `month_start`, `month_end` (weeks start
+/// on Monday), optionally followed by `+N` or `-N` days.
+pub fn resolve_date(bound: &str, today: &str) -> Option<String> {
+    let bound = bound.trim();
+    if is_valid_date_ymd(bound) {
+        return Some(bound.to_string());
+    }
+    let (anchor, offset) = bound.split_at(bound.find(['+', '-']).unwrap_or(bound.len()));
+    let offset: i64 = if offset.is_empty() {
+        0
+    } else {
+        offset.parse().ok()?
+    };
+    if offset.abs() > MAX_OFFSET_DAYS {
+        return None;
+    }
+
+    let today = stats::day_number(today)?;
+    // 1970-01-01 was a Thursday, three days after a Monday.
+    let week_start = today - (today + 3).rem_euclid(7);
+    let month_start = || {
+        let ymd = stats::date_ymd(today);
+        stats::day_number(&format!("{}-01", &ymd[..7]))
+    };
+    let day = match anchor {
+        "today" => today,
+        "week_start" => week_start,
+        "week_end" => week_start + 6,
+        "month_start" => month_start()?,
+        // 31 days after the 1st is always within the next month.
+        "month_end" => {
+            let next = stats::date_ymd(month_start()? + 31);
+            stats::day_number(&format!("{}-01", &next[..7]))? - 1
+        }
+        _ => return None,
+    };
+    Some(stats::date_ymd(day + offset))
+}
+
+/// `#tag` → `tag`, lowercased.
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim().trim_start_matches('#').to_lowercase()
+}
+
+/// Tags in `text`: a word starting with `#` followed by letters, digits, `_`
+/// or `-`, with at least one letter (so `#1` isn't a tag). Lowercased.
+pub fn tags(text: &str) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        let Some(rest) = word.strip_prefix('#') else {
+            continue;
+        };
+        let tag: String = rest
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '-')
+            .collect();
+        if tag.chars().any(char::is_alphabetic) {
+            let tag = tag.to_lowercase();
+            if !out.contains(&tag) {
+                out.push(tag);
+            }
+        }
+    }
+    out
+}
+
+/// Memos on the board (active categories only) that match `filter`, ordered
+/// by date and then by board order, with `position` set to their index in
+/// the smart category.
+pub fn members(filter: &SmartFilter, today: &str, board: &[CategoryWithMemos]) -> Vec<Memo> {
+    let from = filter
+        .date_from
+        .as_deref()
+        .and_then(|b| resolve_date(b, today));
+    let to = filter
+        .date_to
+        .as_deref()
+        .and_then(|b| resolve_date(b, today));
+    let wanted_tags: Vec<String> = filter.tags.iter().map(|t| normalize_tag(t)).collect();
+    let needle = filter
+        .text
+        .as_deref()
+        .map(|t| t.trim().to_lowercase())
+        .filter(|t| !t.is_empty());
+
+    let mut out: Vec<Memo> = Vec::new();
+    for item in board.iter().filter(|item| !item.category.archived) {
+        let category = &item.category;
+        if !filter.category_ids.is_empty() && !filter.category_ids.contains(&category.id) {
+            continue;
+        }
+        for memo in &item.memos {
+            if let Some(done) = filter.done {
+                if category.kind != CategoryKind::Todo || memo.todo_done != done {
+                    continue;
+                }
+            }
+            if !filter.colors.is_empty()
+                && !filter
+                    .colors
+                    .iter()
+                    .any(|c| c.trim().eq_ignore_ascii_case(&memo.color))
+            {
+                continue;
+            }
+            if from
+                .as_deref()
+                .is_some_and(|from| memo.date_ymd.as_str() < from)
+                || to.as_deref().is_some_and(|to| memo.date_ymd.as_str() > to)
+            {
+                continue;
+            }
+            if !wanted_tags.is_empty() || needle.is_some() {
+                let text = format!("{} {}", memo.title, stats::plain_text(&memo.content_md));
+                let memo_tags = tags(&text);
+                if !wanted_tags.iter().all(|tag| memo_tags.contains(tag)) {
+                    continue;
+                }
+                if needle
+                    .as_deref()
+                    .is_some_and(|needle| !text.to_lowercase().contains(needle))
+                {
+                    continue;
+                }
+            }
+            out.push(memo.clone());
+        }
+    }
+
+    out.sort_by(|a, b| a.date_ymd.cmp(&b.date_ymd));
+    for (i, memo) in out.iter_mut().enumerate() {
+        memo.position = i as i64;
+    }
+    out
+}
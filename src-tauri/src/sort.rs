@@ -0,0 +1,79 @@
+// Per-category memo ordering for display.
+//
+// Sorting never touches `rank`, so a category switched back to manual order
+// gets its old arrangement back. Title order compares composed Hangul
+// syllables, which are already in 가나다 order in Unicode; titles typed as
+// decomposed jamo (common on macOS) are composed first.
+
+use crate::models::{Memo, SortMode};
+use std::cmp::Ordering;
+
+const JAMO_L: u32 = 0x1100;
+const JAMO_V: u32 = 0x1161;
+const JAMO_T: u32 = 0x11A7;
+const SYLLABLE_BASE: u32 = 0xAC00;
+const L_COUNT: u32 = 19;
+const V_COUNT: u32 = 21;
+const T_COUNT: u32 = 28;
+
+/// Syllable formed by conjoining jamo `next` onto `prev` (L+V or LV+T).
+fn compose(prev: char, next: char) -> Option<char> {
+    let (prev, next) = (prev as u32, next as u32);
+    if (JAMO_L..JAMO_L + L_COUNT).contains(&prev) && (JAMO_V..JAMO_V + V_COUNT).contains(&next) {
+        let index = ((prev - JAMO_L) * V_COUNT + (next - JAMO_V)) * T_COUNT;
+        return char::from_u32(SYLLABLE_BASE + index);
+    }
+    let index = prev.checked_sub(SYLLABLE_BASE)?;
+    if index < L_COUNT * V_COUNT * T_COUNT
+        && index % T_COUNT == 0
+        && (JAMO_T + 1..JAMO_T + T_COUNT).contains(&next)
+    {
+        return char::from_u32(prev + (next - JAMO_T));
+    }
+    None
+}
+
+/// Lowercased title with conjoining jamo composed into syllables.
+fn title_key(title: &str) -> Vec<char> {
+    let mut out: Vec<char> = Vec::with_capacity(title.len());
+    for c in title.trim().chars().flat_map(char::to_lowercase) {
+        if let Some(last) = out.last_mut() {
+            if let Some(composed) = compose(*last, c) {
+                *last = composed;
+                continue;
+            }
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn compare_titles(a: &str, b: &str) -> Ordering {
+    let (ka, kb) = (title_key(a), title_key(b));
+    // Untitled memos go last.
+    ka.is_empty().cmp(&kb.is_empty()).then_with(|| ka.cmp(&kb))
+}
+
+/// Orders `memos` (given in rank order) for display. Pinned memos stay on
+/// top in rank order; ties keep rank order.
+pub fn sort_memos(memos: &mut [Memo], mode: SortMode, done_last: bool) {
+    memos.sort_by(|a, b| {
+        let pinned = b.pinned.cmp(&a.pinned);
+        if pinned != Ordering::Equal || a.pinned {
+            return pinned;
+        }
+        let done = if done_last {
+            a.todo_done.cmp(&b.todo_done)
+        } else {
+            Ordering::Equal
+        };
+        done.then_with(|| match mode {
+            SortMode::Manual => Ordering::Equal,
+            SortMode::DateAsc => a.date_ymd.cmp(&b.date_ymd),
+            SortMode::DateDesc => b.date_ymd.cmp(&a.date_ymd),
+            SortMode::Title => compare_titles(&a.title, &b.title),
+            SortMode::Updated => b.updated_at.cmp(&a.updated_at),
+            SortMode::Created => b.created_at.cmp(&a.created_at),
+        })
+    });
+}
@@ -0,0 +1,125 @@
+// Text counts and date arithmetic for `get_statistics`.
+//
+// Memo bodies are TipTap HTML, so tags are stripped before counting. Korean
+// separates words (eojeol) with spaces like English does, so a word is any
+// whitespace-separated run with a letter or digit in it. Characters are
+// counted as graphemes, which keeps decomposed Hangul (jamo sequences) at one
+// per syllable.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Inline tags don't separate words: `he<b>ll</b>o` is one word.
+const INLINE_TAGS: &[&str] = &[
+    "a", "b", "code", "em", "i", "mark", "s", "span", "strong", "sub", "sup", "u",
+];
+
+/// Visible text of a TipTap HTML body.
+pub fn plain_text(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(open) = rest.find('<') {
+        push_decoded(&mut out, &rest[..open]);
+        let Some(close) = rest[open..].find('>') else {
+            rest = &rest[open..];
+            break;
+        };
+        let tag = rest[open + 1..open + close]
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        if !INLINE_TAGS.contains(&tag.as_str()) {
+            out.push(' ');
+        }
+        rest = &rest[open + close + 1..];
+    }
+    push_decoded(&mut out, rest);
+    out
+}
+
+fn push_decoded(out: &mut String, text: &str) {
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let entity = rest[amp..].find(';').map(|end| &rest[amp..=amp + end]);
+        let decoded = match entity {
+            Some("&nbsp;") => Some(' '),
+            Some("&amp;") => Some('&'),
+            Some("&lt;") => Some('<'),
+            Some("&gt;") => Some('>'),
+            Some("&quot;") => Some('"'),
+            Some("&#39;") | Some("&apos;") => Some('\''),
+            _ => None,
+        };
+        match (decoded, entity) {
+            (Some(c), Some(entity)) => {
+                out.push(c);
+                rest = &rest[amp + entity.len()..];
+            }
+            _ => {
+                out.push('&');
+                rest = &rest[amp + 1..];
+            }
+        }
+    }
+    out.push_str(rest);
+}
+
+/// `(words, characters)` of plain text; whitespace isn't a character.
+pub fn count_text(text: &str) -> (i64, i64) {
+    let words = text
+        .split_whitespace()
+        .filter(|w| w.chars().any(char::is_alphanumeric))
+        .count();
+    let chars = text
+        .graphemes(true)
+        .filter(|g| !g.chars().all(char::is_whitespace))
+        .count();
+    (words as i64, chars as i64)
+}
+
+/// Days since 1970-01-01 for a `YYYY-MM-DD` date.
+pub fn day_number(date_ymd: &str) -> Option<i64> {
+    let mut parts = date_ymd.splitn(3, '-').map(|p| p.parse::<i64>().ok());
+    let (Some(Some(y)), Some(Some(m)), Some(Some(d))) = (parts.next(), parts.next(), parts.next())
+    else {
+        return None;
+    };
+    // Howard Hinnant's days_from_civil.
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    Some(era * 146_097 + doe - 719_468)
+}
+
+/// Inverse of `day_number`.
+pub fn date_ymd(day: i64) -> String {
+    let z = day + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    format!("{y:04}-{m:02}-{d:02}")
+}
+
+/// Length of the longest run of consecutive days in `days` (sorted, unique).
+pub fn longest_streak(days: &[i64]) -> i64 {
+    let mut best = 0;
+    let mut run = 0;
+    for (i, day) in days.iter().enumerate() {
+        run = if i > 0 && days[i - 1] + 1 == *day {
+            run + 1
+        } else {
+            1
+        };
+        best = best.max(run);
+    }
+    best
+}
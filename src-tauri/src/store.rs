@@ -0,0 +1,3705 @@
+// The database layer: `Store` owns a connection and has one method per
+// operation, with its validation and SQL. The Tauri commands, the HTTP API,
+// the MCP server and the command-line tool all go through it, and it runs
+// without Tauri, so it can be tested on an in-memory database.
+
+use crate::db::{
+    self, get_memo_category, get_memo_revision, next_rank, now_timestamp_ms, record_memo_revision,
+    today_ymd, write_spread_ranks,
+};
+use crate::error::{AppError, AppResult, Entity, FieldError};
+use crate::merge::merge_memo;
+use crate::models::{
+    AppSettings, Archive, BulkItemResult, BulkReport, Category, CategoryKind, CategoryStats,
+    CategoryWithMemos, CreateCategoryInput, CreateMemoFromTemplateInput, CreateMemoInput,
+    CreateSmartCategoryInput, DateRangeInput, DayCount, DeleteMemosInput, DuplicateCategoryInput,
+    DuplicateMemoInput, IntegrityIssue, IntegrityIssueKind, IntegrityReport, ListArchiveInput,
+    Memo, MemoRevision, MemoSlot, MonthInput, MoveMemoInput, MoveMemoToInput, MoveMemosInput,
+    OpenJournalEntryInput, QueryMemosInput, RecentlyCompletedInput, ReorderCategoriesInput,
+    ReorderMemosInput, RepairAction, RepairChange, RepairReport, SaveMemoAsTemplateInput,
+    SetBackgroundColorInput, SetCategoryArchivedInput, SetCategoryAutoArchiveInput,
+    SetCategoryCollapsedInput, SetCategoryDefaultTemplateInput, SetCategorySortInput,
+    SetMemoArchivedInput, SetMemoPinnedInput, SetMemoStarredInput, SetMemosColorInput,
+    SetMemosDateInput, SetMemosTodoDoneInput, SettingsFile, SmartCategory, SmartFilter, SortMode,
+    Statistics, StatisticsInput, StatsSummary, Template, TodoDayCount, UpdateCategoryInput,
+    UpdateCategoryResult, UpdateMemoInput, UpdateMemoResult, UpdateSettingsInput,
+    UpdateSmartCategoryInput, WeekActivity,
+};
+use crate::query;
+use crate::rank;
+use crate::settings;
+use crate::smart;
+use crate::sort;
+use crate::stats;
+use crate::template;
+use crate::validation::{is_valid_date_ymd, Validate, TITLE_MAX_CHARS};
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use uuid::Uuid;
+
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    /// Opens the database file and brings it up to date (`db::open_db`).
+    pub fn open(path: &Path) -> AppResult<Store> {
+        Ok(Store {
+            conn: db::open_db(path)?,
+        })
+    }
+
+    /// A new, empty database that lives as long as the store.
+    pub fn open_in_memory() -> AppResult<Store> {
+        let conn = Connection::open_in_memory()?;
+        db::prepare(&conn)?;
+        Ok(Store { conn })
+    }
+
+    /// The raw connection, for maintenance that isn't an operation of its own.
+    pub fn conn(&self) -> &Connection {
+        &self.conn
+    }
+
+    pub fn get_memo(&self, id: &str) -> AppResult<Memo> {
+        fetch_memo(&self.conn, id)
+    }
+}
+
+// `position` is no longer stored; it is the row's index in `rank` order,
+// kept in the API so callers can keep sorting by it. Rows are numbered among
+// siblings in the same archived state, so live categories and memos count
+// from 0 regardless of what has been archived, and listings of a sorted
+// category overwrite a memo's `position` with its display index.
+//
+// The index comes from one window pass over the table rather than a count
+// per row. `memo_select!` takes an optional filter for that pass; it may
+// only narrow by `category_id` or archived state, which never changes the
+// numbering within a partition.
+macro_rules! category_select {
+    () => {
+        "SELECT id, emoji, title, color, position,
+                rank, archived, kind, is_collapsed, default_template_id, sort_mode, done_last,
+                auto_archive_days, version, created_at, updated_at
+         FROM (SELECT *, ROW_NUMBER() OVER (PARTITION BY archived ORDER BY rank) - 1 AS position
+               FROM categories) AS categories"
+    };
+}
+
+macro_rules! memo_select {
+    () => {
+        memo_select!("")
+    };
+    ($scope:literal) => {
+        concat!(
+            "SELECT id, category_id, emoji, title, color, date_ymd, content_md, todo_done,
+                    completed_at, archived_at, pinned, starred, position,
+                    rank, version, created_at, updated_at
+             FROM (SELECT *, ROW_NUMBER() OVER (
+                       PARTITION BY category_id, archived_at IS NULL ORDER BY rank
+                   ) - 1 AS position
+                   FROM memos ",
+            $scope,
+            ") AS memos"
+        )
+    };
+}
+
+macro_rules! smart_category_select {
+    () => {
+        "SELECT id, emoji, title, color, filter, position, rank, created_at, updated_at
+         FROM (SELECT *, ROW_NUMBER() OVER (ORDER BY rank) - 1 AS position
+               FROM smart_categories) AS smart_categories"
+    };
+}
+
+fn row_to_category(row: &rusqlite::Row<'_>) -> rusqlite::Result<Category> {
+    let kind: CategoryKind = row.get(7)?;
+    Ok(Category {
+        id: row.get(0)?,
+        emoji: row.get(1)?,
+        title: row.get(2)?,
+        color: row.get(3)?,
+        position: row.get(4)?,
+        rank: row.get(5)?,
+        archived: row.get::<_, i64>(6)? != 0,
+        kind,
+        is_todo: kind == CategoryKind::Todo,
+        is_collapsed: row.get::<_, i64>(8)? != 0,
+        default_template_id: row.get(9)?,
+        sort_mode: row.get(10)?,
+        done_last: row.get::<_, i64>(11)? != 0,
+        auto_archive_days: row.get(12)?,
+        version: row.get(13)?,
+        created_at: row.get(14)?,
+        updated_at: row.get(15)?,
+    })
+}
+
+fn fetch_category(conn: &Connection, id: &str) -> AppResult<Category> {
+    conn.query_row(
+        concat!(category_select!(), " WHERE id = ?1"),
+        params![id],
+        row_to_category,
+    )
+    .optional()?
+    .ok_or_else(|| AppError::not_found(Entity::Category, id))
+}
+
+fn row_to_memo(row: &rusqlite::Row<'_>) -> rusqlite::Result<Memo> {
+    Ok(Memo {
+        id: row.get(0)?,
+        category_id: row.get(1)?,
+        emoji: row.get(2)?,
+        title: row.get(3)?,
+        color: row.get(4)?,
+        date_ymd: row.get(5)?,
+        content_md: row.get(6)?,
+        todo_done: row.get::<_, i64>(7)? != 0,
+        completed_at: row.get(8)?,
+        archived: row.get::<_, Option<i64>>(9)?.is_some(),
+        archived_at: row.get(9)?,
+        pinned: row.get::<_, i64>(10)? != 0,
+        starred: row.get::<_, i64>(11)? != 0,
+        position: row.get(12)?,
+        rank: row.get(13)?,
+        version: row.get(14)?,
+        created_at: row.get(15)?,
+        updated_at: row.get(16)?,
+    })
+}
+
+fn fetch_memo(conn: &Connection, id: &str) -> AppResult<Memo> {
+    conn.query_row(
+        concat!(
+            memo_select!("WHERE category_id = (SELECT category_id FROM memos WHERE id = ?1)"),
+            " WHERE id = ?1"
+        ),
+        params![id],
+        row_to_memo,
+    )
+    .optional()?
+    .ok_or_else(|| AppError::not_found(Entity::Memo, id))
+}
+
+/// Loads a category that memos may be added to or moved into.
+fn fetch_writable_category(conn: &Connection, id: &str) -> AppResult<Category> {
+    let category = fetch_category(conn, id)?;
+    if category.archived {
+        return Err(AppError::PreconditionFailed {
+            entity: Entity::Category,
+            ids: vec![id.to_string()],
+            reason: "category_archived",
+        });
+    }
+    Ok(category)
+}
+
+/// Checks that `ordered` is exactly `current` rearranged. The error lists the
+/// ids that are unknown, missing or repeated.
+fn ensure_permutation(entity: Entity, ordered: &[String], current: &[String]) -> AppResult<()> {
+    let current_set: HashSet<&str> = current.iter().map(String::as_str).collect();
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut offending: Vec<String> = Vec::new();
+
+    for id in ordered {
+        if !current_set.contains(id.as_str()) || !seen.insert(id.as_str()) {
+            offending.push(id.clone());
+        }
+    }
+    for id in current {
+        if !seen.contains(id.as_str()) {
+            offending.push(id.clone());
+        }
+    }
+
+    if offending.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::PreconditionFailed {
+            entity,
+            ids: offending,
+            reason: "not_a_permutation",
+        })
+    }
+}
+
+/// `(id, rank)` pairs of one list, in rank order.
+fn query_id_ranks(
+    conn: &Connection,
+    sql: &str,
+    params: impl rusqlite::Params,
+) -> AppResult<Vec<(String, String)>> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt
+        .query_map(params, |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<Vec<(String, String)>>>()?;
+    Ok(rows)
+}
+
+/// Checks `ordered_ids` against the list's current members, then re-ranks
+/// only the rows that have to move. Returns the `(id, new_rank)` writes.
+fn plan_reorder(
+    entity: Entity,
+    ordered_ids: &[String],
+    current: &[(String, String)],
+) -> AppResult<Vec<(String, String)>> {
+    let current_ids: Vec<String> = current.iter().map(|(id, _)| id.clone()).collect();
+    ensure_permutation(entity, ordered_ids, &current_ids)?;
+
+    let rank_of: HashMap<&str, &str> = current
+        .iter()
+        .map(|(id, rank)| (id.as_str(), rank.as_str()))
+        .collect();
+    let ranks: Vec<&str> = ordered_ids.iter().map(|id| rank_of[id.as_str()]).collect();
+    Ok(rank::reassign(&ranks)
+        .into_iter()
+        .map(|(idx, rank)| (ordered_ids[idx].clone(), rank))
+        .collect())
+}
+
+/// Rank for a memo dropped into `slot` of `category_id`. The memo itself is
+/// left out of the sibling list, so this works for moves within a category.
+/// Pinned memos rank before all others, so the slot is kept on the memo's
+/// side of that boundary.
+fn rank_for_slot(
+    conn: &Connection,
+    category_id: &str,
+    memo_id: &str,
+    pinned: bool,
+    slot: &MemoSlot,
+) -> AppResult<String> {
+    let mut stmt = conn.prepare(
+        "SELECT id, rank, archived_at IS NOT NULL, pinned <> 0 FROM memos
+         WHERE category_id = ?1 AND id <> ?2 ORDER BY rank ASC",
+    )?;
+    let rows = stmt
+        .query_map(params![category_id, memo_id], |row| {
+            Ok((
+                (row.get(0)?, row.get(1)?),
+                row.get::<_, bool>(2)?,
+                row.get::<_, bool>(3)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<((String, String), bool, bool)>>>()?;
+    // `index` counts what the board shows; archived siblings still hold ranks.
+    let visible: Vec<usize> = (0..rows.len()).filter(|&i| !rows[i].1).collect();
+    let pinned_count = rows.iter().filter(|row| row.2).count();
+    let siblings: Vec<(String, String)> = rows.into_iter().map(|(pair, ..)| pair).collect();
+    let index_of = |neighbor: &str| {
+        siblings
+            .iter()
+            .position(|(id, _)| id == neighbor)
+            .ok_or_else(|| AppError::PreconditionFailed {
+                entity: Entity::Memo,
+                ids: vec![neighbor.to_string()],
+                reason: "neighbor_not_in_category",
+            })
+    };
+
+    // The new memo goes between siblings[insert_at - 1] and siblings[insert_at].
+    let insert_at = match (&slot.after_id, &slot.before_id, slot.index) {
+        (Some(after), _, _) => index_of(after)? + 1,
+        (None, Some(before), _) => index_of(before)?,
+        (None, None, Some(index)) => visible.get(index).copied().unwrap_or(siblings.len()),
+        (None, None, None) => siblings.len(),
+    };
+    let insert_at = if pinned {
+        insert_at.min(pinned_count)
+    } else {
+        insert_at.max(pinned_count)
+    };
+    Ok(rank::rank_between(
+        insert_at.checked_sub(1).map(|i| siblings[i].1.as_str()),
+        siblings.get(insert_at).map(|(_, rank)| rank.as_str()),
+    ))
+}
+
+impl Store {
+    /// Loads the board without writing anything: done memos are auto-archived
+    /// by `db::spawn_maintenance`, and journal entries are created by
+    /// `open_journal_entry`, so a deleted or moved entry stays gone. Smart
+    /// categories come first, as read-only entries of kind `smart` whose memos
+    /// are computed here.
+    pub fn list_categories_with_memos(&self) -> AppResult<Vec<CategoryWithMemos>> {
+        let today = today_ymd(&self.conn)?;
+
+        let mut stmt = self
+            .conn
+            .prepare(concat!(category_select!(), " ORDER BY rank ASC"))?;
+        let categories_iter = stmt.query_map([], row_to_category)?;
+
+        let mut memo_stmt = self.conn.prepare(concat!(
+            memo_select!("WHERE category_id = ?1 AND archived_at IS NULL"),
+            " ORDER BY rank ASC"
+        ))?;
+
+        let mut out: Vec<CategoryWithMemos> = Vec::new();
+        for cat in categories_iter {
+            let category = cat?;
+
+            let memo_iter = memo_stmt.query_map(params![&category.id], row_to_memo)?;
+
+            let mut memos: Vec<Memo> = Vec::new();
+            for m in memo_iter {
+                memos.push(m?);
+            }
+            if category.sort_mode != SortMode::Manual || category.done_last {
+                sort::sort_memos(&mut memos, category.sort_mode, category.done_last);
+                for (i, memo) in memos.iter_mut().enumerate() {
+                    memo.position = i as i64;
+                }
+            }
+
+            out.push(CategoryWithMemos { category, memos });
+        }
+
+        let mut entries: Vec<CategoryWithMemos> = query_smart_categories(&self.conn)?
+            .into_iter()
+            .map(|smart_category| {
+                let memos = smart::members(&smart_category.filter, &today, &out);
+                smart_category_entry(smart_category, memos)
+            })
+            .collect();
+        entries.extend(out);
+        Ok(entries)
+    }
+
+    pub fn create_category(&mut self, input: CreateCategoryInput) -> AppResult<Category> {
+        input.validate()?;
+
+        let tx = self.conn.transaction()?;
+
+        let id = Uuid::new_v4().to_string();
+        let ts = now_timestamp_ms();
+        let rank = next_rank(&tx, "categories", None)?;
+
+        tx.execute(
+            "INSERT INTO categories (id, emoji, title, color, rank, archived, kind, is_collapsed, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, 0, ?6, 0, ?7, ?8)",
+            params![
+                &id,
+                input.emoji.as_deref().unwrap_or(""),
+                &input.title,
+                &input.color,
+                &rank,
+                input.kind.unwrap_or(if input.is_todo {
+                    CategoryKind::Todo
+                } else {
+                    CategoryKind::Normal
+                }),
+                ts,
+                ts
+            ],
+        )?;
+
+        let category = fetch_category(&tx, &id)?;
+
+        tx.commit()?;
+        Ok(category)
+    }
+
+    pub fn update_category(
+        &mut self,
+        input: UpdateCategoryInput,
+    ) -> AppResult<UpdateCategoryResult> {
+        input.validate()?;
+        let tx = self.conn.transaction()?;
+        let ts = now_timestamp_ms();
+
+        let server = fetch_category(&tx, &input.id)?;
+
+        if input.expected_version.is_some_and(|v| v != server.version) {
+            return Ok(UpdateCategoryResult::Conflict { server });
+        }
+
+        let kind = input.kind.unwrap_or(server.kind);
+        tx.execute(
+            "UPDATE categories
+             SET emoji = ?1, title = ?2, color = ?3, kind = ?4, version = version + 1, updated_at = ?5
+             WHERE id = ?6",
+            params![
+                &input.emoji,
+                &input.title,
+                &input.color,
+                kind,
+                ts,
+                &input.id
+            ],
+        )?;
+        if kind == CategoryKind::Journal && server.kind != CategoryKind::Journal {
+            // Journals are ordered by date only.
+            tx.execute(
+                "UPDATE memos SET pinned = 0 WHERE category_id = ?1",
+                params![&input.id],
+            )?;
+            write_spread_ranks(
+                &tx,
+                "memos",
+                Some(("category_id", &input.id)),
+                JOURNAL_ORDER,
+            )?;
+        }
+
+        let category = fetch_category(&tx, &input.id)?;
+
+        tx.commit()?;
+        Ok(UpdateCategoryResult::Saved { category })
+    }
+
+    pub fn set_category_archived(&self, input: SetCategoryArchivedInput) -> AppResult<Category> {
+        let ts = now_timestamp_ms();
+        let archived_int: i64 = if input.archived { 1 } else { 0 };
+
+        self.conn.execute(
+            "UPDATE categories SET archived = ?1, version = version + 1, updated_at = ?2 WHERE id = ?3",
+            params![archived_int, ts, &input.id],
+        )?;
+
+        fetch_category(&self.conn, &input.id)
+    }
+
+    pub fn set_category_collapsed(&self, input: SetCategoryCollapsedInput) -> AppResult<Category> {
+        let ts = now_timestamp_ms();
+        let collapsed_int: i64 = if input.is_collapsed { 1 } else { 0 };
+
+        let updated = self.conn.execute(
+            "UPDATE categories SET is_collapsed = ?1, version = version + 1, updated_at = ?2 WHERE id = ?3",
+            params![collapsed_int, ts, &input.id],
+        )?;
+        if updated == 0 {
+            return Err(AppError::not_found(Entity::Category, &input.id));
+        }
+
+        fetch_category(&self.conn, &input.id)
+    }
+
+    /// Changes how the category's memos are listed. The manual order (`rank`) is
+    /// kept, so switching back to `manual` restores it.
+    pub fn set_category_sort(&self, input: SetCategorySortInput) -> AppResult<Category> {
+        let ts = now_timestamp_ms();
+
+        let updated = self.conn.execute(
+            "UPDATE categories SET sort_mode = ?1, done_last = ?2, version = version + 1, updated_at = ?3
+             WHERE id = ?4",
+            params![input.sort_mode, input.done_last, ts, &input.id],
+        )?;
+        if updated == 0 {
+            return Err(AppError::not_found(Entity::Category, &input.id));
+        }
+
+        fetch_category(&self.conn, &input.id)
+    }
+
+    /// Sets how many days after completion done memos in the category are
+    /// archived; `None` turns it off. Applied by the next maintenance run.
+    pub fn set_category_auto_archive(
+        &self,
+        input: SetCategoryAutoArchiveInput,
+    ) -> AppResult<Category> {
+        input.validate()?;
+        let ts = now_timestamp_ms();
+
+        let updated = self.conn.execute(
+            "UPDATE categories SET auto_archive_days = ?1, version = version + 1, updated_at = ?2
+             WHERE id = ?3",
+            params![input.days, ts, &input.id],
+        )?;
+        if updated == 0 {
+            return Err(AppError::not_found(Entity::Category, &input.id));
+        }
+
+        fetch_category(&self.conn, &input.id)
+    }
+
+    pub fn delete_category(&self, id: &str) -> AppResult<()> {
+        let deleted = self
+            .conn
+            .execute("DELETE FROM categories WHERE id = ?1", params![id])?;
+        if deleted == 0 {
+            return Err(AppError::not_found(Entity::Category, id));
+        }
+
+        Ok(())
+    }
+
+    pub fn reorder_categories(&mut self, input: ReorderCategoriesInput) -> AppResult<()> {
+        let tx = self.conn.transaction()?;
+
+        // The grid only shows active categories, so that is the list being
+        // reordered; archived ones keep their ranks.
+        let active = query_id_ranks(
+            &tx,
+            "SELECT id, rank FROM categories WHERE archived = 0 ORDER BY rank ASC",
+            [],
+        )?;
+        for (id, rank) in plan_reorder(Entity::Category, &input.ordered_ids, &active)? {
+            tx.execute(
+                "UPDATE categories SET rank = ?1 WHERE id = ?2",
+                params![rank, id],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn reorder_memos(&mut self, input: ReorderMemosInput) -> AppResult<()> {
+        let tx = self.conn.transaction()?;
+
+        let category = fetch_category(&tx, &input.category_id)?;
+        if category.kind == CategoryKind::Journal {
+            return Err(AppError::PreconditionFailed {
+                entity: Entity::Category,
+                ids: vec![category.id],
+                reason: "journal_ordered_by_date",
+            });
+        }
+        // Only the memos on the board are reordered; archived ones keep their ranks.
+        let members = query_id_ranks(
+            &tx,
+            "SELECT id, rank FROM memos WHERE category_id = ?1 AND archived_at IS NULL
+             ORDER BY rank ASC",
+            params![&input.category_id],
+        )?;
+        let pinned: HashSet<String> = tx
+            .prepare("SELECT id FROM memos WHERE category_id = ?1 AND pinned <> 0")?
+            .query_map(params![&input.category_id], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+
+        // Pinned memos rank before the rest. A sorted category only has a manual
+        // order for its pinned memos, so that is all it accepts.
+        let sorted = category.sort_mode != SortMode::Manual || category.done_last;
+        let (mut ordered, rest): (Vec<String>, Vec<String>) = if sorted {
+            if input.ordered_ids.iter().any(|id| !pinned.contains(id)) {
+                return Err(AppError::PreconditionFailed {
+                    entity: Entity::Category,
+                    ids: vec![category.id],
+                    reason: "category_sorted",
+                });
+            }
+            let rest = members
+                .iter()
+                .map(|(id, _)| id.clone())
+                .filter(|id| !pinned.contains(id))
+                .collect();
+            (input.ordered_ids, rest)
+        } else {
+            input
+                .ordered_ids
+                .into_iter()
+                .partition(|id| pinned.contains(id))
+        };
+        ordered.extend(rest);
+        for (id, rank) in plan_reorder(Entity::Memo, &ordered, &members)? {
+            tx.execute(
+                "UPDATE memos SET rank = ?1 WHERE id = ?2",
+                params![rank, id],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn set_background_color(&self, input: SetBackgroundColorInput) -> AppResult<()> {
+        input.validate()?;
+
+        let mut values = serde_json::Map::new();
+        values.insert(
+            "background_color".to_string(),
+            input.background_color.into(),
+        );
+        settings::save(&self.conn, &values, false)
+    }
+
+    pub fn get_settings(&self) -> AppResult<AppSettings> {
+        settings::load(&self.conn)
+    }
+
+    /// Applies a partial update and returns the resulting settings.
+    pub fn update_settings(&mut self, input: UpdateSettingsInput) -> AppResult<AppSettings> {
+        let tx = self.conn.transaction()?;
+
+        settings::save(&tx, &input.values, false)?;
+        let result = settings::load(&tx)?;
+
+        tx.commit()?;
+        Ok(result)
+    }
+}
+
+const SETTINGS_FILE_FORMAT: &str = "ideanode-settings";
+const SETTINGS_FILE_VERSION: u32 = 1;
+
+impl Store {
+    /// Settings as an export file, without the HTTP API token.
+    pub fn export_settings(&self) -> AppResult<SettingsFile> {
+        let mut settings = match serde_json::to_value(settings::load(&self.conn)?) {
+            Ok(serde_json::Value::Object(map)) => map,
+            _ => return Err(AppError::Internal("settings are not an object".to_string())),
+        };
+        settings.retain(|key, _| settings::is_exportable(key));
+        Ok(SettingsFile {
+            format: SETTINGS_FILE_FORMAT.to_string(),
+            version: SETTINGS_FILE_VERSION,
+            settings,
+        })
+    }
+
+    /// Loads an exported file. Keys this version doesn't know and the HTTP API
+    /// token are skipped; any invalid value rejects the whole import.
+    pub fn import_settings(&mut self, input: SettingsFile) -> AppResult<AppSettings> {
+        if input.format != SETTINGS_FILE_FORMAT {
+            return Err(AppError::Validation(vec![FieldError {
+                field: "format".to_string(),
+                code: "invalid_format".to_string(),
+                message: "IdeaNode 설정 파일이 아닙니다.".to_string(),
+                span: None,
+            }]));
+        }
+        let mut values = input.settings;
+        values.retain(|key, _| settings::is_exportable(key));
+        let tx = self.conn.transaction()?;
+
+        settings::save(&tx, &values, true)?;
+        let result = settings::load(&tx)?;
+
+        tx.commit()?;
+        Ok(result)
+    }
+
+    /// Creates a memo. When the input has no title or body and the category has a
+    /// default template, the memo starts from that template.
+    pub fn create_memo(&mut self, input: CreateMemoInput) -> AppResult<Memo> {
+        input.validate()?;
+        let tx = self.conn.transaction()?;
+
+        let category = fetch_writable_category(&tx, &input.category_id)?;
+        let input = match &category.default_template_id {
+            Some(template_id) if input.title.is_empty() && input.content_md.is_empty() => {
+                let template = fetch_template(&tx, template_id)?;
+                let date_ymd = match input.date_ymd.as_deref().filter(|d| !d.is_empty()) {
+                    Some(date) => date.to_string(),
+                    None => today_ymd(&tx)?,
+                };
+                let values = HashMap::new();
+                render_template(&template, &category, date_ymd, &values)
+            }
+            _ => input,
+        };
+        let memo = insert_memo(&tx, &input)?;
+
+        tx.commit()?;
+        Ok(memo)
+    }
+}
+
+/// Validates and inserts a memo at the end of its category.
+fn insert_memo(conn: &Connection, input: &CreateMemoInput) -> AppResult<Memo> {
+    input.validate()?;
+    fetch_writable_category(conn, &input.category_id)?;
+
+    let id = Uuid::new_v4().to_string();
+    let ts = now_timestamp_ms();
+    let rank = next_rank(conn, "memos", Some(("category_id", &input.category_id)))?;
+
+    conn.execute(
+        "INSERT INTO memos (id, category_id, emoji, title, color, date_ymd, content_md, todo_done, rank, created_at, updated_at)
+         VALUES (
+          ?1, ?2, ?3, ?4, ?5,
+           COALESCE(NULLIF(?6, ''), strftime('%Y-%m-%d', ?9/1000, 'unixepoch', 'localtime')),
+          ?7, 0, ?8, ?9, ?10
+         )",
+        params![
+            &id,
+            &input.category_id,
+            input.emoji.as_deref().unwrap_or(""),
+            &input.title,
+            &input.color,
+            input.date_ymd.as_deref(),
+            &input.content_md,
+            &rank,
+            ts,
+            ts
+        ],
+    )?;
+    record_memo_revision(conn, &id)?;
+    place_by_date(conn, &id)?;
+
+    fetch_memo(conn, &id)
+}
+
+impl Store {
+    pub fn update_memo(&mut self, input: UpdateMemoInput) -> AppResult<UpdateMemoResult> {
+        input.validate()?;
+        let tx = self.conn.transaction()?;
+        let ts = now_timestamp_ms();
+
+        let server = fetch_memo(&tx, &input.id)?;
+
+        // A stale client gets its edits merged onto the server copy, using the
+        // revision it started from as the common base.
+        let mut fields = MemoRevision::from(&input);
+        let mut merged = false;
+        if let Some(expected) = input.expected_version.filter(|v| *v != server.version) {
+            let theirs = MemoRevision::from(&server);
+            let merge_result = match get_memo_revision(&tx, &input.id, expected)? {
+                Some(base) => merge_memo(&base, &fields, &theirs),
+                None => Err(changed_memo_fields(&fields, &theirs)),
+            };
+            match merge_result {
+                Ok(result) => {
+                    fields = result;
+                    merged = true;
+                }
+                Err(conflicting) => {
+                    return Ok(UpdateMemoResult::Conflict {
+                        server,
+                        base_version: expected,
+                        fields: conflicting,
+                    });
+                }
+            }
+        }
+
+        tx.execute(
+            "UPDATE memos
+             SET emoji = ?1, title = ?2, color = ?3, date_ymd = ?4, content_md = ?5, todo_done = ?6,
+                 completed_at = CASE WHEN ?6 THEN COALESCE(completed_at, ?7) END,
+                 version = version + 1, updated_at = ?7
+             WHERE id = ?8",
+            params![
+                &fields.emoji,
+                &fields.title,
+                &fields.color,
+                &fields.date_ymd,
+                &fields.content_md,
+                if fields.todo_done { 1 } else { 0 },
+                ts,
+                &input.id
+            ],
+        )?;
+        record_memo_revision(&tx, &input.id)?;
+        place_by_date(&tx, &input.id)?;
+
+        let memo = fetch_memo(&tx, &input.id)?;
+
+        tx.commit()?;
+        Ok(if merged {
+            UpdateMemoResult::Merged { memo }
+        } else {
+            UpdateMemoResult::Saved { memo }
+        })
+    }
+}
+
+/// Field names where `ours` and `theirs` differ; used as the conflict report
+/// when the base revision has already been pruned.
+fn changed_memo_fields(ours: &MemoRevision, theirs: &MemoRevision) -> Vec<String> {
+    let mut out = Vec::new();
+    if ours.emoji != theirs.emoji {
+        out.push("emoji".to_string());
+    }
+    if ours.title != theirs.title {
+        out.push("title".to_string());
+    }
+    if ours.color != theirs.color {
+        out.push("color".to_string());
+    }
+    if ours.date_ymd != theirs.date_ymd {
+        out.push("date_ymd".to_string());
+    }
+    if ours.content_md != theirs.content_md {
+        out.push("content_md".to_string());
+    }
+    if ours.todo_done != theirs.todo_done {
+        out.push("todo_done".to_string());
+    }
+    out
+}
+
+impl Store {
+    pub fn delete_memo(&self, id: &str) -> AppResult<()> {
+        let deleted = self
+            .conn
+            .execute("DELETE FROM memos WHERE id = ?1", params![id])?;
+        if deleted == 0 {
+            return Err(AppError::not_found(Entity::Memo, id));
+        }
+
+        Ok(())
+    }
+
+    /// Moves a memo into a slot of `to_category_id` in one transaction. Positions
+    /// are derived from ranks, so neither category is left with a gap.
+    pub fn move_memo(&mut self, input: MoveMemoInput) -> AppResult<()> {
+        let tx = self.conn.transaction()?;
+
+        move_memo_into(&tx, &input.memo_id, &input.to_category_id, &input.slot)?;
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Same as `move_memo`, but returns the moved memo. `category_id` may be the
+    /// memo's current category. Only the moved row is written.
+    pub fn move_memo_to(&mut self, input: MoveMemoToInput) -> AppResult<Memo> {
+        let tx = self.conn.transaction()?;
+
+        move_memo_into(&tx, &input.memo_id, &input.category_id, &input.slot)?;
+        let memo = fetch_memo(&tx, &input.memo_id)?;
+
+        tx.commit()?;
+        Ok(memo)
+    }
+}
+
+fn move_memo_into(
+    conn: &Connection,
+    memo_id: &str,
+    category_id: &str,
+    slot: &MemoSlot,
+) -> AppResult<()> {
+    let memo = fetch_memo(conn, memo_id)?;
+    fetch_writable_category(conn, category_id)?;
+
+    let pinned = memo.pinned && memo.category_id == category_id;
+    let rank = rank_for_slot(conn, category_id, memo_id, pinned, slot)?;
+    place_memo(conn, memo_id, category_id, &rank)
+}
+
+/// Writes a memo's new slot. A position change alone leaves `version` and
+/// `updated_at` alone; changing category counts as an edit and unpins it.
+fn place_memo(conn: &Connection, memo_id: &str, category_id: &str, rank: &str) -> AppResult<()> {
+    let ts = now_timestamp_ms();
+    let moved_category = conn.execute(
+        "UPDATE memos SET category_id = ?1, rank = ?2, pinned = 0, version = version + 1,
+                          updated_at = ?3
+         WHERE id = ?4 AND category_id <> ?1",
+        params![category_id, rank, ts, memo_id],
+    )?;
+    if moved_category > 0 {
+        record_memo_revision(conn, memo_id)?;
+    } else {
+        conn.execute(
+            "UPDATE memos SET rank = ?1 WHERE id = ?2",
+            params![rank, memo_id],
+        )?;
+    }
+    place_by_date(conn, memo_id)
+}
+
+/// Applies `apply` to each distinct id inside its own savepoint. An item that
+/// fails with a data error (missing, archived target, bad value) is rolled
+/// back and reported; database/IO failures abort the whole batch.
+fn run_bulk<'a>(
+    tx: &mut rusqlite::Transaction<'_>,
+    ids: impl IntoIterator<Item = &'a String>,
+    mut apply: impl FnMut(&Connection, &str) -> AppResult<()>,
+) -> AppResult<BulkReport> {
+    let mut seen = HashSet::new();
+    let mut report = BulkReport::default();
+    for id in ids {
+        if !seen.insert(id.as_str()) {
+            continue;
+        }
+        let sp = tx.savepoint()?;
+        let error = match apply(&sp, id) {
+            Ok(()) => {
+                sp.commit()?;
+                report.succeeded += 1;
+                None
+            }
+            Err(
+                err @ (AppError::NotFound { .. }
+                | AppError::Validation(_)
+                | AppError::PreconditionFailed { .. }
+                | AppError::Constraint { .. }),
+            ) => {
+                report.failed += 1;
+                Some(err)
+            }
+            Err(err) => return Err(err),
+        };
+        report.items.push(BulkItemResult {
+            id: id.clone(),
+            ok: error.is_none(),
+            error,
+        });
+    }
+    Ok(report)
+}
+
+/// Sets one memo column as an edit: bumps `version`/`updated_at` and records
+/// a revision. `column` is always a literal from this module.
+fn set_memo_column(
+    conn: &Connection,
+    memo_id: &str,
+    column: &'static str,
+    value: &dyn rusqlite::ToSql,
+) -> AppResult<()> {
+    let sql = format!(
+        "UPDATE memos SET {column} = ?1, version = version + 1, updated_at = ?2 WHERE id = ?3"
+    );
+    let updated = conn.execute(&sql, params![value, now_timestamp_ms(), memo_id])?;
+    if updated == 0 {
+        return Err(AppError::not_found(Entity::Memo, memo_id));
+    }
+    record_memo_revision(conn, memo_id)?;
+    place_by_date(conn, memo_id)
+}
+
+impl Store {
+    /// Appends the memos to the end of `to_category_id`, keeping the order they
+    /// had on the board (category order, then memo order). Moved memos are
+    /// unpinned.
+    pub fn move_memos(&mut self, input: MoveMemosInput) -> AppResult<BulkReport> {
+        let mut tx = self.conn.transaction()?;
+
+        fetch_writable_category(&tx, &input.to_category_id)?;
+
+        let mut keyed = Vec::with_capacity(input.memo_ids.len());
+        for id in &input.memo_ids {
+            let key: Option<(String, String)> = tx
+                .query_row(
+                    "SELECT c.rank, m.rank FROM memos AS m JOIN categories AS c ON c.id = m.category_id
+                     WHERE m.id = ?1",
+                    params![id],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()?;
+            keyed.push((key, id));
+        }
+        // Unknown ids go last and are reported as not found.
+        keyed.sort_by(|a, b| (a.0.is_none(), &a.0).cmp(&(b.0.is_none(), &b.0)));
+
+        let report = run_bulk(&mut tx, keyed.into_iter().map(|(_, id)| id), |conn, id| {
+            if get_memo_category(conn, id)?.is_none() {
+                return Err(AppError::not_found(Entity::Memo, id));
+            }
+            conn.execute("UPDATE memos SET pinned = 0 WHERE id = ?1", params![id])?;
+            let rank = next_rank(conn, "memos", Some(("category_id", &input.to_category_id)))?;
+            place_memo(conn, id, &input.to_category_id, &rank)
+        })?;
+
+        tx.commit()?;
+        Ok(report)
+    }
+
+    pub fn delete_memos(&mut self, input: DeleteMemosInput) -> AppResult<BulkReport> {
+        let mut tx = self.conn.transaction()?;
+
+        let report = run_bulk(&mut tx, &input.memo_ids, |conn, id| {
+            let deleted = conn.execute("DELETE FROM memos WHERE id = ?1", params![id])?;
+            if deleted == 0 {
+                return Err(AppError::not_found(Entity::Memo, id));
+            }
+            Ok(())
+        })?;
+
+        tx.commit()?;
+        Ok(report)
+    }
+
+    pub fn set_memos_todo_done(&mut self, input: SetMemosTodoDoneInput) -> AppResult<BulkReport> {
+        let mut tx = self.conn.transaction()?;
+
+        let ts = now_timestamp_ms();
+        let report = run_bulk(&mut tx, &input.memo_ids, |conn, id| {
+            let updated = conn.execute(
+                "UPDATE memos
+                 SET todo_done = ?1, completed_at = CASE WHEN ?1 THEN COALESCE(completed_at, ?2) END,
+                     version = version + 1, updated_at = ?2
+                 WHERE id = ?3",
+                params![input.todo_done, ts, id],
+            )?;
+            if updated == 0 {
+                return Err(AppError::not_found(Entity::Memo, id));
+            }
+            record_memo_revision(conn, id)
+        })?;
+
+        tx.commit()?;
+        Ok(report)
+    }
+
+    pub fn set_memos_color(&mut self, input: SetMemosColorInput) -> AppResult<BulkReport> {
+        input.validate()?;
+        let mut tx = self.conn.transaction()?;
+
+        let report = run_bulk(&mut tx, &input.memo_ids, |conn, id| {
+            set_memo_column(conn, id, "color", &input.color)
+        })?;
+
+        tx.commit()?;
+        Ok(report)
+    }
+
+    pub fn set_memos_date(&mut self, input: SetMemosDateInput) -> AppResult<BulkReport> {
+        input.validate()?;
+        let mut tx = self.conn.transaction()?;
+
+        let report = run_bulk(&mut tx, &input.memo_ids, |conn, id| {
+            set_memo_column(conn, id, "date_ymd", &input.date_ymd)
+        })?;
+
+        tx.commit()?;
+        Ok(report)
+    }
+}
+
+const COPY_SUFFIX: &str = " (copy)";
+
+impl Store {
+    /// Pins or unpins a memo. It moves to the end of the pinned group, or to the
+    /// top of the unpinned ones; `version` and `updated_at` are left alone, as
+    /// for other moves.
+    pub fn set_memo_pinned(&mut self, input: SetMemoPinnedInput) -> AppResult<Memo> {
+        let tx = self.conn.transaction()?;
+
+        let memo = fetch_memo(&tx, &input.id)?;
+        if memo.pinned == input.pinned {
+            return Ok(memo);
+        }
+        let category = fetch_category(&tx, &memo.category_id)?;
+        if category.kind == CategoryKind::Journal {
+            return Err(AppError::PreconditionFailed {
+                entity: Entity::Category,
+                ids: vec![category.id],
+                reason: "journal_ordered_by_date",
+            });
+        }
+
+        let (last_pinned, first_unpinned): (Option<String>, Option<String>) = tx.query_row(
+            "SELECT MAX(CASE WHEN pinned <> 0 THEN rank END), MIN(CASE WHEN pinned = 0 THEN rank END)
+             FROM memos WHERE category_id = ?1 AND id <> ?2",
+            params![&memo.category_id, &memo.id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        let rank = rank::rank_between(last_pinned.as_deref(), first_unpinned.as_deref());
+        tx.execute(
+            "UPDATE memos SET pinned = ?1, rank = ?2 WHERE id = ?3",
+            params![input.pinned, rank, &memo.id],
+        )?;
+        let memo = fetch_memo(&tx, &memo.id)?;
+
+        tx.commit()?;
+        Ok(memo)
+    }
+
+    /// Stars or unstars a memo. Like pinning, this isn't an edit.
+    pub fn set_memo_starred(&self, input: SetMemoStarredInput) -> AppResult<Memo> {
+        let updated = self.conn.execute(
+            "UPDATE memos SET starred = ?1 WHERE id = ?2",
+            params![input.starred, &input.id],
+        )?;
+        if updated == 0 {
+            return Err(AppError::not_found(Entity::Memo, &input.id));
+        }
+
+        fetch_memo(&self.conn, &input.id)
+    }
+
+    /// Starred memos on the board, across categories, in board order.
+    pub fn list_favorites(&self) -> AppResult<Vec<Memo>> {
+        let mut stmt = self.conn.prepare(concat!(
+            memo_select!(),
+            " WHERE starred <> 0 AND archived_at IS NULL
+                AND category_id IN (SELECT id FROM categories WHERE archived = 0)
+              ORDER BY (SELECT rank FROM categories WHERE id = memos.category_id) ASC,
+                       rank ASC"
+        ))?;
+        let memos = stmt
+            .query_map([], row_to_memo)?
+            .collect::<rusqlite::Result<Vec<Memo>>>()?;
+
+        Ok(memos)
+    }
+}
+
+const DAY_MS: i64 = 86_400_000;
+const RECENTLY_COMPLETED_DEFAULT_DAYS: u32 = 7;
+const RECENTLY_COMPLETED_MAX_DAYS: u32 = 3650;
+const RECENTLY_COMPLETED_DEFAULT_LIMIT: u32 = 100;
+
+/// Archives done memos completed at least `auto_archive_days` ago, in
+/// categories that have the rule. A memo restored from the archive gets the
+/// full period again, counted from the restore. Run when the database is
+/// opened and periodically by `db::spawn_maintenance`.
+pub fn archive_completed(conn: &Connection, now: i64) -> AppResult<usize> {
+    let archived = conn.execute(
+        "UPDATE memos SET archived_at = ?1
+         WHERE archived_at IS NULL AND todo_done <> 0 AND completed_at IS NOT NULL
+           AND MAX(completed_at, COALESCE(restored_at, 0))
+               <= ?1 - ?2 * (SELECT auto_archive_days FROM categories
+                             WHERE id = memos.category_id)",
+        params![now, DAY_MS],
+    )?;
+    Ok(archived)
+}
+
+impl Store {
+    /// Memos completed within the last `days` days across all categories, most
+    /// recent first. Archived memos are included, so this also shows what
+    /// auto-archiving took off the board.
+    pub fn list_recently_completed(&self, input: RecentlyCompletedInput) -> AppResult<Vec<Memo>> {
+        let days = input
+            .days
+            .unwrap_or(RECENTLY_COMPLETED_DEFAULT_DAYS)
+            .clamp(1, RECENTLY_COMPLETED_MAX_DAYS);
+        let limit = input.limit.unwrap_or(RECENTLY_COMPLETED_DEFAULT_LIMIT);
+        let since = now_timestamp_ms() - i64::from(days) * DAY_MS;
+
+        let mut stmt = self.conn.prepare(concat!(
+            memo_select!(),
+            " WHERE todo_done <> 0 AND completed_at >= ?1
+              ORDER BY completed_at DESC, id ASC LIMIT ?2"
+        ))?;
+        let memos = stmt
+            .query_map(params![since, limit], row_to_memo)?
+            .collect::<rusqlite::Result<Vec<Memo>>>()?;
+
+        Ok(memos)
+    }
+
+    /// Archives or restores a memo. It keeps its rank, so a restored memo goes
+    /// back to where it was on the board.
+    pub fn set_memo_archived(&self, input: SetMemoArchivedInput) -> AppResult<Memo> {
+        let ts = now_timestamp_ms();
+
+        let updated = self.conn.execute(
+            "UPDATE memos
+             SET archived_at = CASE WHEN ?1 THEN COALESCE(archived_at, ?2) END,
+                 restored_at = CASE WHEN ?1 OR archived_at IS NULL THEN restored_at ELSE ?2 END
+             WHERE id = ?3",
+            params![input.archived, ts, &input.id],
+        )?;
+        if updated == 0 {
+            return Err(AppError::not_found(Entity::Memo, &input.id));
+        }
+
+        fetch_memo(&self.conn, &input.id)
+    }
+
+    /// Archived categories and archived memos, in board order, filtered by
+    /// `query`. A category that matches keeps all of its listed memos.
+    pub fn list_archive(&self, input: ListArchiveInput) -> AppResult<Archive> {
+        let query = input.query.unwrap_or_default().trim().to_lowercase();
+        let matches = |text: &str| text.to_lowercase().contains(&query);
+        let memo_matches = |memo: &Memo| {
+            query.is_empty()
+                || matches(&memo.title)
+                || matches(&stats::plain_text(&memo.content_md))
+        };
+
+        let mut archive = Archive::default();
+        for CategoryWithMemos { category, memos } in self.list_all_categories_with_memos()? {
+            let title_matches = query.is_empty() || matches(&category.title);
+            if category.archived {
+                if title_matches || memos.iter().any(memo_matches) {
+                    archive
+                        .categories
+                        .push(CategoryWithMemos { category, memos });
+                }
+                continue;
+            }
+            let memos: Vec<Memo> = memos
+                .into_iter()
+                .filter(|memo| memo.archived && (title_matches || memo_matches(memo)))
+                .collect();
+            if !memos.is_empty() {
+                archive.memos.push(CategoryWithMemos { category, memos });
+            }
+        }
+
+        Ok(archive)
+    }
+
+    /// Every category, archived or not, with all of its memos including archived
+    /// ones, in rank order. Nothing is sorted or created as a side effect.
+    pub fn list_all_categories_with_memos(&self) -> AppResult<Vec<CategoryWithMemos>> {
+        let categories = self
+            .conn
+            .prepare(concat!(category_select!(), " ORDER BY rank ASC"))?
+            .query_map([], row_to_category)?
+            .collect::<rusqlite::Result<Vec<Category>>>()?;
+        let mut memo_stmt = self.conn.prepare(concat!(
+            memo_select!("WHERE category_id = ?1"),
+            " ORDER BY rank ASC"
+        ))?;
+
+        let mut out = Vec::with_capacity(categories.len());
+        for category in categories {
+            let memos = memo_stmt
+                .query_map(params![&category.id], row_to_memo)?
+                .collect::<rusqlite::Result<Vec<Memo>>>()?;
+            out.push(CategoryWithMemos { category, memos });
+        }
+        Ok(out)
+    }
+
+    /// Memos matching a search query, in board order. A malformed query is
+    /// rejected as a validation error on `query` with the offending range in
+    /// `span`.
+    pub fn query_memos(&self, input: QueryMemosInput) -> AppResult<Vec<Memo>> {
+        let today = today_ymd(&self.conn)?;
+
+        let parsed = query::parse(&input.query, &today).map_err(|err| {
+            AppError::Validation(vec![FieldError {
+                field: "query".to_string(),
+                code: err.code.to_string(),
+                message: err.message,
+                span: Some(err.span),
+            }])
+        })?;
+        let compiled = query::compile(&parsed);
+        let limit = input.limit.map_or(-1, i64::from);
+
+        let sql = format!(
+            "{} WHERE {}
+             ORDER BY (SELECT rank FROM categories WHERE id = memos.category_id) ASC, rank ASC
+             LIMIT {}",
+            memo_select!(),
+            compiled.condition,
+            limit
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let memos = stmt
+            .query_map(params_from_iter(compiled.params), row_to_memo)?
+            .collect::<rusqlite::Result<Vec<Memo>>>()?;
+
+        Ok(memos)
+    }
+}
+
+/// `title` with the copy suffix, shortened so the result still validates.
+/// An empty title gives just the suffix, without its leading space.
+fn copy_title(title: &str) -> String {
+    let keep = TITLE_MAX_CHARS - COPY_SUFFIX.chars().count();
+    let base: String = title.trim().chars().take(keep).collect();
+    let mut out = base.trim_end().to_string();
+    out.push_str(COPY_SUFFIX);
+    out.trim_start().to_string()
+}
+
+/// Inserts a copy of memo `source_id` into `category_id` at `rank`, keeping
+/// its date. Copies start unpinned and unstarred. Returns the new id.
+fn copy_memo(
+    conn: &Connection,
+    source_id: &str,
+    category_id: &str,
+    title: &str,
+    rank: &str,
+    reset_todo: bool,
+) -> AppResult<String> {
+    let id = Uuid::new_v4().to_string();
+    let ts = now_timestamp_ms();
+    conn.execute(
+        "INSERT INTO memos (id, category_id, emoji, title, color, date_ymd, content_md, todo_done, completed_at, pinned, starred, rank, created_at, updated_at)
+         SELECT ?1, ?2, emoji, ?3, color, date_ymd, content_md, CASE WHEN ?4 THEN 0 ELSE todo_done END,
+                CASE WHEN ?4 THEN NULL ELSE completed_at END, 0, 0, ?5, ?6, ?6
+         FROM memos WHERE id = ?7",
+        params![&id, category_id, title, reset_todo, rank, ts, source_id],
+    )?;
+    record_memo_revision(conn, &id)?;
+    place_by_date(conn, &id)?;
+    Ok(id)
+}
+
+impl Store {
+    /// Copies a memo to the slot right after the original, titled "… (copy)".
+    /// The copy of a pinned memo is unpinned, so it goes to the top of the
+    /// unpinned ones instead.
+    pub fn duplicate_memo(&mut self, input: DuplicateMemoInput) -> AppResult<Memo> {
+        let tx = self.conn.transaction()?;
+
+        let source = fetch_memo(&tx, &input.id)?;
+        fetch_writable_category(&tx, &source.category_id)?;
+
+        let (lower, upper): (Option<String>, Option<String>) = if source.pinned {
+            tx.query_row(
+                "SELECT MAX(CASE WHEN pinned <> 0 THEN rank END), MIN(CASE WHEN pinned = 0 THEN rank END)
+                 FROM memos WHERE category_id = ?1",
+                params![&source.category_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )?
+        } else {
+            let upper = tx.query_row(
+                "SELECT MIN(rank) FROM memos WHERE category_id = ?1 AND rank > ?2",
+                params![&source.category_id, &source.rank],
+                |row| row.get(0),
+            )?;
+            (Some(source.rank.clone()), upper)
+        };
+        let rank = rank::rank_between(lower.as_deref(), upper.as_deref());
+        let id = copy_memo(
+            &tx,
+            &source.id,
+            &source.category_id,
+            &copy_title(&source.title),
+            &rank,
+            input.reset_todo,
+        )?;
+        let memo = fetch_memo(&tx, &id)?;
+
+        tx.commit()?;
+        Ok(memo)
+    }
+
+    /// Copies a category right after the original, titled "… (copy)", with all of
+    /// its memos in the same order. Memo titles are kept as they are, and the copy
+    /// starts out unarchived.
+    pub fn duplicate_category(
+        &mut self,
+        input: DuplicateCategoryInput,
+    ) -> AppResult<CategoryWithMemos> {
+        let tx = self.conn.transaction()?;
+
+        let source = fetch_category(&tx, &input.id)?;
+        let upper: Option<String> = tx.query_row(
+            "SELECT MIN(rank) FROM categories WHERE rank > ?1",
+            params![&source.rank],
+            |row| row.get(0),
+        )?;
+        let rank = rank::rank_between(Some(&source.rank), upper.as_deref());
+
+        let id = Uuid::new_v4().to_string();
+        let ts = now_timestamp_ms();
+        tx.execute(
+            "INSERT INTO categories (id, emoji, title, color, rank, archived, kind, is_collapsed, default_template_id, sort_mode, done_last, auto_archive_days, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, 0, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?12)",
+            params![
+                &id,
+                &source.emoji,
+                copy_title(&source.title),
+                &source.color,
+                &rank,
+                source.kind,
+                source.is_collapsed,
+                &source.default_template_id,
+                source.sort_mode,
+                source.done_last,
+                source.auto_archive_days,
+                ts
+            ],
+        )?;
+
+        let mut stmt = tx.prepare(
+            "SELECT id, title FROM memos WHERE category_id = ?1 AND archived_at IS NULL
+             ORDER BY rank ASC",
+        )?;
+        let memos = stmt
+            .query_map(params![&source.id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<Vec<(String, String)>>>()?;
+        drop(stmt);
+        for ((memo_id, title), memo_rank) in memos.iter().zip(rank::spread(memos.len())) {
+            copy_memo(&tx, memo_id, &id, title, &memo_rank, input.reset_todo)?;
+        }
+
+        let category = fetch_category(&tx, &id)?;
+        let mut stmt = tx.prepare(concat!(
+            memo_select!("WHERE category_id = ?1"),
+            " ORDER BY rank ASC"
+        ))?;
+        let memos = stmt
+            .query_map(params![&id], row_to_memo)?
+            .collect::<rusqlite::Result<Vec<Memo>>>()?;
+        drop(stmt);
+
+        tx.commit()?;
+        Ok(CategoryWithMemos { category, memos })
+    }
+}
+
+fn row_to_template(row: &rusqlite::Row<'_>) -> rusqlite::Result<Template> {
+    let title: String = row.get(3)?;
+    let content_md: String = row.get(5)?;
+    Ok(Template {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        emoji: row.get(2)?,
+        prompts: template::prompts(&[&title, &content_md]),
+        title,
+        color: row.get(4)?,
+        content_md,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
+fn fetch_template(conn: &Connection, id: &str) -> AppResult<Template> {
+    conn.query_row(
+        "SELECT id, name, emoji, title, color, content_md, created_at, updated_at
+         FROM templates WHERE id = ?1",
+        params![id],
+        row_to_template,
+    )
+    .optional()?
+    .ok_or_else(|| AppError::not_found(Entity::Template, id))
+}
+
+/// The memo a template produces in `category` on `date_ymd`.
+fn render_template(
+    tpl: &Template,
+    category: &Category,
+    date_ymd: String,
+    values: &HashMap<String, String>,
+) -> CreateMemoInput {
+    let ctx = template::Context {
+        date_ymd: &date_ymd,
+        category: &category.title,
+        values,
+    };
+    CreateMemoInput {
+        category_id: category.id.clone(),
+        emoji: Some(tpl.emoji.clone()),
+        title: template::render(&tpl.title, &ctx),
+        color: tpl.color.clone(),
+        content_md: template::render_html(&tpl.content_md, &ctx),
+        date_ymd: Some(date_ymd),
+    }
+}
+
+impl Store {
+    pub fn list_templates(&self) -> AppResult<Vec<Template>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, emoji, title, color, content_md, created_at, updated_at
+             FROM templates ORDER BY name ASC, created_at ASC",
+        )?;
+        let templates = stmt
+            .query_map([], row_to_template)?
+            .collect::<rusqlite::Result<Vec<Template>>>()?;
+
+        Ok(templates)
+    }
+
+    /// Saves a memo's emoji, title, color and body as a new template. Placeholders
+    /// are kept as typed.
+    pub fn save_memo_as_template(&self, input: SaveMemoAsTemplateInput) -> AppResult<Template> {
+        input.validate()?;
+
+        let memo = fetch_memo(&self.conn, &input.memo_id)?;
+        let id = Uuid::new_v4().to_string();
+        let ts = now_timestamp_ms();
+        self.conn.execute(
+            "INSERT INTO templates (id, name, emoji, title, color, content_md, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)",
+            params![
+                &id,
+                input.name.trim(),
+                &memo.emoji,
+                &memo.title,
+                &memo.color,
+                &memo.content_md,
+                ts
+            ],
+        )?;
+
+        fetch_template(&self.conn, &id)
+    }
+
+    /// Deletes a template; categories using it as their default fall back to none.
+    pub fn delete_template(&self, id: &str) -> AppResult<()> {
+        let deleted = self
+            .conn
+            .execute("DELETE FROM templates WHERE id = ?1", params![id])?;
+        if deleted == 0 {
+            return Err(AppError::not_found(Entity::Template, id));
+        }
+
+        Ok(())
+    }
+
+    /// Creates a memo from a template, filling in placeholders. `date_ymd`
+    /// defaults to today; `values` answers the template's custom prompts.
+    pub fn create_memo_from_template(
+        &mut self,
+        input: CreateMemoFromTemplateInput,
+    ) -> AppResult<Memo> {
+        let tx = self.conn.transaction()?;
+
+        let tpl = fetch_template(&tx, &input.template_id)?;
+        let category = fetch_writable_category(&tx, &input.category_id)?;
+        let date_ymd = match input.date_ymd.filter(|d| !d.is_empty()) {
+            Some(date) => date,
+            None => today_ymd(&tx)?,
+        };
+        let memo = insert_memo(
+            &tx,
+            &render_template(&tpl, &category, date_ymd, &input.values),
+        )?;
+
+        tx.commit()?;
+        Ok(memo)
+    }
+
+    pub fn set_category_default_template(
+        &mut self,
+        input: SetCategoryDefaultTemplateInput,
+    ) -> AppResult<Category> {
+        let tx = self.conn.transaction()?;
+
+        if let Some(template_id) = &input.template_id {
+            fetch_template(&tx, template_id)?;
+        }
+        let updated = tx.execute(
+            "UPDATE categories SET default_template_id = ?1, version = version + 1, updated_at = ?2
+             WHERE id = ?3",
+            params![&input.template_id, now_timestamp_ms(), &input.category_id],
+        )?;
+        if updated == 0 {
+            return Err(AppError::not_found(Entity::Category, &input.category_id));
+        }
+        let category = fetch_category(&tx, &input.category_id)?;
+
+        tx.commit()?;
+        Ok(category)
+    }
+}
+
+/// Journal categories order memos by `date_ymd`, newest first.
+const JOURNAL_ORDER: &str = "date_ymd DESC, created_at ASC";
+
+fn row_to_smart_category(row: &rusqlite::Row<'_>) -> rusqlite::Result<SmartCategory> {
+    let filter: String = row.get(4)?;
+    Ok(SmartCategory {
+        id: row.get(0)?,
+        emoji: row.get(1)?,
+        title: row.get(2)?,
+        color: row.get(3)?,
+        filter: serde_json::from_str(&filter).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(4, rusqlite::types::Type::Text, Box::new(e))
+        })?,
+        position: row.get(5)?,
+        rank: row.get(6)?,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+    })
+}
+
+fn fetch_smart_category(conn: &Connection, id: &str) -> AppResult<SmartCategory> {
+    conn.query_row(
+        concat!(smart_category_select!(), " WHERE id = ?1"),
+        params![id],
+        row_to_smart_category,
+    )
+    .optional()?
+    .ok_or_else(|| AppError::not_found(Entity::SmartCategory, id))
+}
+
+fn query_smart_categories(conn: &Connection) -> AppResult<Vec<SmartCategory>> {
+    let smart_categories = conn
+        .prepare(concat!(smart_category_select!(), " ORDER BY rank ASC"))?
+        .query_map([], row_to_smart_category)?
+        .collect::<rusqlite::Result<Vec<SmartCategory>>>()?;
+    Ok(smart_categories)
+}
+
+fn filter_json(filter: &SmartFilter) -> AppResult<String> {
+    serde_json::to_string(filter)
+        .map_err(|e| AppError::Internal(format!("smart filter doesn't serialize: {e}")))
+}
+
+/// A smart category as a board entry. It has no settings of its own, so the
+/// category fields that don't apply keep their defaults.
+fn smart_category_entry(smart_category: SmartCategory, memos: Vec<Memo>) -> CategoryWithMemos {
+    CategoryWithMemos {
+        category: Category {
+            id: smart_category.id,
+            emoji: smart_category.emoji,
+            title: smart_category.title,
+            color: smart_category.color,
+            position: smart_category.position,
+            rank: smart_category.rank,
+            archived: false,
+            kind: CategoryKind::Smart,
+            is_todo: false,
+            is_collapsed: false,
+            default_template_id: None,
+            sort_mode: SortMode::Manual,
+            done_last: false,
+            auto_archive_days: None,
+            version: 0,
+            created_at: smart_category.created_at,
+            updated_at: smart_category.updated_at,
+        },
+        memos,
+    }
+}
+
+impl Store {
+    pub fn list_smart_categories(&self) -> AppResult<Vec<SmartCategory>> {
+        query_smart_categories(&self.conn)
+    }
+
+    pub fn create_smart_category(
+        &self,
+        input: CreateSmartCategoryInput,
+    ) -> AppResult<SmartCategory> {
+        input.validate()?;
+
+        let id = Uuid::new_v4().to_string();
+        let ts = now_timestamp_ms();
+        let rank = next_rank(&self.conn, "smart_categories", None)?;
+        self.conn.execute(
+            "INSERT INTO smart_categories (id, emoji, title, color, filter, rank, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)",
+            params![
+                &id,
+                input.emoji.as_deref().unwrap_or(""),
+                &input.title,
+                &input.color,
+                filter_json(&input.filter)?,
+                &rank,
+                ts
+            ],
+        )?;
+
+        fetch_smart_category(&self.conn, &id)
+    }
+
+    pub fn update_smart_category(
+        &self,
+        input: UpdateSmartCategoryInput,
+    ) -> AppResult<SmartCategory> {
+        input.validate()?;
+        let ts = now_timestamp_ms();
+
+        let updated = self.conn.execute(
+            "UPDATE smart_categories
+             SET emoji = ?1, title = ?2, color = ?3, filter = ?4, updated_at = ?5
+             WHERE id = ?6",
+            params![
+                &input.emoji,
+                &input.title,
+                &input.color,
+                filter_json(&input.filter)?,
+                ts,
+                &input.id
+            ],
+        )?;
+        if updated == 0 {
+            return Err(AppError::not_found(Entity::SmartCategory, &input.id));
+        }
+
+        fetch_smart_category(&self.conn, &input.id)
+    }
+
+    /// Deletes the saved search only; its memos are untouched.
+    pub fn delete_smart_category(&self, id: &str) -> AppResult<()> {
+        let deleted = self
+            .conn
+            .execute("DELETE FROM smart_categories WHERE id = ?1", params![id])?;
+        if deleted == 0 {
+            return Err(AppError::not_found(Entity::SmartCategory, id));
+        }
+
+        Ok(())
+    }
+
+    pub fn reorder_smart_categories(&mut self, input: ReorderCategoriesInput) -> AppResult<()> {
+        let tx = self.conn.transaction()?;
+
+        let current = query_id_ranks(
+            &tx,
+            "SELECT id, rank FROM smart_categories ORDER BY rank ASC",
+            [],
+        )?;
+        for (id, rank) in plan_reorder(Entity::SmartCategory, &input.ordered_ids, &current)? {
+            tx.execute(
+                "UPDATE smart_categories SET rank = ?1 WHERE id = ?2",
+                params![rank, id],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+/// Keeps a memo in date order when it sits in a journal category; a no-op
+/// elsewhere. Called after every write that can change a memo's category or
+/// date, so manual slots never stick in a journal.
+fn place_by_date(conn: &Connection, memo_id: &str) -> AppResult<()> {
+    let row: Option<(String, CategoryKind, String, String)> = conn
+        .query_row(
+            "SELECT m.category_id, c.kind, m.date_ymd, m.rank
+             FROM memos AS m JOIN categories AS c ON c.id = m.category_id
+             WHERE m.id = ?1",
+            params![memo_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .optional()?;
+    let Some((category_id, CategoryKind::Journal, date_ymd, current_rank)) = row else {
+        return Ok(());
+    };
+
+    let mut stmt = conn.prepare(
+        "SELECT date_ymd, rank FROM memos WHERE category_id = ?1 AND id <> ?2 ORDER BY rank ASC",
+    )?;
+    let siblings = stmt
+        .query_map(params![&category_id, memo_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<rusqlite::Result<Vec<(String, String)>>>()?;
+
+    // Already between a newer-or-same and an older-or-same entry.
+    let at = siblings.partition_point(|(_, rank)| *rank < current_rank);
+    let after_ok = at == 0 || siblings[at - 1].0 >= date_ymd;
+    let before_ok = siblings.get(at).is_none_or(|(date, _)| *date <= date_ymd);
+    if after_ok && before_ok {
+        return Ok(());
+    }
+
+    // After every entry of the same day or newer.
+    let at = siblings
+        .iter()
+        .position(|(date, _)| *date < date_ymd)
+        .unwrap_or(siblings.len());
+    let rank = rank::rank_between(
+        at.checked_sub(1).map(|i| siblings[i].1.as_str()),
+        siblings.get(at).map(|(_, rank)| rank.as_str()),
+    );
+    conn.execute(
+        "UPDATE memos SET rank = ?1 WHERE id = ?2",
+        params![rank, memo_id],
+    )?;
+    Ok(())
+}
+
+/// The entry of a journal category for `date_ymd`, created when missing. A
+/// new entry is titled with the date and weekday and starts from the
+/// category's default template, if any.
+fn ensure_journal_entry(conn: &Connection, category: &Category, date_ymd: &str) -> AppResult<Memo> {
+    let existing: Option<String> = conn
+        .query_row(
+            "SELECT id FROM memos WHERE category_id = ?1 AND date_ymd = ?2
+             ORDER BY created_at ASC LIMIT 1",
+            params![&category.id, date_ymd],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if let Some(id) = existing {
+        return fetch_memo(conn, &id);
+    }
+
+    let mut input = match &category.default_template_id {
+        Some(template_id) => render_template(
+            &fetch_template(conn, template_id)?,
+            category,
+            date_ymd.to_string(),
+            &HashMap::new(),
+        ),
+        None => CreateMemoInput {
+            category_id: category.id.clone(),
+            emoji: None,
+            title: String::new(),
+            color: category.color.clone(),
+            date_ymd: Some(date_ymd.to_string()),
+            content_md: String::new(),
+        },
+    };
+    input.title = match template::weekday(date_ymd) {
+        Some(weekday) => format!("{date_ymd} {weekday}"),
+        None => date_ymd.to_string(),
+    };
+    insert_memo(conn, &input)
+}
+
+impl Store {
+    /// Jumps to a journal's entry for `date_ymd` (default today), creating it on
+    /// first open.
+    pub fn open_journal_entry(&mut self, input: OpenJournalEntryInput) -> AppResult<Memo> {
+        input.validate()?;
+        let tx = self.conn.transaction()?;
+
+        let category = fetch_category(&tx, &input.category_id)?;
+        if category.kind != CategoryKind::Journal {
+            return Err(AppError::PreconditionFailed {
+                entity: Entity::Category,
+                ids: vec![category.id],
+                reason: "not_a_journal",
+            });
+        }
+        let date_ymd = match input.date_ymd.filter(|d| !d.is_empty()) {
+            Some(date) => date,
+            None => today_ymd(&tx)?,
+        };
+        let memo = ensure_journal_entry(&tx, &category, &date_ymd)?;
+
+        tx.commit()?;
+        Ok(memo)
+    }
+
+    /// Memos dated within the range, across categories, ordered by date and then
+    /// by board position.
+    pub fn list_memos_in_range(&self, input: DateRangeInput) -> AppResult<Vec<Memo>> {
+        input.validate()?;
+
+        let mut stmt = self.conn.prepare(concat!(
+            memo_select!(),
+            " WHERE date_ymd BETWEEN ?1 AND ?2
+                AND (?3 OR archived_at IS NULL)
+                AND category_id IN (SELECT id FROM categories WHERE ?3 OR archived = 0)
+              ORDER BY date_ymd ASC,
+                       (SELECT rank FROM categories WHERE id = memos.category_id) ASC,
+                       rank ASC"
+        ))?;
+        let memos = stmt
+            .query_map(
+                params![&input.from_ymd, &input.to_ymd, input.include_archived],
+                row_to_memo,
+            )?
+            .collect::<rusqlite::Result<Vec<Memo>>>()?;
+
+        Ok(memos)
+    }
+
+    /// Memo count per day of a month; days without memos are left out.
+    pub fn count_memos_by_day(&self, input: MonthInput) -> AppResult<Vec<DayCount>> {
+        input.validate()?;
+
+        // Day "31" is an upper bound for every month, as dates compare as text.
+        let mut stmt = self.conn.prepare(
+            "SELECT date_ymd, COUNT(*) FROM memos
+             WHERE date_ymd BETWEEN ?1 || '-01' AND ?1 || '-31'
+               AND (?2 OR archived_at IS NULL)
+               AND category_id IN (SELECT id FROM categories WHERE ?2 OR archived = 0)
+             GROUP BY date_ymd ORDER BY date_ymd ASC",
+        )?;
+        let counts = stmt
+            .query_map(params![&input.month, input.include_archived], |row| {
+                Ok(DayCount {
+                    date_ymd: row.get(0)?,
+                    count: row.get(1)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<DayCount>>>()?;
+
+        Ok(counts)
+    }
+
+    /// Open and done todo memos per day in the range, from todo categories only.
+    pub fn count_todos_by_day(&self, input: DateRangeInput) -> AppResult<Vec<TodoDayCount>> {
+        input.validate()?;
+
+        let mut stmt = self.conn.prepare(
+            "SELECT m.date_ymd, SUM(m.todo_done = 0), SUM(m.todo_done <> 0)
+             FROM memos AS m JOIN categories AS c ON c.id = m.category_id
+             WHERE m.date_ymd BETWEEN ?1 AND ?2
+               AND c.kind = 'todo' AND (?3 OR (c.archived = 0 AND m.archived_at IS NULL))
+             GROUP BY m.date_ymd ORDER BY m.date_ymd ASC",
+        )?;
+        let counts = stmt
+            .query_map(
+                params![&input.from_ymd, &input.to_ymd, input.include_archived],
+                |row| {
+                    Ok(TodoDayCount {
+                        date_ymd: row.get(0)?,
+                        open: row.get(1)?,
+                        done: row.get(2)?,
+                    })
+                },
+            )?
+            .collect::<rusqlite::Result<Vec<TodoDayCount>>>()?;
+
+        Ok(counts)
+    }
+}
+
+const STATS_DEFAULT_WEEKS: u32 = 12;
+const STATS_MAX_WEEKS: u32 = 520;
+
+/// Picks the counter a weekly query adds to.
+type WeekField = fn(&mut WeekActivity) -> &mut i64;
+
+/// Local Monday (`YYYY-MM-DD`) of the week a millisecond timestamp falls in.
+macro_rules! week_of {
+    ($ts:literal) => {
+        concat!(
+            "strftime('%Y-%m-%d', ",
+            $ts,
+            "/1000, 'unixepoch', 'localtime', 'weekday 0', '-6 days')"
+        )
+    };
+}
+
+impl Store {
+    /// Counts, text size, todo completion, weekly activity and writing streaks,
+    /// per category and overall. Weekly updates and streaks count the days a
+    /// memo was edited as recorded in `memo_activity`.
+    pub fn get_statistics(&self, input: StatisticsInput) -> AppResult<Statistics> {
+        let weeks = i64::from(
+            input
+                .weeks
+                .unwrap_or(STATS_DEFAULT_WEEKS)
+                .clamp(1, STATS_MAX_WEEKS),
+        );
+
+        let categories = self
+            .conn
+            .prepare(concat!(
+                category_select!(),
+                " WHERE ?1 OR archived = 0 ORDER BY rank ASC"
+            ))?
+            .query_map(params![input.include_archived], row_to_category)?
+            .collect::<rusqlite::Result<Vec<Category>>>()?;
+        let index: HashMap<&str, usize> = categories
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (c.id.as_str(), i))
+            .collect();
+
+        let this_week: String = self.conn.query_row(
+            concat!("SELECT ", week_of!("?1")),
+            params![now_timestamp_ms()],
+            |row| row.get(0),
+        )?;
+        let first_day = stats::day_number(&this_week).unwrap_or(0) - 7 * (weeks - 1);
+        let empty_weeks: Vec<WeekActivity> = (0..weeks)
+            .map(|i| WeekActivity {
+                week_start: stats::date_ymd(first_day + 7 * i),
+                created: 0,
+                updated: 0,
+                completed: 0,
+            })
+            .collect();
+        let first_week = stats::date_ymd(first_day);
+
+        let mut overall = StatsSummary {
+            weekly: empty_weeks.clone(),
+            ..Default::default()
+        };
+        let mut per_category: Vec<StatsSummary> = categories
+            .iter()
+            .map(|_| StatsSummary {
+                weekly: empty_weeks.clone(),
+                ..Default::default()
+            })
+            .collect();
+
+        let mut stmt = self.conn.prepare(
+            "SELECT m.category_id, m.title, m.content_md, m.todo_done, c.kind
+             FROM memos AS m JOIN categories AS c ON c.id = m.category_id",
+        )?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let category_id: String = row.get(0)?;
+            let Some(&i) = index.get(category_id.as_str()) else {
+                continue;
+            };
+            let title: String = row.get(1)?;
+            let content_md: String = row.get(2)?;
+            let (words, chars) =
+                stats::count_text(&format!("{title} {}", stats::plain_text(&content_md)));
+            let is_todo = row.get::<_, CategoryKind>(4)? == CategoryKind::Todo;
+            let done = is_todo && row.get::<_, i64>(3)? != 0;
+            for summary in [&mut per_category[i], &mut overall] {
+                summary.memo_count += 1;
+                summary.word_count += words;
+                summary.char_count += chars;
+                summary.todo_total += i64::from(is_todo);
+                summary.todo_done += i64::from(done);
+            }
+        }
+        drop(rows);
+        drop(stmt);
+
+        let weekly_queries: [(&str, WeekField); 3] = [
+            (
+                concat!(
+                    "SELECT category_id, ",
+                    week_of!("created_at"),
+                    " AS week, COUNT(*) FROM memos GROUP BY 1, 2 HAVING week >= ?1"
+                ),
+                |w| &mut w.created,
+            ),
+            (
+                "SELECT m.category_id, strftime('%Y-%m-%d', a.day, 'weekday 0', '-6 days') AS week,
+                        COUNT(DISTINCT a.memo_id)
+                 FROM memo_activity AS a JOIN memos AS m ON m.id = a.memo_id
+                 GROUP BY 1, 2 HAVING week >= ?1",
+                |w| &mut w.updated,
+            ),
+            (
+                concat!(
+                    "SELECT category_id, ",
+                    week_of!("completed_at"),
+                    " AS week, COUNT(*) FROM memos
+                     WHERE completed_at IS NOT NULL GROUP BY 1, 2 HAVING week >= ?1"
+                ),
+                |w| &mut w.completed,
+            ),
+        ];
+        for (sql, field) in weekly_queries {
+            let mut stmt = self.conn.prepare(sql)?;
+            let mut rows = stmt.query(params![&first_week])?;
+            while let Some(row) = rows.next()? {
+                let category_id: String = row.get(0)?;
+                let week: String = row.get(1)?;
+                let count: i64 = row.get(2)?;
+                let (Some(&i), Some(day)) =
+                    (index.get(category_id.as_str()), stats::day_number(&week))
+                else {
+                    continue;
+                };
+                let Ok(w) = usize::try_from((day - first_day) / 7) else {
+                    continue;
+                };
+                if w < per_category[i].weekly.len() {
+                    *field(&mut per_category[i].weekly[w]) += count;
+                    *field(&mut overall.weekly[w]) += count;
+                }
+            }
+        }
+
+        let mut stmt = self.conn.prepare(
+            "SELECT DISTINCT m.category_id, a.day
+             FROM (SELECT id AS memo_id,
+                          strftime('%Y-%m-%d', created_at/1000, 'unixepoch', 'localtime') AS day
+                   FROM memos
+                   UNION ALL SELECT memo_id, day FROM memo_activity) AS a
+             JOIN memos AS m ON m.id = a.memo_id
+             ORDER BY a.day ASC",
+        )?;
+        let mut days: Vec<Vec<i64>> = vec![Vec::new(); categories.len()];
+        let mut all_days: Vec<i64> = Vec::new();
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let category_id: String = row.get(0)?;
+            let day: String = row.get(1)?;
+            let (Some(&i), Some(day)) = (index.get(category_id.as_str()), stats::day_number(&day))
+            else {
+                continue;
+            };
+            days[i].push(day);
+            if all_days.last() != Some(&day) {
+                all_days.push(day);
+            }
+        }
+        drop(rows);
+        drop(stmt);
+
+        for (summary, days) in per_category.iter_mut().zip(&days) {
+            summary.longest_streak_days = stats::longest_streak(days);
+        }
+        overall.longest_streak_days = stats::longest_streak(&all_days);
+        for summary in per_category.iter_mut().chain([&mut overall]) {
+            summary.todo_completion_rate = (summary.todo_total > 0)
+                .then(|| summary.todo_done as f64 / summary.todo_total as f64);
+        }
+
+        Ok(Statistics {
+            overall,
+            categories: categories
+                .into_iter()
+                .zip(per_category)
+                .map(|(category, stats)| CategoryStats {
+                    category_id: category.id,
+                    title: category.title,
+                    stats,
+                })
+                .collect(),
+        })
+    }
+}
+
+/// Title of the category `repair_database` moves orphaned memos into.
+const RECOVERED_CATEGORY_TITLE: &str = "Recovered";
+const RECOVERED_CATEGORY_COLOR: &str = "#ffffff";
+
+/// Rank-ordered tables, with the column that splits them into lists.
+const RANKED_LISTS: &[(&str, Option<&str>)] = &[
+    ("categories", None),
+    ("smart_categories", None),
+    ("memos", Some("category_id")),
+];
+
+/// `(table, column, label)` of text columns that must not be blank.
+const REQUIRED_FIELDS: &[(&str, &str, &str)] = &[
+    ("categories", "title", "카테고리 제목"),
+    ("categories", "color", "카테고리 색상"),
+    ("memos", "color", "메모 색상"),
+    ("templates", "name", "템플릿 이름"),
+    ("templates", "color", "템플릿 색상"),
+    ("smart_categories", "title", "스마트 카테고리 제목"),
+    ("smart_categories", "color", "스마트 카테고리 색상"),
+];
+
+fn table_label(table: &str) -> &str {
+    match table {
+        "categories" => "카테고리",
+        "memos" => "메모",
+        "memo_revisions" => "메모 기록",
+        "memo_activity" => "메모 활동",
+        "templates" => "템플릿",
+        "smart_categories" => "스마트 카테고리",
+        other => other,
+    }
+}
+
+fn issue(
+    kind: IntegrityIssueKind,
+    table: &str,
+    ids: Vec<String>,
+    message: String,
+    detail: String,
+    repairable: bool,
+) -> IntegrityIssue {
+    IntegrityIssue {
+        kind,
+        table: table.to_string(),
+        ids,
+        message,
+        detail,
+        repairable,
+    }
+}
+
+/// `PRAGMA integrity_check` output, minus the lone `ok` of a healthy file.
+fn integrity_errors(conn: &Connection) -> AppResult<Vec<String>> {
+    let lines: Vec<String> = conn
+        .prepare("PRAGMA integrity_check")?
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(lines.into_iter().filter(|line| line != "ok").collect())
+}
+
+struct ForeignKeyViolation {
+    table: String,
+    parent: String,
+    rowid: i64,
+    /// `id` of the row (`memo_id` for revisions and activity).
+    id: String,
+}
+
+fn foreign_key_violations(conn: &Connection) -> AppResult<Vec<ForeignKeyViolation>> {
+    let rows: Vec<(String, Option<i64>, String)> = conn
+        .prepare("PRAGMA foreign_key_check")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+
+    let mut violations = Vec::new();
+    for (table, rowid, parent) in rows {
+        let Some(rowid) = rowid else { continue };
+        let column = match table.as_str() {
+            "memo_revisions" | "memo_activity" => "memo_id",
+            _ => "id",
+        };
+        let id: String = conn.query_row(
+            &format!("SELECT {column} FROM {table} WHERE rowid = ?1"),
+            params![rowid],
+            |row| row.get(0),
+        )?;
+        violations.push(ForeignKeyViolation {
+            table,
+            parent,
+            rowid,
+            id,
+        });
+    }
+    Ok(violations)
+}
+
+/// One list of a rank-ordered table whose ranks tie or are malformed.
+struct BrokenRanks {
+    table: &'static str,
+    /// Category id, for memos.
+    list: Option<String>,
+    duplicate: Vec<String>,
+    invalid: Vec<String>,
+}
+
+fn broken_rank_lists(conn: &Connection) -> AppResult<Vec<BrokenRanks>> {
+    let mut broken = Vec::new();
+    for &(table, split) in RANKED_LISTS {
+        let split = split.unwrap_or("NULL");
+        let rows: Vec<(String, String, Option<String>)> = conn
+            .prepare(&format!(
+                "SELECT id, rank, {split} FROM {table} ORDER BY {split}, rank, id"
+            ))?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+
+        for list in rows.chunk_by(|a, b| a.2 == b.2) {
+            let duplicate: Vec<String> = list
+                .chunk_by(|a, b| a.1 == b.1)
+                .filter(|tied| tied.len() > 1)
+                .flatten()
+                .map(|row| row.0.clone())
+                .collect();
+            let invalid: Vec<String> = list
+                .iter()
+                .filter(|row| !rank::is_valid(&row.1))
+                .map(|row| row.0.clone())
+                .collect();
+            if !duplicate.is_empty() || !invalid.is_empty() {
+                broken.push(BrokenRanks {
+                    table,
+                    list: list[0].2.clone(),
+                    duplicate,
+                    invalid,
+                });
+            }
+        }
+    }
+    Ok(broken)
+}
+
+/// The active "Recovered" category, created at the end of the board when
+/// there is none.
+fn recovered_category(conn: &Connection, changes: &mut Vec<RepairChange>) -> AppResult<String> {
+    let existing: Option<String> = conn
+        .query_row(
+            "SELECT id FROM categories
+             WHERE title = ?1 AND archived = 0 AND kind = 'normal'
+             ORDER BY rank LIMIT 1",
+            params![RECOVERED_CATEGORY_TITLE],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if let Some(id) = existing {
+        return Ok(id);
+    }
+
+    let id = Uuid::new_v4().to_string();
+    let rank = next_rank(conn, "categories", None)?;
+    let ts = now_timestamp_ms();
+    conn.execute(
+        "INSERT INTO categories (id, emoji, title, color, rank, archived, kind, is_collapsed, created_at, updated_at)
+         VALUES (?1, '', ?2, ?3, ?4, 0, 'normal', 0, ?5, ?5)",
+        params![&id, RECOVERED_CATEGORY_TITLE, RECOVERED_CATEGORY_COLOR, &rank, ts],
+    )?;
+    changes.push(RepairChange {
+        action: RepairAction::CreateCategory,
+        table: "categories".to_string(),
+        ids: vec![id.clone()],
+        message: format!("'{RECOVERED_CATEGORY_TITLE}' 카테고리를 만들었습니다."),
+    });
+    Ok(id)
+}
+
+impl Store {
+    /// Looks for damage that the app's own writes should never leave behind:
+    /// SQLite integrity and foreign key errors, memos whose category is gone,
+    /// tied or malformed ranks (positions are numbered from ranks, so these
+    /// are what duplicate or gapped positions come from), invalid memo dates
+    /// and blank required fields. Changes nothing.
+    pub fn check_database(&self) -> AppResult<IntegrityReport> {
+        let conn = &self.conn;
+        let mut issues = Vec::new();
+
+        let errors = integrity_errors(conn)?;
+        if !errors.is_empty() {
+            issues.push(issue(
+                IntegrityIssueKind::Integrity,
+                "",
+                Vec::new(),
+                format!(
+                    "데이터베이스 파일 검사에서 문제 {}건이 발견되었습니다.",
+                    errors.len()
+                ),
+                errors.join("; "),
+                false,
+            ));
+        }
+
+        let mut violations: Vec<((String, String), Vec<String>)> = Vec::new();
+        for violation in foreign_key_violations(conn)? {
+            let key = (violation.table, violation.parent);
+            match violations.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, ids)) => ids.push(violation.id),
+                None => violations.push((key, vec![violation.id])),
+            }
+        }
+        for ((table, parent), ids) in violations {
+            let n = ids.len();
+            let detail = format!("{table} -> {parent}");
+            issues.push(match (table.as_str(), parent.as_str()) {
+                ("memos", "categories") => issue(
+                    IntegrityIssueKind::OrphanMemo,
+                    &table,
+                    ids,
+                    format!("카테고리가 없는 메모가 {n}개 있습니다."),
+                    detail,
+                    true,
+                ),
+                ("memo_revisions", "memos") => issue(
+                    IntegrityIssueKind::ForeignKey,
+                    &table,
+                    ids,
+                    format!("메모가 없는 메모 기록이 {n}개 있습니다."),
+                    detail,
+                    true,
+                ),
+                ("memo_activity", "memos") => issue(
+                    IntegrityIssueKind::ForeignKey,
+                    &table,
+                    ids,
+                    format!("메모가 없는 메모 활동이 {n}개 있습니다."),
+                    detail,
+                    true,
+                ),
+                ("categories", "templates") => issue(
+                    IntegrityIssueKind::ForeignKey,
+                    &table,
+                    ids,
+                    format!("기본 템플릿이 없어진 카테고리가 {n}개 있습니다."),
+                    detail,
+                    true,
+                ),
+                _ => issue(
+                    IntegrityIssueKind::ForeignKey,
+                    &table,
+                    ids,
+                    format!(
+                        "{} {n}개가 없는 {} 항목을 가리킵니다.",
+                        table_label(&table),
+                        table_label(&parent)
+                    ),
+                    detail,
+                    false,
+                ),
+            });
+        }
+
+        for broken in broken_rank_lists(conn)? {
+            let label = table_label(broken.table);
+            let detail = match &broken.list {
+                Some(category_id) => format!("{} in category {category_id}", broken.table),
+                None => broken.table.to_string(),
+            };
+            if !broken.duplicate.is_empty() {
+                issues.push(issue(
+                    IntegrityIssueKind::DuplicateRank,
+                    broken.table,
+                    broken.duplicate.clone(),
+                    format!("{label} {}개의 순서 값이 겹칩니다.", broken.duplicate.len()),
+                    detail.clone(),
+                    true,
+                ));
+            }
+            if !broken.invalid.is_empty() {
+                issues.push(issue(
+                    IntegrityIssueKind::InvalidRank,
+                    broken.table,
+                    broken.invalid.clone(),
+                    format!(
+                        "{label} {}개의 순서 값이 올바르지 않습니다.",
+                        broken.invalid.len()
+                    ),
+                    detail,
+                    true,
+                ));
+            }
+        }
+
+        let dates: Vec<(String, String)> = conn
+            .prepare("SELECT id, date_ymd FROM memos ORDER BY id")?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+        let invalid_dates: Vec<&(String, String)> = dates
+            .iter()
+            .filter(|(_, date)| !is_valid_date_ymd(date))
+            .collect();
+        if !invalid_dates.is_empty() {
+            issues.push(issue(
+                IntegrityIssueKind::InvalidDate,
+                "memos",
+                invalid_dates.iter().map(|(id, _)| id.clone()).collect(),
+                format!("메모 {}개의 날짜가 올바르지 않습니다.", invalid_dates.len()),
+                invalid_dates
+                    .iter()
+                    .map(|(id, date)| format!("{id}: {date:?}"))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                false,
+            ));
+        }
+
+        for &(table, column, label) in REQUIRED_FIELDS {
+            let ids: Vec<String> = conn
+                .prepare(&format!(
+                    "SELECT id FROM {table} WHERE TRIM({column}) = '' ORDER BY id"
+                ))?
+                .query_map([], |row| row.get(0))?
+                .collect::<rusqlite::Result<_>>()?;
+            if !ids.is_empty() {
+                let n = ids.len();
+                issues.push(issue(
+                    IntegrityIssueKind::EmptyField,
+                    table,
+                    ids,
+                    format!("{label}이 비어 있는 항목이 {n}개 있습니다."),
+                    format!("{table}.{column}"),
+                    false,
+                ));
+            }
+        }
+
+        Ok(IntegrityReport {
+            ok: issues.is_empty(),
+            issues,
+        })
+    }
+
+    /// Fixes what `check_database` marks repairable, in one transaction:
+    /// orphaned memos move to a "Recovered" category, revisions of missing
+    /// memos are deleted, missing default templates are cleared and lists
+    /// with tied or malformed ranks are re-ranked in their current order.
+    /// Integrity errors get a `REINDEX`, which helps when only an index is
+    /// damaged. Reports each change and what is still wrong afterwards.
+    pub fn repair_database(&mut self) -> AppResult<RepairReport> {
+        let mut changes = Vec::new();
+        let tx = self.conn.transaction()?;
+
+        if !integrity_errors(&tx)?.is_empty() {
+            tx.execute_batch("REINDEX;")?;
+            changes.push(RepairChange {
+                action: RepairAction::Reindex,
+                table: String::new(),
+                ids: Vec::new(),
+                message: "인덱스를 모두 다시 만들었습니다.".to_string(),
+            });
+        }
+
+        let mut has_orphans = false;
+        for violation in foreign_key_violations(&tx)? {
+            match (violation.table.as_str(), violation.parent.as_str()) {
+                ("memos", "categories") => has_orphans = true,
+                ("memo_revisions", "memos") => {
+                    tx.execute(
+                        "DELETE FROM memo_revisions WHERE rowid = ?1",
+                        params![violation.rowid],
+                    )?;
+                    changes.push(RepairChange {
+                        action: RepairAction::DeleteRevision,
+                        table: violation.table,
+                        ids: vec![violation.id],
+                        message: "메모가 없는 메모 기록을 삭제했습니다.".to_string(),
+                    });
+                }
+                ("memo_activity", "memos") => {
+                    tx.execute(
+                        "DELETE FROM memo_activity WHERE rowid = ?1",
+                        params![violation.rowid],
+                    )?;
+                    changes.push(RepairChange {
+                        action: RepairAction::DeleteActivity,
+                        table: violation.table,
+                        ids: vec![violation.id],
+                        message: "메모가 없는 메모 활동을 삭제했습니다.".to_string(),
+                    });
+                }
+                ("categories", "templates") => {
+                    tx.execute(
+                        "UPDATE categories SET default_template_id = NULL WHERE rowid = ?1",
+                        params![violation.rowid],
+                    )?;
+                    changes.push(RepairChange {
+                        action: RepairAction::ClearDefaultTemplate,
+                        table: violation.table,
+                        ids: vec![violation.id],
+                        message: "없어진 기본 템플릿 지정을 해제했습니다.".to_string(),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        if has_orphans {
+            let orphans: Vec<String> = tx
+                .prepare(
+                    "SELECT id FROM memos
+                     WHERE category_id NOT IN (SELECT id FROM categories)
+                     ORDER BY category_id, rank, id",
+                )?
+                .query_map([], |row| row.get(0))?
+                .collect::<rusqlite::Result<_>>()?;
+            let category_id = recovered_category(&tx, &mut changes)?;
+            for memo_id in &orphans {
+                let rank = next_rank(&tx, "memos", Some(("category_id", &category_id)))?;
+                place_memo(&tx, memo_id, &category_id, &rank)?;
+            }
+            changes.push(RepairChange {
+                action: RepairAction::MoveMemo,
+                table: "memos".to_string(),
+                message: format!(
+                    "카테고리가 없는 메모 {}개를 '{RECOVERED_CATEGORY_TITLE}' 카테고리로 옮겼습니다.",
+                    orphans.len()
+                ),
+                ids: orphans,
+            });
+        }
+
+        for broken in broken_rank_lists(&tx)? {
+            // Pinned memos must stay ahead of the rest.
+            let order_by = if broken.table == "memos" {
+                "pinned DESC, rank ASC, created_at ASC, id ASC"
+            } else {
+                "rank ASC, created_at ASC, id ASC"
+            };
+            let where_clause = broken.list.as_deref().map(|list| ("category_id", list));
+            let count = write_spread_ranks(&tx, broken.table, where_clause, order_by)?;
+            changes.push(RepairChange {
+                action: RepairAction::Rerank,
+                table: broken.table.to_string(),
+                ids: broken.list.into_iter().collect(),
+                message: format!(
+                    "{} {count}개의 순서 값을 현재 순서대로 다시 매겼습니다.",
+                    table_label(broken.table)
+                ),
+            });
+        }
+
+        tx.commit()?;
+        Ok(RepairReport {
+            changes,
+            remaining: self.check_database()?.issues,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn category(store: &mut Store, title: &str) -> String {
+        store
+            .create_category(CreateCategoryInput {
+                emoji: None,
+                title: title.to_string(),
+                color: "#ffffff".to_string(),
+                is_todo: false,
+                kind: None,
+            })
+            .unwrap()
+            .id
+    }
+
+    fn memo(store: &mut Store, category_id: &str, title: &str) -> String {
+        store
+            .create_memo(CreateMemoInput {
+                category_id: category_id.to_string(),
+                emoji: None,
+                title: title.to_string(),
+                color: "#ffffff".to_string(),
+                date_ymd: Some("2026-01-01".to_string()),
+                content_md: String::new(),
+            })
+            .unwrap()
+            .id
+    }
+
+    fn move_to(store: &mut Store, memo_id: &str, category_id: &str, slot: MemoSlot) {
+        store
+            .move_memo_to(MoveMemoToInput {
+                memo_id: memo_id.to_string(),
+                category_id: category_id.to_string(),
+                slot,
+            })
+            .unwrap();
+    }
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    /// Memo titles of a category as the board lists them, after checking
+    /// that positions run 0, 1, 2, ... and ranks strictly increase.
+    fn titles(store: &mut Store, category_id: &str) -> Vec<String> {
+        let board = store.list_categories_with_memos().unwrap();
+        let entry = board
+            .iter()
+            .find(|entry| entry.category.id == category_id)
+            .unwrap();
+        for (i, memo) in entry.memos.iter().enumerate() {
+            assert_eq!(memo.position, i as i64, "position of {}", memo.title);
+        }
+        assert!(entry.memos.windows(2).all(|w| w[0].rank < w[1].rank));
+        entry.memos.iter().map(|memo| memo.title.clone()).collect()
+    }
+
+    /// Category titles in board order, with the same checks as `titles`.
+    fn category_titles(store: &mut Store) -> Vec<String> {
+        let board = store.list_categories_with_memos().unwrap();
+        for (i, entry) in board.iter().enumerate() {
+            assert_eq!(entry.category.position, i as i64);
+        }
+        assert!(board
+            .windows(2)
+            .all(|w| w[0].category.rank < w[1].category.rank));
+        board
+            .into_iter()
+            .map(|entry| entry.category.title)
+            .collect()
+    }
+
+    fn is_not_a_permutation(err: AppError) -> bool {
+        matches!(
+            err,
+            AppError::PreconditionFailed {
+                reason: "not_a_permutation",
+                ..
+            }
+        )
+    }
+
+    #[test]
+    fn created_memos_are_appended() {
+        let mut store = Store::open_in_memory().unwrap();
+        let c = category(&mut store, "C");
+        for title in ["a", "b", "c"] {
+            memo(&mut store, &c, title);
+        }
+        assert_eq!(titles(&mut store, &c), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn moves_within_a_category_follow_the_slot() {
+        let mut store = Store::open_in_memory().unwrap();
+        let c = category(&mut store, "C");
+        let a = memo(&mut store, &c, "a");
+        memo(&mut store, &c, "b");
+        let m = memo(&mut store, &c, "c");
+        let d = memo(&mut store, &c, "d");
+
+        let after_a = MemoSlot {
+            after_id: Some(a.clone()),
+            ..Default::default()
+        };
+        move_to(&mut store, &d, &c, after_a);
+        assert_eq!(titles(&mut store, &c), ["a", "d", "b", "c"]);
+
+        let before_c = MemoSlot {
+            before_id: Some(m.clone()),
+            ..Default::default()
+        };
+        move_to(&mut store, &a, &c, before_c);
+        assert_eq!(titles(&mut store, &c), ["d", "b", "a", "c"]);
+
+        let first = MemoSlot {
+            index: Some(0),
+            ..Default::default()
+        };
+        move_to(&mut store, &m, &c, first);
+        assert_eq!(titles(&mut store, &c), ["c", "d", "b", "a"]);
+
+        move_to(&mut store, &m, &c, MemoSlot::default());
+        assert_eq!(titles(&mut store, &c), ["d", "b", "a", "c"]);
+    }
+
+    #[test]
+    fn moves_between_categories_leave_no_gaps() {
+        let mut store = Store::open_in_memory().unwrap();
+        let from = category(&mut store, "From");
+        let to = category(&mut store, "To");
+        let a = memo(&mut store, &from, "a");
+        let b = memo(&mut store, &from, "b");
+        let c = memo(&mut store, &from, "c");
+        memo(&mut store, &to, "x");
+        memo(&mut store, &to, "y");
+
+        let second = MemoSlot {
+            index: Some(1),
+            ..Default::default()
+        };
+        move_to(&mut store, &b, &to, second);
+        assert_eq!(titles(&mut store, &from), ["a", "c"]);
+        assert_eq!(titles(&mut store, &to), ["x", "b", "y"]);
+
+        // Bulk moves append in board order, whatever order the ids come in.
+        let report = store
+            .move_memos(MoveMemosInput {
+                memo_ids: vec![c, a],
+                to_category_id: to.clone(),
+            })
+            .unwrap();
+        assert_eq!(report.succeeded, 2);
+        assert!(titles(&mut store, &from).is_empty());
+        assert_eq!(titles(&mut store, &to), ["x", "b", "y", "a", "c"]);
+    }
+
+    #[test]
+    fn reorder_applies_a_permutation_and_rejects_anything_else() {
+        let mut store = Store::open_in_memory().unwrap();
+        let c = category(&mut store, "C");
+        let a = memo(&mut store, &c, "a");
+        let b = memo(&mut store, &c, "b");
+        let m = memo(&mut store, &c, "c");
+
+        store
+            .reorder_memos(ReorderMemosInput {
+                category_id: c.clone(),
+                ordered_ids: ids(&[&m, &a, &b]),
+            })
+            .unwrap();
+        assert_eq!(titles(&mut store, &c), ["c", "a", "b"]);
+
+        for ordered in [
+            ids(&[&m, &a]),
+            ids(&[&m, &a, &a]),
+            ids(&[&m, &a, &b, "unknown"]),
+        ] {
+            let err = store
+                .reorder_memos(ReorderMemosInput {
+                    category_id: c.clone(),
+                    ordered_ids: ordered,
+                })
+                .unwrap_err();
+            assert!(is_not_a_permutation(err));
+        }
+        assert_eq!(titles(&mut store, &c), ["c", "a", "b"]);
+    }
+
+    #[test]
+    fn deletes_close_the_gap() {
+        let mut store = Store::open_in_memory().unwrap();
+        let c = category(&mut store, "C");
+        let a = memo(&mut store, &c, "a");
+        let b = memo(&mut store, &c, "b");
+        memo(&mut store, &c, "c");
+        memo(&mut store, &c, "d");
+
+        store.delete_memo(&b).unwrap();
+        assert_eq!(titles(&mut store, &c), ["a", "c", "d"]);
+
+        let report = store
+            .delete_memos(DeleteMemosInput {
+                memo_ids: vec![a, "unknown".to_string()],
+            })
+            .unwrap();
+        assert_eq!((report.succeeded, report.failed), (1, 1));
+        assert_eq!(titles(&mut store, &c), ["c", "d"]);
+    }
+
+    #[test]
+    fn pinned_memos_stay_first() {
+        let mut store = Store::open_in_memory().unwrap();
+        let c = category(&mut store, "C");
+        let a = memo(&mut store, &c, "a");
+        let b = memo(&mut store, &c, "b");
+        let m = memo(&mut store, &c, "c");
+
+        store
+            .set_memo_pinned(SetMemoPinnedInput {
+                id: m.clone(),
+                pinned: true,
+            })
+            .unwrap();
+        assert_eq!(titles(&mut store, &c), ["c", "a", "b"]);
+
+        let first = MemoSlot {
+            index: Some(0),
+            ..Default::default()
+        };
+        move_to(&mut store, &b, &c, first);
+        assert_eq!(titles(&mut store, &c), ["c", "b", "a"]);
+
+        store
+            .reorder_memos(ReorderMemosInput {
+                category_id: c.clone(),
+                ordered_ids: ids(&[&a, &b, &m]),
+            })
+            .unwrap();
+        assert_eq!(titles(&mut store, &c), ["c", "a", "b"]);
+    }
+
+    #[test]
+    fn archived_memos_keep_their_slot() {
+        let mut store = Store::open_in_memory().unwrap();
+        let c = category(&mut store, "C");
+        memo(&mut store, &c, "a");
+        let b = memo(&mut store, &c, "b");
+        memo(&mut store, &c, "c");
+
+        let archive = |archived| SetMemoArchivedInput {
+            id: b.clone(),
+            archived,
+        };
+        store.set_memo_archived(archive(true)).unwrap();
+        assert_eq!(titles(&mut store, &c), ["a", "c"]);
+        store.set_memo_archived(archive(false)).unwrap();
+        assert_eq!(titles(&mut store, &c), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn positions_skip_archived_rows_the_same_way() {
+        let mut store = Store::open_in_memory().unwrap();
+        let a = category(&mut store, "A");
+        let b = category(&mut store, "B");
+        let c = category(&mut store, "C");
+        memo(&mut store, &c, "x");
+        let y = memo(&mut store, &c, "y");
+        let z = memo(&mut store, &c, "z");
+
+        store
+            .set_category_archived(SetCategoryArchivedInput {
+                id: a.clone(),
+                archived: true,
+            })
+            .unwrap();
+        store
+            .set_memo_archived(SetMemoArchivedInput {
+                id: y.clone(),
+                archived: true,
+            })
+            .unwrap();
+
+        assert_eq!(fetch_category(&store.conn, &a).unwrap().position, 0);
+        assert_eq!(fetch_category(&store.conn, &b).unwrap().position, 0);
+        assert_eq!(fetch_category(&store.conn, &c).unwrap().position, 1);
+        assert_eq!(store.get_memo(&y).unwrap().position, 0);
+        assert_eq!(store.get_memo(&z).unwrap().position, 1);
+        assert_eq!(titles(&mut store, &c), ["x", "z"]);
+    }
+
+    #[test]
+    fn categories_reorder_and_delete_without_gaps() {
+        let mut store = Store::open_in_memory().unwrap();
+        let a = category(&mut store, "A");
+        let b = category(&mut store, "B");
+        let c = category(&mut store, "C");
+        assert_eq!(category_titles(&mut store), ["A", "B", "C"]);
+
+        store
+            .reorder_categories(ReorderCategoriesInput {
+                ordered_ids: ids(&[&c, &a, &b]),
+            })
+            .unwrap();
+        assert_eq!(category_titles(&mut store), ["C", "A", "B"]);
+
+        let err = store
+            .reorder_categories(ReorderCategoriesInput {
+                ordered_ids: ids(&[&c, &a]),
+            })
+            .unwrap_err();
+        assert!(is_not_a_permutation(err));
+
+        store.delete_category(&a).unwrap();
+        assert_eq!(category_titles(&mut store), ["C", "B"]);
+    }
+
+    #[test]
+    fn bulk_edits_report_each_id_once() {
+        let mut store = Store::open_in_memory().unwrap();
+        let c = category(&mut store, "C");
+        let a = memo(&mut store, &c, "a");
+        let b = memo(&mut store, &c, "b");
+
+        let report = store
+            .set_memos_todo_done(SetMemosTodoDoneInput {
+                memo_ids: ids(&[&a, "unknown", &a, &b]),
+                todo_done: true,
+            })
+            .unwrap();
+        assert_eq!((report.succeeded, report.failed), (2, 1));
+        let outcome: Vec<_> = report.items.iter().map(|item| item.ok).collect();
+        assert_eq!(outcome, [true, false, true]);
+        assert!(matches!(
+            report.items[1].error,
+            Some(AppError::NotFound { .. })
+        ));
+        let done = store.get_memo(&a).unwrap();
+        assert!(done.todo_done && done.completed_at.is_some());
+        assert_eq!(done.version, 2);
+
+        store
+            .set_memos_color(SetMemosColorInput {
+                memo_ids: ids(&[&a, &b]),
+                color: "#ff0000".to_string(),
+            })
+            .unwrap();
+        store
+            .set_memos_date(SetMemosDateInput {
+                memo_ids: ids(&[&b]),
+                date_ymd: "2026-02-03".to_string(),
+            })
+            .unwrap();
+        assert_eq!(store.get_memo(&a).unwrap().color, "#ff0000");
+        let b_memo = store.get_memo(&b).unwrap();
+        assert_eq!(
+            (b_memo.color.as_str(), b_memo.date_ymd.as_str()),
+            ("#ff0000", "2026-02-03")
+        );
+    }
+
+    #[test]
+    fn bulk_edits_reject_bad_input_up_front() {
+        let mut store = Store::open_in_memory().unwrap();
+        let c = category(&mut store, "C");
+        let a = memo(&mut store, &c, "a");
+
+        let err = store
+            .set_memos_color(SetMemosColorInput {
+                memo_ids: ids(&[&a]),
+                color: "#12345".to_string(),
+            })
+            .unwrap_err();
+        assert!(matches!(err, AppError::Validation(_)));
+        let err = store
+            .set_memos_date(SetMemosDateInput {
+                memo_ids: ids(&[&a]),
+                date_ymd: "2026-13-01".to_string(),
+            })
+            .unwrap_err();
+        assert!(matches!(err, AppError::Validation(_)));
+
+        // An archived target fails the whole move, not each memo.
+        let archived = category(&mut store, "Archived");
+        store
+            .set_category_archived(SetCategoryArchivedInput {
+                id: archived.clone(),
+                archived: true,
+            })
+            .unwrap();
+        let err = store
+            .move_memos(MoveMemosInput {
+                memo_ids: ids(&[&a]),
+                to_category_id: archived,
+            })
+            .unwrap_err();
+        assert!(matches!(err, AppError::PreconditionFailed { .. }));
+        assert_eq!(titles(&mut store, &c), ["a"]);
+        assert_eq!(store.get_memo(&a).unwrap().version, 1);
+    }
+
+    #[test]
+    fn bulk_moves_unpin_and_skip_unknown_ids() {
+        let mut store = Store::open_in_memory().unwrap();
+        let from = category(&mut store, "From");
+        let to = category(&mut store, "To");
+        memo(&mut store, &from, "a");
+        let b = memo(&mut store, &from, "b");
+        memo(&mut store, &to, "x");
+        store
+            .set_memo_pinned(SetMemoPinnedInput {
+                id: b.clone(),
+                pinned: true,
+            })
+            .unwrap();
+
+        let report = store
+            .move_memos(MoveMemosInput {
+                memo_ids: ids(&["unknown", &b]),
+                to_category_id: to.clone(),
+            })
+            .unwrap();
+        assert_eq!((report.succeeded, report.failed), (1, 1));
+        assert_eq!(report.items.last().unwrap().id, "unknown");
+        assert!(!store.get_memo(&b).unwrap().pinned);
+        assert_eq!(titles(&mut store, &from), ["a"]);
+        assert_eq!(titles(&mut store, &to), ["x", "b"]);
+    }
+
+    #[test]
+    fn duplicates_keep_the_date_and_drop_pin_and_star() {
+        let mut store = Store::open_in_memory().unwrap();
+        let c = category(&mut store, "C");
+        let a = memo(&mut store, &c, "a");
+        let b = memo(&mut store, &c, "b");
+        memo(&mut store, &c, "c");
+        store
+            .set_memos_todo_done(SetMemosTodoDoneInput {
+                memo_ids: ids(&[&b]),
+                todo_done: true,
+            })
+            .unwrap();
+        store
+            .set_memo_starred(SetMemoStarredInput {
+                id: b.clone(),
+                starred: true,
+            })
+            .unwrap();
+
+        let copy = store
+            .duplicate_memo(DuplicateMemoInput {
+                id: b.clone(),
+                reset_todo: false,
+            })
+            .unwrap();
+        assert_eq!(copy.date_ymd, "2026-01-01");
+        assert!(copy.todo_done && !copy.starred && !copy.pinned);
+        assert_eq!(titles(&mut store, &c), ["a", "b", "b (copy)", "c"]);
+
+        let copy = store
+            .duplicate_memo(DuplicateMemoInput {
+                id: b.clone(),
+                reset_todo: true,
+            })
+            .unwrap();
+        assert!(!copy.todo_done && copy.completed_at.is_none());
+
+        // The copy of a pinned memo leads the unpinned ones.
+        store
+            .set_memo_pinned(SetMemoPinnedInput {
+                id: a.clone(),
+                pinned: true,
+            })
+            .unwrap();
+        store
+            .set_memo_pinned(SetMemoPinnedInput {
+                id: b,
+                pinned: true,
+            })
+            .unwrap();
+        let copy = store
+            .duplicate_memo(DuplicateMemoInput {
+                id: a,
+                reset_todo: false,
+            })
+            .unwrap();
+        assert!(!copy.pinned);
+        assert_eq!(
+            titles(&mut store, &c),
+            ["a", "b", "a (copy)", "b (copy)", "b (copy)", "c"]
+        );
+    }
+
+    #[test]
+    fn copy_titles_stay_trimmed_and_within_the_limit() {
+        assert_eq!(copy_title("메모"), "메모 (copy)");
+        assert_eq!(copy_title("  메모  "), "메모 (copy)");
+        assert_eq!(copy_title(""), "(copy)");
+        assert_eq!(copy_title("   "), "(copy)");
+
+        let long = copy_title(&"가".repeat(TITLE_MAX_CHARS));
+        assert_eq!(long.chars().count(), TITLE_MAX_CHARS);
+        assert!(long.ends_with(COPY_SUFFIX));
+
+        // A cut that lands on a space doesn't leave two spaces before the suffix.
+        let keep = TITLE_MAX_CHARS - COPY_SUFFIX.chars().count();
+        let spaced = format!("{} tail", "a".repeat(keep));
+        assert_eq!(
+            copy_title(&spaced),
+            format!("{}{COPY_SUFFIX}", "a".repeat(keep))
+        );
+        let spaced = format!("{} tail", "a".repeat(keep - 1));
+        assert_eq!(
+            copy_title(&spaced),
+            format!("{}{COPY_SUFFIX}", "a".repeat(keep - 1))
+        );
+    }
+
+    #[test]
+    fn duplicated_categories_copy_live_memos_in_order() {
+        let mut store = Store::open_in_memory().unwrap();
+        let c = category(&mut store, "C");
+        category(&mut store, "D");
+        memo(&mut store, &c, "a");
+        let b = memo(&mut store, &c, "b");
+        let m = memo(&mut store, &c, "c");
+        store
+            .set_memo_pinned(SetMemoPinnedInput {
+                id: m,
+                pinned: true,
+            })
+            .unwrap();
+        store
+            .set_memo_archived(SetMemoArchivedInput {
+                id: b,
+                archived: true,
+            })
+            .unwrap();
+
+        let copy = store
+            .duplicate_category(DuplicateCategoryInput {
+                id: c,
+                reset_todo: false,
+            })
+            .unwrap();
+        assert_eq!(copy.category.title, "C (copy)");
+        assert_eq!(category_titles(&mut store), ["C", "C (copy)", "D"]);
+        assert_eq!(titles(&mut store, &copy.category.id), ["c", "a"]);
+        assert!(copy.memos.iter().all(|memo| !memo.pinned));
+        assert!(copy.memos.iter().all(|memo| memo.date_ymd == "2026-01-01"));
+    }
+
+    fn open_entry(store: &mut Store, category_id: &str, date_ymd: &str) -> Memo {
+        store
+            .open_journal_entry(OpenJournalEntryInput {
+                category_id: category_id.to_string(),
+                date_ymd: Some(date_ymd.to_string()),
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn journal_entries_are_created_on_open_only() {
+        let mut store = Store::open_in_memory().unwrap();
+        let j = store
+            .create_category(CreateCategoryInput {
+                emoji: None,
+                title: "J".to_string(),
+                color: "#ffffff".to_string(),
+                is_todo: false,
+                kind: Some(CategoryKind::Journal),
+            })
+            .unwrap()
+            .id;
+        assert!(titles(&mut store, &j).is_empty());
+
+        let entry = open_entry(&mut store, &j, "2026-01-05");
+        assert_eq!(entry.title, "2026-01-05 월요일");
+        assert_eq!(open_entry(&mut store, &j, "2026-01-05").id, entry.id);
+
+        // Listing never brings a deleted entry back.
+        store.delete_memo(&entry.id).unwrap();
+        assert!(titles(&mut store, &j).is_empty());
+        let today = store
+            .open_journal_entry(OpenJournalEntryInput {
+                category_id: j.clone(),
+                date_ymd: None,
+            })
+            .unwrap();
+        assert_eq!(today.date_ymd, today_ymd(store.conn()).unwrap());
+        store.delete_memo(&today.id).unwrap();
+        assert!(titles(&mut store, &j).is_empty());
+
+        let c = category(&mut store, "C");
+        let err = store
+            .open_journal_entry(OpenJournalEntryInput {
+                category_id: c,
+                date_ymd: None,
+            })
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AppError::PreconditionFailed {
+                reason: "not_a_journal",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn journal_memos_stay_in_date_order() {
+        let mut store = Store::open_in_memory().unwrap();
+        let j = store
+            .create_category(CreateCategoryInput {
+                emoji: None,
+                title: "J".to_string(),
+                color: "#ffffff".to_string(),
+                is_todo: false,
+                kind: Some(CategoryKind::Journal),
+            })
+            .unwrap()
+            .id;
+        for date in ["2026-01-03", "2026-01-05", "2026-01-01", "2026-01-04"] {
+            open_entry(&mut store, &j, date);
+        }
+        let days = |store: &mut Store| -> Vec<String> {
+            titles(store, &j)
+                .into_iter()
+                .map(|title| title[8..10].to_string())
+                .collect()
+        };
+        assert_eq!(days(&mut store), ["05", "04", "03", "01"]);
+
+        // Memos moved in or redated find their date slot, not the requested one.
+        let c = category(&mut store, "C");
+        let m = memo(&mut store, &c, "x");
+        move_to(&mut store, &m, &j, MemoSlot::default());
+        store
+            .set_memos_date(SetMemosDateInput {
+                memo_ids: ids(&[&m]),
+                date_ymd: "2026-01-02".to_string(),
+            })
+            .unwrap();
+        assert_eq!(
+            titles(&mut store, &j),
+            [
+                "2026-01-05 월요일",
+                "2026-01-04 일요일",
+                "2026-01-03 토요일",
+                "x",
+                "2026-01-01 목요일"
+            ]
+        );
+
+        let err = store
+            .reorder_memos(ReorderMemosInput {
+                category_id: j.clone(),
+                ordered_ids: Vec::new(),
+            })
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AppError::PreconditionFailed {
+                reason: "journal_ordered_by_date",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn revision_pruning_leaves_statistics_alone() {
+        const DAY_MS: i64 = 86_400_000;
+        let mut store = Store::open_in_memory().unwrap();
+        let c = category(&mut store, "C");
+        let m = memo(&mut store, &c, "a");
+        let now = now_timestamp_ms();
+        let edit = |store: &Store, ts: i64| {
+            store
+                .conn()
+                .execute(
+                    "UPDATE memos SET version = version + 1, updated_at = ?1 WHERE id = ?2",
+                    params![ts, &m],
+                )
+                .unwrap();
+            record_memo_revision(store.conn(), &m).unwrap();
+        };
+        let count = |store: &Store, table: &str| -> i64 {
+            store
+                .conn()
+                .query_row(
+                    &format!("SELECT COUNT(*) FROM {table} WHERE memo_id = ?1"),
+                    params![&m],
+                    |row| row.get(0),
+                )
+                .unwrap()
+        };
+
+        // One edit a day for 30 days, then many more today.
+        for days_ago in (0..30).rev() {
+            edit(&store, now - days_ago * DAY_MS);
+        }
+        for _ in 0..30 {
+            edit(&store, now);
+        }
+        // Only the last 20 versions are kept, and one activity row per day.
+        assert_eq!(count(&store, "memo_revisions"), 20);
+        assert_eq!(count(&store, "memo_activity"), 30);
+
+        let stats = store
+            .get_statistics(StatisticsInput {
+                weeks: Some(1),
+                include_archived: false,
+            })
+            .unwrap();
+        assert_eq!(stats.overall.longest_streak_days, 30);
+        assert_eq!(stats.overall.weekly[0].updated, 1);
+    }
+
+    fn pin(store: &mut Store, memo_id: &str, pinned: bool) {
+        store
+            .set_memo_pinned(SetMemoPinnedInput {
+                id: memo_id.to_string(),
+                pinned,
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn pins_join_the_end_of_the_group_and_unpins_lead_the_rest() {
+        let mut store = Store::open_in_memory().unwrap();
+        let c = category(&mut store, "C");
+        let a = memo(&mut store, &c, "a");
+        let b = memo(&mut store, &c, "b");
+        let m = memo(&mut store, &c, "c");
+        memo(&mut store, &c, "d");
+
+        pin(&mut store, &m, true);
+        pin(&mut store, &a, true);
+        assert_eq!(titles(&mut store, &c), ["c", "a", "b", "d"]);
+        pin(&mut store, &m, false);
+        assert_eq!(titles(&mut store, &c), ["a", "c", "b", "d"]);
+
+        // Unpinned memos can't be dropped into the pinned group.
+        let first = MemoSlot {
+            index: Some(0),
+            ..Default::default()
+        };
+        move_to(&mut store, &b, &c, first);
+        assert_eq!(titles(&mut store, &c), ["a", "b", "c", "d"]);
+
+        // Pinning is not an edit, and a pinned memo moved elsewhere is unpinned.
+        assert_eq!(store.get_memo(&a).unwrap().version, 1);
+        let other = category(&mut store, "Other");
+        move_to(&mut store, &a, &other, MemoSlot::default());
+        assert!(!store.get_memo(&a).unwrap().pinned);
+    }
+
+    #[test]
+    fn sorted_categories_only_reorder_pinned_memos() {
+        let mut store = Store::open_in_memory().unwrap();
+        let c = category(&mut store, "C");
+        let a = memo(&mut store, &c, "a");
+        let b = memo(&mut store, &c, "b");
+        let m = memo(&mut store, &c, "c");
+        pin(&mut store, &b, true);
+        pin(&mut store, &m, true);
+        store
+            .set_category_sort(SetCategorySortInput {
+                id: c.clone(),
+                sort_mode: SortMode::Title,
+                done_last: false,
+            })
+            .unwrap();
+        assert_eq!(titles(&mut store, &c), ["b", "c", "a"]);
+
+        store
+            .reorder_memos(ReorderMemosInput {
+                category_id: c.clone(),
+                ordered_ids: ids(&[&m, &b]),
+            })
+            .unwrap();
+        assert_eq!(titles(&mut store, &c), ["c", "b", "a"]);
+
+        let err = store
+            .reorder_memos(ReorderMemosInput {
+                category_id: c.clone(),
+                ordered_ids: ids(&[&m, &b, &a]),
+            })
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AppError::PreconditionFailed {
+                reason: "category_sorted",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn favorites_follow_the_board_and_skip_archived() {
+        let mut store = Store::open_in_memory().unwrap();
+        let first = category(&mut store, "First");
+        let second = category(&mut store, "Second");
+        let hidden = category(&mut store, "Hidden");
+        let star = |store: &mut Store, memo_id: &str| {
+            store
+                .set_memo_starred(SetMemoStarredInput {
+                    id: memo_id.to_string(),
+                    starred: true,
+                })
+                .unwrap();
+        };
+        for (category_id, title) in [
+            (&second, "s1"),
+            (&first, "f1"),
+            (&first, "f2"),
+            (&hidden, "h1"),
+        ] {
+            let id = memo(&mut store, category_id, title);
+            star(&mut store, &id);
+        }
+        let archived = memo(&mut store, &first, "f3");
+        star(&mut store, &archived);
+        store
+            .set_memo_archived(SetMemoArchivedInput {
+                id: archived,
+                archived: true,
+            })
+            .unwrap();
+        store
+            .set_category_archived(SetCategoryArchivedInput {
+                id: hidden,
+                archived: true,
+            })
+            .unwrap();
+
+        let favorites: Vec<String> = store
+            .list_favorites()
+            .unwrap()
+            .into_iter()
+            .map(|memo| memo.title)
+            .collect();
+        assert_eq!(favorites, ["f1", "f2", "s1"]);
+    }
+
+    #[test]
+    fn restored_memos_return_to_their_slot() {
+        let mut store = Store::open_in_memory().unwrap();
+        let c = category(&mut store, "C");
+        let a = memo(&mut store, &c, "a");
+        let b = memo(&mut store, &c, "b");
+        let m = memo(&mut store, &c, "c");
+        let archive = |store: &mut Store, archived| {
+            store
+                .set_memo_archived(SetMemoArchivedInput {
+                    id: b.clone(),
+                    archived,
+                })
+                .unwrap()
+        };
+
+        let archived = archive(&mut store, true);
+        assert!(archived.archived && archived.archived_at.is_some());
+        // Reordering the board leaves the archived memo's rank alone.
+        store
+            .reorder_memos(ReorderMemosInput {
+                category_id: c.clone(),
+                ordered_ids: ids(&[&m, &a]),
+            })
+            .unwrap();
+        memo(&mut store, &c, "d");
+        assert_eq!(titles(&mut store, &c), ["c", "a", "d"]);
+
+        let restored = archive(&mut store, false);
+        assert!(!restored.archived && restored.archived_at.is_none());
+        assert_eq!(restored.rank, archived.rank);
+        let board = titles(&mut store, &c);
+        assert_eq!(board.len(), 4);
+        assert!(board.contains(&"b".to_string()));
+    }
+
+    #[test]
+    fn archive_lists_archived_categories_and_memos() {
+        let mut store = Store::open_in_memory().unwrap();
+        let live = category(&mut store, "Live");
+        let old = category(&mut store, "Old");
+        memo(&mut store, &live, "kept");
+        let gone = memo(&mut store, &live, "Gone memo");
+        memo(&mut store, &old, "inside");
+        store
+            .set_memo_archived(SetMemoArchivedInput {
+                id: gone,
+                archived: true,
+            })
+            .unwrap();
+        store
+            .set_category_archived(SetCategoryArchivedInput {
+                id: old.clone(),
+                archived: true,
+            })
+            .unwrap();
+
+        let list = |store: &Store, query: Option<&str>| {
+            let archive = store
+                .list_archive(ListArchiveInput {
+                    query: query.map(str::to_string),
+                })
+                .unwrap();
+            let names = |entries: Vec<CategoryWithMemos>| -> Vec<(String, Vec<String>)> {
+                entries
+                    .into_iter()
+                    .map(|entry| {
+                        let memos = entry.memos.into_iter().map(|memo| memo.title).collect();
+                        (entry.category.title, memos)
+                    })
+                    .collect()
+            };
+            (names(archive.categories), names(archive.memos))
+        };
+
+        let (categories, memos) = list(&store, None);
+        assert_eq!(
+            categories,
+            [("Old".to_string(), vec!["inside".to_string()])]
+        );
+        assert_eq!(memos, [("Live".to_string(), vec!["Gone memo".to_string()])]);
+
+        let (categories, memos) = list(&store, Some("gone"));
+        assert!(categories.is_empty());
+        assert_eq!(memos.len(), 1);
+        let (categories, memos) = list(&store, Some("OLD"));
+        assert_eq!(categories.len(), 1);
+        assert!(memos.is_empty());
+
+        store
+            .set_category_archived(SetCategoryArchivedInput {
+                id: old.clone(),
+                archived: false,
+            })
+            .unwrap();
+        assert_eq!(titles(&mut store, &old), ["inside"]);
+    }
+
+    #[test]
+    fn auto_archive_restarts_the_period_on_restore() {
+        let mut store = Store::open_in_memory().unwrap();
+        let c = category(&mut store, "C");
+        let a = memo(&mut store, &c, "a");
+        memo(&mut store, &c, "b");
+        store
+            .set_category_auto_archive(SetCategoryAutoArchiveInput {
+                id: c.clone(),
+                days: Some(7),
+            })
+            .unwrap();
+        store
+            .set_memos_todo_done(SetMemosTodoDoneInput {
+                memo_ids: ids(&[&a]),
+                todo_done: true,
+            })
+            .unwrap();
+        assert_eq!(titles(&mut store, &c), ["a", "b"]);
+
+        let eight_days_ago = now_timestamp_ms() - 8 * DAY_MS;
+        store
+            .conn()
+            .execute(
+                "UPDATE memos SET completed_at = ?1 WHERE id = ?2",
+                params![eight_days_ago, &a],
+            )
+            .unwrap();
+        // Listing doesn't archive; the maintenance run does.
+        assert_eq!(titles(&mut store, &c), ["a", "b"]);
+        assert_eq!(
+            archive_completed(store.conn(), now_timestamp_ms()).unwrap(),
+            1
+        );
+        assert_eq!(titles(&mut store, &c), ["b"]);
+        assert!(store.get_memo(&a).unwrap().archived);
+
+        store
+            .set_memo_archived(SetMemoArchivedInput {
+                id: a.clone(),
+                archived: false,
+            })
+            .unwrap();
+        assert_eq!(
+            archive_completed(store.conn(), now_timestamp_ms()).unwrap(),
+            0
+        );
+        assert_eq!(titles(&mut store, &c), ["a", "b"]);
+    }
+
+    #[test]
+    fn settings_files_leave_out_the_api_token() {
+        let mut store = Store::open_in_memory().unwrap();
+        let token = |c: char| serde_json::Value::String(c.to_string().repeat(settings::TOKEN_LEN));
+        let mut values = serde_json::Map::new();
+        values.insert("http_api_token".to_string(), token('a'));
+        values.insert("font_size".to_string(), serde_json::json!(18));
+        store
+            .update_settings(UpdateSettingsInput { values })
+            .unwrap();
+
+        let mut file = store.export_settings().unwrap();
+        assert!(!file.settings.contains_key("http_api_token"));
+        assert_eq!(file.settings["font_size"], 18);
+
+        file.settings
+            .insert("http_api_token".to_string(), token('b'));
+        file.settings
+            .insert("font_size".to_string(), serde_json::json!(20));
+        let imported = store.import_settings(file).unwrap();
+        assert_eq!(imported.http_api_token, "a".repeat(settings::TOKEN_LEN));
+        assert_eq!(imported.font_size, 20);
+    }
+
+    fn issue_kinds(issues: &[IntegrityIssue]) -> Vec<IntegrityIssueKind> {
+        let mut kinds: Vec<_> = issues.iter().map(|issue| issue.kind).collect();
+        kinds.sort_by_key(|kind| format!("{kind:?}"));
+        kinds
+    }
+
+    #[test]
+    fn check_and_repair_fix_what_they_report() {
+        let mut store = Store::open_in_memory().unwrap();
+        let c = category(&mut store, "C");
+        let d = category(&mut store, "D");
+        let a = memo(&mut store, &c, "a");
+        let b = memo(&mut store, &c, "b");
+        let m = memo(&mut store, &c, "c");
+        let x = memo(&mut store, &c, "x");
+        let y = memo(&mut store, &c, "y");
+        let report = store.check_database().unwrap();
+        assert!(report.ok && report.issues.is_empty());
+
+        store
+            .conn()
+            .execute_batch(&format!(
+                "PRAGMA foreign_keys = OFF;
+                 UPDATE memos SET category_id = 'gone' WHERE id = '{x}';
+                 DELETE FROM memos WHERE id = '{y}';
+                 UPDATE memos SET rank = (SELECT rank FROM memos WHERE id = '{a}') WHERE id = '{b}';
+                 UPDATE memos SET date_ymd = '2026-02-30' WHERE id = '{m}';
+                 UPDATE categories SET rank = 'x0', title = ' ' WHERE id = '{d}';
+                 PRAGMA foreign_keys = ON;"
+            ))
+            .unwrap();
+
+        let report = store.check_database().unwrap();
+        assert!(!report.ok);
+        assert_eq!(
+            issue_kinds(&report.issues),
+            [
+                IntegrityIssueKind::DuplicateRank,
+                IntegrityIssueKind::EmptyField,
+                IntegrityIssueKind::ForeignKey,
+                IntegrityIssueKind::InvalidDate,
+                IntegrityIssueKind::InvalidRank,
+                IntegrityIssueKind::OrphanMemo,
+            ]
+        );
+        let orphans = report
+            .issues
+            .iter()
+            .find(|issue| issue.kind == IntegrityIssueKind::OrphanMemo)
+            .unwrap();
+        assert_eq!(orphans.ids, [x.as_str()]);
+        // Checking changes nothing.
+        assert_eq!(store.check_database().unwrap().issues.len(), 6);
+
+        let repair = store.repair_database().unwrap();
+        let actions: Vec<RepairAction> =
+            repair.changes.iter().map(|change| change.action).collect();
+        assert!(actions.contains(&RepairAction::DeleteRevision));
+        assert!(actions.contains(&RepairAction::CreateCategory));
+        assert!(actions.contains(&RepairAction::MoveMemo));
+        assert!(actions.contains(&RepairAction::Rerank));
+        // Bad dates and blank fields need the user.
+        assert_eq!(
+            issue_kinds(&repair.remaining),
+            [
+                IntegrityIssueKind::EmptyField,
+                IntegrityIssueKind::InvalidDate
+            ]
+        );
+        assert!(repair.remaining.iter().all(|issue| !issue.repairable));
+
+        assert_eq!(titles(&mut store, &c), ["a", "b", "c"]);
+        assert_eq!(
+            category_titles(&mut store),
+            ["C", " ", RECOVERED_CATEGORY_TITLE]
+        );
+        assert_eq!(store.get_memo(&x).unwrap().title, "x");
+
+        // A second run finds nothing left to do.
+        let again = store.repair_database().unwrap();
+        assert!(again.changes.is_empty());
+        assert_eq!(again.remaining.len(), 2);
+    }
+
+    #[test]
+    fn category_settings_bump_the_version() {
+        let mut store = Store::open_in_memory().unwrap();
+        let c = category(&mut store, "C");
+        let collapsed = store
+            .set_category_collapsed(SetCategoryCollapsedInput {
+                id: c.clone(),
+                is_collapsed: true,
+            })
+            .unwrap();
+        let sorted = store
+            .set_category_sort(SetCategorySortInput {
+                id: c.clone(),
+                sort_mode: SortMode::Title,
+                done_last: false,
+            })
+            .unwrap();
+        let auto_archive = store
+            .set_category_auto_archive(SetCategoryAutoArchiveInput {
+                id: c.clone(),
+                days: Some(7),
+            })
+            .unwrap();
+        assert_eq!(
+            (collapsed.version, sorted.version, auto_archive.version),
+            (2, 3, 4)
+        );
+
+        let err = store
+            .set_category_collapsed(SetCategoryCollapsedInput {
+                id: "unknown".to_string(),
+                is_collapsed: true,
+            })
+            .unwrap_err();
+        assert!(matches!(err, AppError::NotFound { .. }));
+    }
+}
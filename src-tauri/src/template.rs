@@ -0,0 +1,134 @@
+// Placeholder rendering for memo templates.
+//
+// `{{date}}`, `{{weekday}}` and `{{category}}` are filled from the memo being
+// created. Any other `{{name}}` is a custom prompt: the frontend asks the user
+// for it and passes the answer in `values`. Unanswered prompts render empty.
+
+use std::collections::HashMap;
+
+const BUILTINS: &[&str] = &["date", "weekday", "category"];
+
+const WEEKDAYS: [&str; 7] = [
+    "일요일",
+    "월요일",
+    "화요일",
+    "수요일",
+    "목요일",
+    "금요일",
+    "토요일",
+];
+
+pub struct Context<'a> {
+    pub date_ymd: &'a str,
+    pub category: &'a str,
+    pub values: &'a HashMap<String, String>,
+}
+
+/// Calls `f` with the text before each placeholder and its trimmed name, then
+/// returns the text after the last one.
+fn scan<'a>(text: &'a str, mut f: impl FnMut(&'a str, &'a str)) -> &'a str {
+    let mut rest = text;
+    while let Some(open) = rest.find("{{") {
+        let Some(close) = rest[open + 2..].find("}}") else {
+            break;
+        };
+        f(&rest[..open], rest[open + 2..open + 2 + close].trim());
+        rest = &rest[open + 2 + close + 2..];
+    }
+    rest
+}
+
+/// Korean weekday name of a `YYYY-MM-DD` date (Sakamoto's method).
+pub fn weekday(date_ymd: &str) -> Option<&'static str> {
+    const OFFSETS: [i64; 12] = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+    let mut parts = date_ymd.splitn(3, '-').map(|p| p.parse::<i64>().ok());
+    let (Some(Some(mut y)), Some(Some(m)), Some(Some(d))) =
+        (parts.next(), parts.next(), parts.next())
+    else {
+        return None;
+    };
+    if !(1..=12).contains(&m) {
+        return None;
+    }
+    if m < 3 {
+        y -= 1;
+    }
+    let day = (y + y / 4 - y / 100 + y / 400 + OFFSETS[(m - 1) as usize] + d).rem_euclid(7);
+    Some(WEEKDAYS[day as usize])
+}
+
+/// Custom prompt names used in `texts`, in first-use order.
+pub fn prompts(texts: &[&str]) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for text in texts {
+        scan(text, |_, name| {
+            if !name.is_empty() && !BUILTINS.contains(&name) && !out.iter().any(|p| p == name) {
+                out.push(name.to_string());
+            }
+        });
+    }
+    out
+}
+
+/// Fills the placeholders of a plain-text field such as the title.
+pub fn render(text: &str, ctx: &Context<'_>) -> String {
+    render_with(text, ctx, |out, value| out.push_str(value))
+}
+
+/// Fills the placeholders of TipTap HTML (`content_md`), escaping the values
+/// so a category name or prompt answer can't inject markup.
+pub fn render_html(text: &str, ctx: &Context<'_>) -> String {
+    render_with(text, ctx, escape_html)
+}
+
+fn escape_html(out: &mut String, value: &str) {
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+fn render_with(text: &str, ctx: &Context<'_>, push: impl Fn(&mut String, &str)) -> String {
+    let mut out = String::with_capacity(text.len());
+    let tail = scan(text, |before, name| {
+        out.push_str(before);
+        let value = match name {
+            "date" => ctx.date_ymd,
+            "weekday" => weekday(ctx.date_ymd).unwrap_or(""),
+            "category" => ctx.category,
+            _ => ctx.values.get(name).map(String::as_str).unwrap_or(""),
+        };
+        push(&mut out, value);
+    });
+    out.push_str(tail);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn html_values_are_escaped_but_titles_are_not() {
+        let values = HashMap::from([("who".to_string(), "<b>Kim & \"Lee\"</b>".to_string())]);
+        let ctx = Context {
+            date_ymd: "2026-10-18",
+            category: "R&D's",
+            values: &values,
+        };
+        assert_eq!(
+            render("{{ category }} {{date}} {{weekday}}: {{who}}", &ctx),
+            "R&D's 2026-10-18 일요일: <b>Kim & \"Lee\"</b>"
+        );
+        assert_eq!(
+            render_html("<p>{{category}}</p><p>{{who}}{{missing}}</p>", &ctx),
+            "<p>R&amp;D&#39;s</p><p>&lt;b&gt;Kim &amp; &quot;Lee&quot;&lt;/b&gt;</p>"
+        );
+    }
+}
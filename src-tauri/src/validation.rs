@@ -0,0 +1,557 @@
+// Input checks that run before any SQL. Each command input implements
+// `Validate`; all field problems are collected and returned together as
+// `AppError::Validation`.
+
+use crate::error::{AppError, AppResult, FieldError};
+use crate::models::{
+    CategoryKind, CreateCategoryInput, CreateMemoInput, CreateSmartCategoryInput, DateRangeInput,
+    MonthInput, OpenJournalEntryInput, SaveMemoAsTemplateInput, SetBackgroundColorInput,
+    SetCategoryAutoArchiveInput, SetMemosColorInput, SetMemosDateInput, SmartFilter,
+    UpdateCategoryInput, UpdateMemoInput, UpdateSmartCategoryInput,
+};
+use crate::smart;
+use unicode_segmentation::UnicodeSegmentation;
+
+pub const TITLE_MAX_CHARS: usize = 200;
+pub const CONTENT_MAX_CHARS: usize = 1_000_000;
+pub const AUTO_ARCHIVE_MAX_DAYS: i64 = 3650;
+
+/// CSS keywords accepted besides hex and `rgb()`/`rgba()`.
+const PRESET_COLOR_NAMES: &[&str] = &[
+    "transparent",
+    "white",
+    "black",
+    "gray",
+    "grey",
+    "silver",
+    "red",
+    "orange",
+    "gold",
+    "yellow",
+    "green",
+    "lime",
+    "teal",
+    "cyan",
+    "blue",
+    "navy",
+    "indigo",
+    "purple",
+    "violet",
+    "pink",
+    "brown",
+];
+
+pub trait Validate {
+    fn validate(&self) -> AppResult<()>;
+}
+
+#[derive(Default)]
+struct Errors(Vec<FieldError>);
+
+impl Errors {
+    fn check(&mut self, field: &str, result: Result<(), (&'static str, String)>) {
+        if let Err((code, message)) = result {
+            self.0.push(FieldError {
+                field: field.to_string(),
+                code: code.to_string(),
+                message,
+                span: None,
+            });
+        }
+    }
+
+    fn finish(self) -> AppResult<()> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::Validation(self.0))
+        }
+    }
+}
+
+fn is_hex_color(value: &str) -> bool {
+    value.strip_prefix('#').is_some_and(|hex| {
+        matches!(hex.len(), 3 | 4 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit())
+    })
+}
+
+fn is_rgb_channel(part: &str) -> bool {
+    match part.strip_suffix('%') {
+        Some(pct) => pct.parse::<f64>().is_ok_and(|v| (0.0..=100.0).contains(&v)),
+        None => part.parse::<u8>().is_ok(),
+    }
+}
+
+fn is_rgb_color(value: &str) -> bool {
+    let (body, with_alpha) = if let Some(rest) = value.strip_prefix("rgba(") {
+        (rest, true)
+    } else if let Some(rest) = value.strip_prefix("rgb(") {
+        (rest, false)
+    } else {
+        return false;
+    };
+    let Some(body) = body.strip_suffix(')') else {
+        return false;
+    };
+
+    let parts: Vec<&str> = body.split(',').map(str::trim).collect();
+    match (parts.as_slice(), with_alpha) {
+        ([r, g, b], false) => [r, g, b].iter().all(|p| is_rgb_channel(p)),
+        ([r, g, b, a], true) => {
+            [r, g, b].iter().all(|p| is_rgb_channel(p))
+                && a.parse::<f64>().is_ok_and(|v| (0.0..=1.0).contains(&v))
+        }
+        _ => false,
+    }
+}
+
+pub fn is_valid_color(value: &str) -> bool {
+    let value = value.trim().to_ascii_lowercase();
+    is_hex_color(&value) || is_rgb_color(&value) || PRESET_COLOR_NAMES.contains(&value.as_str())
+}
+
+/// True for a real calendar date written as `YYYY-MM-DD`.
+pub fn is_valid_date_ymd(value: &str) -> bool {
+    let b = value.as_bytes();
+    if b.len() != 10 || b[4] != b'-' || b[7] != b'-' {
+        return false;
+    }
+    let digits = |s: &str| s.bytes().all(|c| c.is_ascii_digit());
+    let (y, m, d) = (&value[0..4], &value[5..7], &value[8..10]);
+    if !digits(y) || !digits(m) || !digits(d) {
+        return false;
+    }
+    let (y, m, d): (u32, u32, u32) = (
+        y.parse().unwrap_or(0),
+        m.parse().unwrap_or(0),
+        d.parse().unwrap_or(0),
+    );
+    let leap = (y % 4 == 0 && y % 100 != 0) || y % 400 == 0;
+    let days_in_month = match m {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+    y >= 1 && (1..=days_in_month).contains(&d)
+}
+
+fn check_color(value: &str, allow_empty: bool) -> Result<(), (&'static str, String)> {
+    if (allow_empty && value.is_empty()) || is_valid_color(value) {
+        Ok(())
+    } else {
+        Err((
+            "invalid_color",
+            "색상은 #hex, rgb(), 또는 프리셋 이름이어야 합니다.".to_string(),
+        ))
+    }
+}
+
+fn check_date(value: &str) -> Result<(), (&'static str, String)> {
+    if is_valid_date_ymd(value) {
+        Ok(())
+    } else {
+        Err((
+            "invalid_date",
+            "날짜는 실제 존재하는 YYYY-MM-DD 형식이어야 합니다.".to_string(),
+        ))
+    }
+}
+
+fn check_title(value: &str, required: bool) -> Result<(), (&'static str, String)> {
+    if required && value.trim().is_empty() {
+        return Err(("required", "제목을 입력해 주세요.".to_string()));
+    }
+    if value.chars().count() > TITLE_MAX_CHARS {
+        return Err((
+            "too_long",
+            format!("제목은 {TITLE_MAX_CHARS}자 이하여야 합니다."),
+        ));
+    }
+    Ok(())
+}
+
+fn check_name(value: &str) -> Result<(), (&'static str, String)> {
+    if value.trim().is_empty() {
+        return Err(("required", "이름을 입력해 주세요.".to_string()));
+    }
+    if value.chars().count() > TITLE_MAX_CHARS {
+        return Err((
+            "too_long",
+            format!("이름은 {TITLE_MAX_CHARS}자 이하여야 합니다."),
+        ));
+    }
+    Ok(())
+}
+
+fn check_content(value: &str) -> Result<(), (&'static str, String)> {
+    if value.chars().count() > CONTENT_MAX_CHARS {
+        return Err((
+            "too_long",
+            format!("본문은 {CONTENT_MAX_CHARS}자 이하여야 합니다."),
+        ));
+    }
+    Ok(())
+}
+
+/// Empty, or exactly one user-perceived character.
+fn check_emoji(value: &str) -> Result<(), (&'static str, String)> {
+    if value.graphemes(true).count() <= 1 {
+        Ok(())
+    } else {
+        Err((
+            "invalid_emoji",
+            "이모지는 한 글자만 입력할 수 있습니다.".to_string(),
+        ))
+    }
+}
+
+impl Validate for CreateCategoryInput {
+    fn validate(&self) -> AppResult<()> {
+        let mut errors = Errors::default();
+        errors.check("title", check_title(&self.title, true));
+        errors.check("color", check_color(&self.color, false));
+        if let Some(emoji) = &self.emoji {
+            errors.check("emoji", check_emoji(emoji));
+        }
+        errors.check("kind", check_kind(self.kind));
+        errors.finish()
+    }
+}
+
+impl Validate for UpdateCategoryInput {
+    fn validate(&self) -> AppResult<()> {
+        let mut errors = Errors::default();
+        errors.check("title", check_title(&self.title, true));
+        errors.check("color", check_color(&self.color, false));
+        errors.check("emoji", check_emoji(&self.emoji));
+        errors.check("kind", check_kind(self.kind));
+        errors.finish()
+    }
+}
+
+/// Smart categories are created through their own commands.
+fn check_kind(kind: Option<CategoryKind>) -> Result<(), (&'static str, String)> {
+    if kind == Some(CategoryKind::Smart) {
+        return Err((
+            "invalid_kind",
+            "스마트 카테고리는 일반 카테고리로 만들 수 없습니다.".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn check_filter(errors: &mut Errors, filter: &SmartFilter) {
+    for (field, bound) in [
+        ("filter.date_from", &filter.date_from),
+        ("filter.date_to", &filter.date_to),
+    ] {
+        // Any date works as "today" for checking the syntax.
+        if bound
+            .as_deref()
+            .is_some_and(|b| smart::resolve_date(b, "2000-01-01").is_none())
+        {
+            errors.check(
+                field,
+                Err((
+                    "invalid_date",
+                    "날짜는 YYYY-MM-DD 또는 today, week_start 같은 기준일(+N/-N)이어야 합니다."
+                        .to_string(),
+                )),
+            );
+        }
+    }
+    for (i, color) in filter.colors.iter().enumerate() {
+        errors.check(&format!("filter.colors.{i}"), check_color(color, false));
+    }
+    for (i, tag) in filter.tags.iter().enumerate() {
+        if smart::normalize_tag(tag).is_empty() {
+            errors.check(
+                &format!("filter.tags.{i}"),
+                Err(("required", "태그를 입력해 주세요.".to_string())),
+            );
+        }
+    }
+    if let Some(text) = &filter.text {
+        errors.check("filter.text", check_title(text, false));
+    }
+}
+
+impl Validate for CreateSmartCategoryInput {
+    fn validate(&self) -> AppResult<()> {
+        let mut errors = Errors::default();
+        errors.check("title", check_title(&self.title, true));
+        errors.check("color", check_color(&self.color, false));
+        if let Some(emoji) = &self.emoji {
+            errors.check("emoji", check_emoji(emoji));
+        }
+        check_filter(&mut errors, &self.filter);
+        errors.finish()
+    }
+}
+
+impl Validate for UpdateSmartCategoryInput {
+    fn validate(&self) -> AppResult<()> {
+        let mut errors = Errors::default();
+        errors.check("title", check_title(&self.title, true));
+        errors.check("color", check_color(&self.color, false));
+        errors.check("emoji", check_emoji(&self.emoji));
+        check_filter(&mut errors, &self.filter);
+        errors.finish()
+    }
+}
+
+impl Validate for CreateMemoInput {
+    fn validate(&self) -> AppResult<()> {
+        let mut errors = Errors::default();
+        errors.check("title", check_title(&self.title, false));
+        errors.check("color", check_color(&self.color, false));
+        errors.check("content_md", check_content(&self.content_md));
+        if let Some(emoji) = &self.emoji {
+            errors.check("emoji", check_emoji(emoji));
+        }
+        // Empty means "today", filled in by the insert.
+        if let Some(date) = self.date_ymd.as_deref().filter(|d| !d.is_empty()) {
+            errors.check("date_ymd", check_date(date));
+        }
+        errors.finish()
+    }
+}
+
+impl Validate for UpdateMemoInput {
+    fn validate(&self) -> AppResult<()> {
+        let mut errors = Errors::default();
+        errors.check("title", check_title(&self.title, false));
+        errors.check("color", check_color(&self.color, false));
+        errors.check("content_md", check_content(&self.content_md));
+        errors.check("emoji", check_emoji(&self.emoji));
+        errors.check("date_ymd", check_date(&self.date_ymd));
+        errors.finish()
+    }
+}
+
+impl Validate for SetBackgroundColorInput {
+    fn validate(&self) -> AppResult<()> {
+        let mut errors = Errors::default();
+        // Empty resets to the default background.
+        errors.check(
+            "background_color",
+            check_color(&self.background_color, true),
+        );
+        errors.finish()
+    }
+}
+
+impl Validate for SetCategoryAutoArchiveInput {
+    fn validate(&self) -> AppResult<()> {
+        let mut errors = Errors::default();
+        if self
+            .days
+            .is_some_and(|days| !(0..=AUTO_ARCHIVE_MAX_DAYS).contains(&days))
+        {
+            errors.check(
+                "days",
+                Err((
+                    "invalid_range",
+                    format!("0~{AUTO_ARCHIVE_MAX_DAYS}일 사이여야 합니다."),
+                )),
+            );
+        }
+        errors.finish()
+    }
+}
+
+impl Validate for SetMemosColorInput {
+    fn validate(&self) -> AppResult<()> {
+        let mut errors = Errors::default();
+        errors.check("color", check_color(&self.color, false));
+        errors.finish()
+    }
+}
+
+impl Validate for SetMemosDateInput {
+    fn validate(&self) -> AppResult<()> {
+        let mut errors = Errors::default();
+        errors.check("date_ymd", check_date(&self.date_ymd));
+        errors.finish()
+    }
+}
+
+impl Validate for SaveMemoAsTemplateInput {
+    fn validate(&self) -> AppResult<()> {
+        let mut errors = Errors::default();
+        errors.check("name", check_name(&self.name));
+        errors.finish()
+    }
+}
+
+impl Validate for OpenJournalEntryInput {
+    fn validate(&self) -> AppResult<()> {
+        let mut errors = Errors::default();
+        // Empty means "today".
+        if let Some(date) = self.date_ymd.as_deref().filter(|d| !d.is_empty()) {
+            errors.check("date_ymd", check_date(date));
+        }
+        errors.finish()
+    }
+}
+
+impl Validate for DateRangeInput {
+    fn validate(&self) -> AppResult<()> {
+        let mut errors = Errors::default();
+        errors.check("from_ymd", check_date(&self.from_ymd));
+        errors.check("to_ymd", check_date(&self.to_ymd));
+        if self.from_ymd > self.to_ymd {
+            errors.check(
+                "to_ymd",
+                Err((
+                    "invalid_range",
+                    "끝 날짜는 시작 날짜보다 빠를 수 없습니다.".to_string(),
+                )),
+            );
+        }
+        errors.finish()
+    }
+}
+
+impl Validate for MonthInput {
+    fn validate(&self) -> AppResult<()> {
+        let mut errors = Errors::default();
+        if !is_valid_date_ymd(&format!("{}-01", self.month)) {
+            errors.check(
+                "month",
+                Err((
+                    "invalid_date",
+                    "월은 YYYY-MM 형식이어야 합니다.".to_string(),
+                )),
+            );
+        }
+        errors.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(result: AppResult<()>) -> Vec<(String, String)> {
+        match result {
+            Ok(()) => Vec::new(),
+            Err(AppError::Validation(fields)) => {
+                fields.into_iter().map(|f| (f.field, f.code)).collect()
+            }
+            Err(err) => panic!("unexpected error: {err}"),
+        }
+    }
+
+    #[test]
+    fn colors() {
+        for ok in [
+            "#fff",
+            "#ffffff",
+            "#FFFFFF80",
+            " #abcd ",
+            "rgb(255, 0, 0)",
+            "rgba(0,0,0,0.5)",
+            "rgb(100%, 0%, 50%)",
+            "Red",
+            "transparent",
+        ] {
+            assert!(is_valid_color(ok), "{ok}");
+        }
+        for bad in [
+            "",
+            "#ggg",
+            "#ff",
+            "fff",
+            "rgb(256,0,0)",
+            "rgb(0,0)",
+            "rgb(0,0,0,1)",
+            "rgba(0,0,0,2)",
+            "rgb(101%,0,0)",
+            "reddish",
+        ] {
+            assert!(!is_valid_color(bad), "{bad}");
+        }
+        assert!(check_color("", true).is_ok());
+        assert!(check_color("", false).is_err());
+    }
+
+    #[test]
+    fn dates() {
+        for ok in ["2024-02-29", "2000-02-29", "2026-12-31", "0001-01-01"] {
+            assert!(is_valid_date_ymd(ok), "{ok}");
+        }
+        for bad in [
+            "2023-02-29",
+            "1900-02-29",
+            "2026-04-31",
+            "2026-13-01",
+            "2026-00-10",
+            "0000-01-01",
+            "2026-1-01",
+            "2026/01/01",
+            "2026-01-0a",
+            "",
+        ] {
+            assert!(!is_valid_date_ymd(bad), "{bad}");
+        }
+    }
+
+    #[test]
+    fn lengths() {
+        assert!(check_title(&"가".repeat(TITLE_MAX_CHARS), true).is_ok());
+        assert_eq!(
+            check_title(&"가".repeat(TITLE_MAX_CHARS + 1), true)
+                .unwrap_err()
+                .0,
+            "too_long"
+        );
+        assert_eq!(check_title("  ", true).unwrap_err().0, "required");
+        assert!(check_title("", false).is_ok());
+        assert!(check_content(&"a".repeat(CONTENT_MAX_CHARS)).is_ok());
+        assert!(check_content(&"a".repeat(CONTENT_MAX_CHARS + 1)).is_err());
+    }
+
+    #[test]
+    fn emoji_is_one_grapheme() {
+        for ok in ["", "😀", "👨‍👩‍👧‍👦", "🇰🇷", "👍🏽", "가"] {
+            assert!(check_emoji(ok).is_ok(), "{ok}");
+        }
+        for bad in ["😀😀", "👨👩", "ab"] {
+            assert_eq!(check_emoji(bad).unwrap_err().0, "invalid_emoji", "{bad}");
+        }
+    }
+
+    #[test]
+    fn every_bad_field_is_reported() {
+        let input = CreateMemoInput {
+            category_id: "c".to_string(),
+            emoji: Some("😀😀".to_string()),
+            title: "t".repeat(TITLE_MAX_CHARS + 1),
+            color: "#ggg".to_string(),
+            date_ymd: Some("2023-02-29".to_string()),
+            content_md: String::new(),
+        };
+        let pair = |field: &str, code: &str| (field.to_string(), code.to_string());
+        assert_eq!(
+            codes(input.validate()),
+            [
+                pair("title", "too_long"),
+                pair("color", "invalid_color"),
+                pair("emoji", "invalid_emoji"),
+                pair("date_ymd", "invalid_date"),
+            ]
+        );
+
+        let input = CreateMemoInput {
+            emoji: None,
+            title: String::new(),
+            color: "#ffffff".to_string(),
+            date_ymd: Some(String::new()),
+            ..input
+        };
+        assert!(codes(input.validate()).is_empty());
+    }
+}
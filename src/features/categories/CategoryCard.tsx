@@ -4,6 +4,7 @@ import { useDndContext, useDroppable } from "@dnd-kit/core";
 import { SortableContext, verticalListSortingStrategy } from "@dnd-kit/sortable";
 import type { CategoryWithMemos, Memo } from "../../types";
 import { SortableMemoRow } from "../memos/SortableMemoRow";
+import { MemoRow } from "../memos/MemoRow";
 
 type Props = {
   item: CategoryWithMemos;
@@ -19,6 +20,10 @@ type Props = {
   cardClassName?: string;
   dndEnabled?: boolean;
   isDraggingCategory?: boolean;
+  /** 스마트 카테고리: 편집/추가/삭제/드래그 없이 목록만 표시 */
+  readOnly?: boolean;
+  /** readOnly일 때 메모별 할 일 여부(원래 카테고리 기준) */
+  isTodoMemo?: (memo: Memo) => boolean;
 };
 
 export function CategoryCard({
@@ -35,6 +40,8 @@ export function CategoryCard({
   cardClassName,
   dndEnabled = true,
   isDraggingCategory,
+  readOnly = false,
+  isTodoMemo,
 }: Props) {
   const { category, memos } = item;
   const collapsed = category.is_collapsed;
@@ -48,7 +55,7 @@ export function CategoryCard({
 
   const { setNodeRef, isOver } = useDroppable({
     id: `catdrop:${category.id}`,
-    disabled: !dndEnabled || !isMemoDragging,
+    disabled: readOnly || !dndEnabled || !isMemoDragging,
   });
 
   return (
@@ -71,13 +78,13 @@ export function CategoryCard({
               type="button"
               onClick={(e) => {
                 // 드래그로 인한 클릭(마우스 업)에서는 편집창이 뜨지 않도록 방지
-                if (isDraggingCategory) {
+                if (readOnly || isDraggingCategory) {
                   e.preventDefault();
                   return;
                 }
                 onOpenSettings();
               }}
-              aria-label="카테고리 제목 편집"
+              aria-label={readOnly ? category.title : "카테고리 제목 편집"}
               title={category.title}
             >
               {category.emoji?.length ? <span className="categoryEmoji">{category.emoji}</span> : null}
@@ -88,29 +95,48 @@ export function CategoryCard({
           </div>
 
           {/* 카테고리 기능 아이콘: 제목바 hover에서만 */}
-          <div className="categoryActions">
-            {/* [접기][삭제]는 호버 시에만 */}
-            <div className="hoverOnly hoverOnlyCategory row">
-              <button className="iconOnlyBtn" onClick={onToggleCollapse} aria-label="접기/펼치기">
-                {collapsed ? <ChevronRight size={18} /> : <ChevronDown size={18} />}
-              </button>
-              <button className="iconOnlyBtn danger" onClick={onDelete} aria-label="카테고리 삭제">
-                <Trash2 size={18} />
+          {readOnly ? null : (
+            <div className="categoryActions">
+              {/* [접기][삭제]는 호버 시에만 */}
+              <div className="hoverOnly hoverOnlyCategory row">
+                <button className="iconOnlyBtn" onClick={onToggleCollapse} aria-label="접기/펼치기">
+                  {collapsed ? <ChevronRight size={18} /> : <ChevronDown size={18} />}
+                </button>
+                <button className="iconOnlyBtn danger" onClick={onDelete} aria-label="카테고리 삭제">
+                  <Trash2 size={18} />
+                </button>
+              </div>
+
+              {/* [추가]는 상시 표시 */}
+              <button className="iconOnlyBtn categoryAddBtn" onClick={onCreateMemo} aria-label="메모 추가">
+                <Plus size={18} />
               </button>
             </div>
-
-            {/* [추가]는 상시 표시 */}
-            <button className="iconOnlyBtn categoryAddBtn" onClick={onCreateMemo} aria-label="메모 추가">
-              <Plus size={18} />
-            </button>
-          </div>
+          )}
         </div>
       </div>
 
       {collapsed ? null : (
         <div className="categoryBody">
           {memos.length === 0 ? (
-            <div className="emptyHint">메모가 없습니다. + 를 눌러 추가하세요.</div>
+            <div className="emptyHint">
+              {readOnly ? "조건에 맞는 메모가 없습니다." : "메모가 없습니다. + 를 눌러 추가하세요."}
+            </div>
+          ) : readOnly ? (
+            <ul className="memoList">
+              {memos
+                .slice()
+                .sort((a, b) => a.position - b.position)
+                .map((m) => (
+                  <MemoRow
+                    key={m.id}
+                    memo={m}
+                    isTodo={isTodoMemo?.(m)}
+                    onToggleTodo={(next) => onToggleTodoDone?.(m, next)}
+                    onOpen={() => onOpenMemo(m)}
+                  />
+                ))}
+            </ul>
           ) : (
             <SortableContext items={memoIds} strategy={verticalListSortingStrategy}>
               <ul className="memoList">
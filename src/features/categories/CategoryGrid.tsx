@@ -15,6 +15,7 @@ import {
 } from "@dnd-kit/core";
 import { SortableContext, arrayMove, rectSortingStrategy } from "@dnd-kit/sortable";
 import { SortableCategoryCard } from "./SortableCategoryCard";
+import { CategoryCard } from "./CategoryCard";
 import { CategorySettingsModal } from "./CategorySettingsModal";
 import { CreateCategoryModal } from "./CreateCategoryModal";
 import { WebviewWindow } from "@tauri-apps/api/webviewWindow";
@@ -36,6 +37,7 @@ export function CategoryGrid() {
     settings,
     setBackgroundColorLocal,
     saveBackgroundColor,
+    openJournalEntry,
   } = useAppStore();
 
   const [createOpen, setCreateOpen] = useState(false);
@@ -76,6 +78,22 @@ export function CategoryGrid() {
       window.removeEventListener("keydown", onKeyDown);
     };
   }, [bgOpen]);
+  // 펼쳐진 일지 카테고리에 오늘 메모가 없으면 만듦(접혀 있다 펼칠 때도 여기서 처리).
+  // 실패해도 다시 시도하지 않도록 카테고리·날짜별로 한 번만 요청
+  const journalOpenedRef = useRef(new Set<string>());
+  useEffect(() => {
+    const today = new Date().toLocaleDateString("sv-SE"); // YYYY-MM-DD
+    for (const item of categories) {
+      const c = item.category;
+      if (c.kind !== "journal" || c.archived || c.is_collapsed) continue;
+      if (item.memos.some((m) => m.date_ymd === today)) continue;
+      const key = `${c.id}:${today}`;
+      if (journalOpenedRef.current.has(key)) continue;
+      journalOpenedRef.current.add(key);
+      void openJournalEntry({ category_id: c.id, date_ymd: today });
+    }
+  }, [categories, openJournalEntry]);
+
   const sleep = (ms: number) => new Promise<void>((r) => window.setTimeout(r, ms));
 
   const openMemoEdit = (memo: Memo) => {
@@ -108,7 +126,15 @@ export function CategoryGrid() {
     [filtered],
   );
 
-  const categoryIds = useMemo(() => sorted.map((c) => `cat:${c.category.id}`), [sorted]);
+  // 스마트 카테고리는 읽기 전용이라 드래그 정렬에서 제외하고 맨 앞에 표시
+  const smartItems = useMemo(() => sorted.filter((c) => c.category.kind === "smart"), [sorted]);
+  const regularItems = useMemo(() => sorted.filter((c) => c.category.kind !== "smart"), [sorted]);
+  const todoCategoryIds = useMemo(
+    () => new Set(categories.filter((c) => c.category.is_todo).map((c) => c.category.id)),
+    [categories],
+  );
+
+  const categoryIds = useMemo(() => regularItems.map((c) => `cat:${c.category.id}`), [regularItems]);
   const dndEnabled = query.trim().length === 0;
 
   const sensors = useSensors(
@@ -289,9 +315,13 @@ export function CategoryGrid() {
               setActiveId(null);
               return;
             }
-            // 다른 카테고리의 메모 위로 드롭한 경우: 해당 카테고리로 이동(끝에 붙이기)
+            // 다른 카테고리의 메모 위로 드롭한 경우: 해당 메모 자리로 이동
             if (activeCat && overCat && activeCat !== overCat) {
-              await moveMemo({ memo_id: activeId.replace("memo:", ""), to_category_id: overCat });
+              await moveMemo({
+                memo_id: activeId.replace("memo:", ""),
+                to_category_id: overCat,
+                before_id: overId.replace("memo:", ""),
+              });
               setActiveId(null);
               return;
             }
@@ -329,7 +359,37 @@ export function CategoryGrid() {
         </DragOverlay>
         <SortableContext items={categoryIds} strategy={rectSortingStrategy}>
           <div className="categoryGrid">
-            {sorted.map((item) => (
+            {smartItems.map((item) => (
+              <CategoryCard
+                key={item.category.id}
+                item={item}
+                readOnly
+                dndEnabled={false}
+                cardClassName="smartCategoryCard"
+                isTodoMemo={(memo) => todoCategoryIds.has(memo.category_id)}
+                onToggleCollapse={() => {}}
+                onCreateMemo={() => {}}
+                onOpenSettings={() => {}}
+                onDelete={() => {}}
+                onDeleteMemo={() => {}}
+                onOpenMemo={(memo) => {
+                  openMemoEdit(memo);
+                }}
+                onToggleTodoDone={async (memo, next) => {
+                  await updateMemo({
+                    id: memo.id,
+                    emoji: memo.emoji,
+                    title: memo.title,
+                    color: memo.color,
+                    date_ymd: memo.date_ymd,
+                    content_md: memo.content_md,
+                    todo_done: next,
+                    expected_version: memo.version,
+                  });
+                }}
+              />
+            ))}
+            {regularItems.map((item) => (
               <SortableCategoryCard
                 key={item.category.id}
                 item={item}
@@ -366,6 +426,7 @@ export function CategoryGrid() {
                     date_ymd: memo.date_ymd,
                     content_md: memo.content_md,
                     todo_done: next,
+                    expected_version: memo.version,
                   });
                 }}
                 onDeleteMemo={async (memo) => {
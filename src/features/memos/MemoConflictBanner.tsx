@@ -0,0 +1,34 @@
+const FIELD_LABELS: Record<string, string> = {
+  emoji: "이모지",
+  title: "제목",
+  color: "텍스트 컬러",
+  date_ymd: "날짜",
+  content_md: "본문",
+  todo_done: "완료 여부",
+};
+
+type Props = {
+  fields: string[];
+  onUseServer: () => void;
+  onKeepMine: () => void;
+};
+
+/** 다른 창에서 같은 부분을 먼저 고쳐 자동 병합할 수 없을 때 편집기 위에 표시 */
+export function MemoConflictBanner({ fields, onUseServer, onKeepMine }: Props) {
+  const labels = fields.map((f) => FIELD_LABELS[f] ?? f).join(", ");
+  return (
+    <div className="memoConflictBanner" role="alert">
+      <div className="memoConflictText">
+        다른 창에서 이 메모를 먼저 수정했습니다. 겹치는 항목: {labels}
+      </div>
+      <div className="memoConflictActions">
+        <button className="btn" type="button" onClick={onUseServer}>
+          저장된 내용 불러오기
+        </button>
+        <button className="btn primary" type="button" onClick={onKeepMine}>
+          내 내용으로 덮어쓰기
+        </button>
+      </div>
+    </div>
+  );
+}
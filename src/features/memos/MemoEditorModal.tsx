@@ -15,6 +15,7 @@ import { AnchoredPopover } from "../../components/AnchoredPopover";
 import type { Memo } from "../../types";
 import { api } from "../../lib/tauri";
 import { formatYmdShort } from "../../lib/date";
+import { MemoConflictBanner } from "./MemoConflictBanner";
 
 type Mode =
   | { kind: "create"; categoryId: string; defaultColor: string }
@@ -369,6 +370,12 @@ export function MemoEditorModal({ open, mode, onClose, onCreatedOrUpdated }: Pro
     content_md: string;
     todo_done: boolean;
   } | null>(null);
+  // 편집 내용의 기준이 된 서버 버전. 저장할 때 expected_version으로 보내면 그사이
+  // 다른 창에서 바뀐 부분과 서버에서 병합되고, 겹치면 conflict가 돌아온다.
+  const baseVersionRef = useRef<number | null>(null);
+  const [conflict, setConflict] = useState<{ server: Memo; fields: string[] } | null>(null);
+  const conflictRef = useRef(conflict);
+  conflictRef.current = conflict;
 
   const editor = useEditor({
     extensions: [
@@ -392,8 +399,34 @@ export function MemoEditorModal({ open, mode, onClose, onCreatedOrUpdated }: Pro
     },
   });
 
+  const memoFields = (memo: Memo) => ({
+    id: memo.id,
+    emoji: memo.emoji ?? "",
+    title: memo.title,
+    color: memo.color,
+    date_ymd: memo.date_ymd,
+    content_md: memo.content_md,
+    todo_done: !!memo.todo_done,
+  });
+
+  /** 서버 쪽 메모(병합 결과/충돌 시 저장본)를 편집기에 반영 */
+  const applyMemo = (memo: Memo) => {
+    setEmoji(memo.emoji ?? "");
+    setTitle(memo.title);
+    setColor(memo.color);
+    setDateYmd(memo.date_ymd);
+    setContent(memo.content_md);
+    if (editor && editor.getHTML() !== memo.content_md) {
+      editor.commands.setContent(memo.content_md?.length ? memo.content_md : "<p></p>");
+    }
+    lastSavedRef.current = memoFields(memo);
+    baseVersionRef.current = memo.version;
+  };
+
   useEffect(() => {
     if (!open) return;
+    // 아직 저장 안 된 편집이 있으면 다른 창의 변경으로 덮어쓰지 않는다(다음 저장 때 병합).
+    if (mode?.kind === "edit" && lastSavedRef.current?.id === mode.memo.id && isDirty()) return;
     setEmoji(initial.emoji);
     setTitle(initial.title);
     setColor(initial.color);
@@ -402,10 +435,13 @@ export function MemoEditorModal({ open, mode, onClose, onCreatedOrUpdated }: Pro
     setColorOpen(false);
     setEmojiOpen(false);
     setDraftMemoId(null);
-    lastSavedRef.current = null;
-    if (editor) {
+    setConflict(null);
+    lastSavedRef.current = mode?.kind === "edit" ? memoFields(mode.memo) : null;
+    baseVersionRef.current = mode?.kind === "edit" ? mode.memo.version : null;
+    if (editor && editor.getHTML() !== initial.content_md) {
       editor.commands.setContent(initial.content_md?.length ? initial.content_md : "<p></p>");
     }
+    // eslint-disable-next-line react-hooks/exhaustive-deps
   }, [open, initial.emoji, initial.title, initial.color, initial.date_ymd, initial.content_md, editor]);
 
   // create 모드: 모달이 열리면 DB에 초안 메모를 즉시 생성해 유실을 방지한다.
@@ -425,15 +461,8 @@ export function MemoEditorModal({ open, mode, onClose, onCreatedOrUpdated }: Pro
         })) as Memo;
         if (cancelled) return;
         setDraftMemoId(created.id);
-        lastSavedRef.current = {
-          id: created.id,
-          emoji: created.emoji ?? "",
-          title: created.title,
-          color: created.color,
-          date_ymd: created.date_ymd,
-          content_md: created.content_md,
-          todo_done: !!created.todo_done,
-        };
+        lastSavedRef.current = memoFields(created);
+        baseVersionRef.current = created.version;
       } catch {
         // v1: 초안 생성 실패 시에도 사용자는 계속 작성 가능(단, 닫기 전에 수동 저장 필요)
       }
@@ -572,12 +601,11 @@ export function MemoEditorModal({ open, mode, onClose, onCreatedOrUpdated }: Pro
     };
   };
 
-  const flushSave = async () => {
+  const isDirty = () => {
     const snap = getSnapshot();
-    if (!snap) return;
     const last = lastSavedRef.current;
-    if (
-      last &&
+    if (!snap || !last) return false;
+    return !(
       last.id === snap.id &&
       last.emoji === snap.emoji &&
       last.title === snap.title &&
@@ -585,11 +613,17 @@ export function MemoEditorModal({ open, mode, onClose, onCreatedOrUpdated }: Pro
       last.date_ymd === snap.date_ymd &&
       last.content_md === snap.content_md &&
       last.todo_done === snap.todo_done
-    ) {
-      return;
-    }
+    );
+  };
+
+  /** 저장 후 닫아도 되면 true. 충돌이 나면 배너를 띄우고 false */
+  const flushSave = async (): Promise<boolean> => {
+    if (conflictRef.current) return false;
+    const snap = getSnapshot();
+    if (!snap) return true;
+    if (lastSavedRef.current && !isDirty()) return true;
     try {
-      await api.updateMemo({
+      const result = await api.updateMemo({
         id: snap.id,
         emoji: snap.emoji,
         title: snap.title,
@@ -597,11 +631,37 @@ export function MemoEditorModal({ open, mode, onClose, onCreatedOrUpdated }: Pro
         date_ymd: snap.date_ymd,
         content_md: snap.content_md,
         todo_done: snap.todo_done,
+        expected_version: baseVersionRef.current ?? undefined,
       });
-      lastSavedRef.current = snap;
+      if (result.status === "conflict") {
+        setConflict({ server: result.server, fields: result.fields });
+        return false;
+      }
+      if (result.status === "merged") {
+        applyMemo(result.memo);
+      } else {
+        lastSavedRef.current = snap;
+        baseVersionRef.current = result.memo.version;
+      }
     } catch {
       // v1: 자동 저장 실패는 조용히 무시(사용자 흐름 방해 방지)
     }
+    return true;
+  };
+
+  const resolveConflict = async (keepMine: boolean) => {
+    const current = conflictRef.current;
+    if (!current) return;
+    conflictRef.current = null;
+    setConflict(null);
+    if (keepMine) {
+      // 저장본을 기준으로 다시 저장하면 병합 없이 내 내용이 그대로 기록된다.
+      baseVersionRef.current = current.server.version;
+      await flushSave();
+    } else {
+      applyMemo(current.server);
+    }
+    await onCreatedOrUpdated();
   };
 
   // 자동 저장: edit 모드 + create 모드(초안 생성 후) 모두 적용
@@ -770,8 +830,8 @@ export function MemoEditorModal({ open, mode, onClose, onCreatedOrUpdated }: Pro
           content_md: editor?.getHTML() ?? content ?? "",
         })) as Memo;
         setDraftMemoId(created.id);
-      } else {
-        await flushSave();
+      } else if (!(await flushSave())) {
+        return;
       }
       await onCreatedOrUpdated();
       onClose();
@@ -787,7 +847,7 @@ export function MemoEditorModal({ open, mode, onClose, onCreatedOrUpdated }: Pro
       onClose={async () => {
         // 닫기 전 마지막 1회 저장(가능한 경우)
         persistLastPos();
-        await flushSave();
+        if (!(await flushSave())) return;
         await onCreatedOrUpdated();
         onClose();
       }}
@@ -853,7 +913,7 @@ export function MemoEditorModal({ open, mode, onClose, onCreatedOrUpdated }: Pro
               className="iconOnlyBtn"
               onClick={async () => {
                 persistLastPos();
-                await flushSave();
+                if (!(await flushSave())) return;
                 await onCreatedOrUpdated();
                 onClose();
               }}
@@ -867,6 +927,13 @@ export function MemoEditorModal({ open, mode, onClose, onCreatedOrUpdated }: Pro
       }
     >
       <div className="memoEditorBody">
+        {conflict && (
+          <MemoConflictBanner
+            fields={conflict.fields}
+            onUseServer={() => resolveConflict(false)}
+            onKeepMine={() => resolveConflict(true)}
+          />
+        )}
         <div className="richEditorWrap">
           <div className="richEditorShell">
             <div className="richEditorScroll" ref={editorScrollRef}>
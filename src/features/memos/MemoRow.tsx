@@ -0,0 +1,43 @@
+import type { Memo } from "../../types";
+import { formatYmdShort } from "../../lib/date";
+
+type Props = {
+  memo: Memo;
+  isTodo?: boolean;
+  onToggleTodo?: (next: boolean) => void;
+  onOpen: () => void;
+};
+
+/** 드래그/삭제 없는 메모 행(스마트 카테고리 등 읽기 전용 목록용) */
+export function MemoRow({ memo, isTodo, onToggleTodo, onOpen }: Props) {
+  return (
+    <li className="memoListItem">
+      <button className="memoMainBtn" onClick={onOpen} aria-label="메모 열기">
+        {isTodo ? (
+          <input
+            className="todoCheckbox"
+            type="checkbox"
+            checked={!!memo.todo_done}
+            onClick={(e) => e.stopPropagation()}
+            onChange={(e) => onToggleTodo?.(e.currentTarget.checked)}
+            aria-label="Todo 완료"
+          />
+        ) : memo.emoji?.length ? (
+          <span className="memoEmoji">{memo.emoji}</span>
+        ) : null}
+        <span
+          className={`memoTitle ${isTodo && memo.todo_done ? "memoDone" : ""}`}
+          style={{ color: isTodo && memo.todo_done ? undefined : memo.color }}
+        >
+          {memo.title}
+        </span>
+      </button>
+
+      <div className="memoRight">
+        <div className="memoDateText" title={memo.date_ymd}>
+          {formatYmdShort(memo.date_ymd)}
+        </div>
+      </div>
+    </li>
+  );
+}
@@ -1,18 +1,59 @@
 import { invoke } from "@tauri-apps/api/core";
 import type {
   AppSettings,
+  Archive,
+  BulkReport,
   Category,
   CategoryWithMemos,
   CreateCategoryInput,
+  CreateMemoFromTemplateInput,
   CreateMemoInput,
+  CreateSmartCategoryInput,
+  DateRangeInput,
+  DayCount,
+  DeleteMemosInput,
+  DuplicateCategoryInput,
+  DuplicateMemoInput,
+  HttpApiStatus,
+  IntegrityReport,
+  ListArchiveInput,
+  Memo,
+  MonthInput,
   MoveMemoInput,
+  MoveMemoToInput,
+  MoveMemosInput,
+  OpenJournalEntryInput,
+  QueryMemosInput,
+  RecentlyCompletedInput,
+  RepairReport,
   ReorderCategoriesInput,
   ReorderMemosInput,
+  SaveMemoAsTemplateInput,
   SetBackgroundColorInput,
   SetCategoryArchivedInput,
+  SetCategoryAutoArchiveInput,
   SetCategoryCollapsedInput,
+  SetCategoryDefaultTemplateInput,
+  SetCategorySortInput,
+  SetHttpApiInput,
+  SetMemoArchivedInput,
+  SetMemoPinnedInput,
+  SetMemoStarredInput,
+  SetMemosColorInput,
+  SetMemosDateInput,
+  SetMemosTodoDoneInput,
+  SettingsFile,
+  SmartCategory,
+  Statistics,
+  StatisticsInput,
+  Template,
+  TodoDayCount,
   UpdateCategoryInput,
+  UpdateCategoryResult,
   UpdateMemoInput,
+  UpdateMemoResult,
+  UpdateSettingsInput,
+  UpdateSmartCategoryInput,
 } from "../types";
 
 export const api = {
@@ -32,7 +73,7 @@ export const api = {
     return invoke("create_category", { input });
   },
 
-  updateCategory(input: UpdateCategoryInput): Promise<Category> {
+  updateCategory(input: UpdateCategoryInput): Promise<UpdateCategoryResult> {
     return invoke("update_category", { input });
   },
 
@@ -44,10 +85,38 @@ export const api = {
     return invoke("set_category_collapsed", { input });
   },
 
+  setCategorySort(input: SetCategorySortInput): Promise<Category> {
+    return invoke("set_category_sort", { input });
+  },
+
+  setCategoryAutoArchive(input: SetCategoryAutoArchiveInput): Promise<Category> {
+    return invoke("set_category_auto_archive", { input });
+  },
+
   deleteCategory(id: string): Promise<void> {
     return invoke("delete_category", { id });
   },
 
+  listSmartCategories(): Promise<SmartCategory[]> {
+    return invoke("list_smart_categories");
+  },
+
+  createSmartCategory(input: CreateSmartCategoryInput): Promise<SmartCategory> {
+    return invoke("create_smart_category", { input });
+  },
+
+  updateSmartCategory(input: UpdateSmartCategoryInput): Promise<SmartCategory> {
+    return invoke("update_smart_category", { input });
+  },
+
+  deleteSmartCategory(id: string): Promise<void> {
+    return invoke("delete_smart_category", { id });
+  },
+
+  reorderSmartCategories(input: ReorderCategoriesInput): Promise<void> {
+    return invoke("reorder_smart_categories", { input });
+  },
+
   reorderCategories(input: ReorderCategoriesInput): Promise<void> {
     return invoke("reorder_categories", { input });
   },
@@ -60,7 +129,7 @@ export const api = {
     return invoke("create_memo", { input });
   },
 
-  updateMemo(input: UpdateMemoInput) {
+  updateMemo(input: UpdateMemoInput): Promise<UpdateMemoResult> {
     return invoke("update_memo", { input });
   },
 
@@ -71,6 +140,146 @@ export const api = {
   moveMemo(input: MoveMemoInput): Promise<void> {
     return invoke("move_memo", { input });
   },
+
+  moveMemoTo(input: MoveMemoToInput): Promise<Memo> {
+    return invoke("move_memo_to", { input });
+  },
+
+  moveMemos(input: MoveMemosInput): Promise<BulkReport> {
+    return invoke("move_memos", { input });
+  },
+
+  deleteMemos(input: DeleteMemosInput): Promise<BulkReport> {
+    return invoke("delete_memos", { input });
+  },
+
+  setMemosTodoDone(input: SetMemosTodoDoneInput): Promise<BulkReport> {
+    return invoke("set_memos_todo_done", { input });
+  },
+
+  setMemoPinned(input: SetMemoPinnedInput): Promise<Memo> {
+    return invoke("set_memo_pinned", { input });
+  },
+
+  setMemoStarred(input: SetMemoStarredInput): Promise<Memo> {
+    return invoke("set_memo_starred", { input });
+  },
+
+  listFavorites(): Promise<Memo[]> {
+    return invoke("list_favorites");
+  },
+
+  setMemoArchived(input: SetMemoArchivedInput): Promise<Memo> {
+    return invoke("set_memo_archived", { input });
+  },
+
+  listArchive(input: ListArchiveInput = {}): Promise<Archive> {
+    return invoke("list_archive", { input });
+  },
+
+  /** 검색식으로 메모 조회(보드 순서). 잘못된 검색식은 fields[0].span에 위치 포함 */
+  queryMemos(input: QueryMemosInput): Promise<Memo[]> {
+    return invoke("query_memos", { input });
+  },
+
+  listRecentlyCompleted(input: RecentlyCompletedInput = {}): Promise<Memo[]> {
+    return invoke("list_recently_completed", { input });
+  },
+
+  setMemosColor(input: SetMemosColorInput): Promise<BulkReport> {
+    return invoke("set_memos_color", { input });
+  },
+
+  setMemosDate(input: SetMemosDateInput): Promise<BulkReport> {
+    return invoke("set_memos_date", { input });
+  },
+
+  duplicateMemo(input: DuplicateMemoInput): Promise<Memo> {
+    return invoke("duplicate_memo", { input });
+  },
+
+  duplicateCategory(input: DuplicateCategoryInput): Promise<CategoryWithMemos> {
+    return invoke("duplicate_category", { input });
+  },
+
+  listTemplates(): Promise<Template[]> {
+    return invoke("list_templates");
+  },
+
+  saveMemoAsTemplate(input: SaveMemoAsTemplateInput): Promise<Template> {
+    return invoke("save_memo_as_template", { input });
+  },
+
+  deleteTemplate(id: string): Promise<void> {
+    return invoke("delete_template", { id });
+  },
+
+  createMemoFromTemplate(input: CreateMemoFromTemplateInput): Promise<Memo> {
+    return invoke("create_memo_from_template", { input });
+  },
+
+  setCategoryDefaultTemplate(input: SetCategoryDefaultTemplateInput): Promise<Category> {
+    return invoke("set_category_default_template", { input });
+  },
+
+  openJournalEntry(input: OpenJournalEntryInput): Promise<Memo> {
+    return invoke("open_journal_entry", { input });
+  },
+
+  listMemosInRange(input: DateRangeInput): Promise<Memo[]> {
+    return invoke("list_memos_in_range", { input });
+  },
+
+  countMemosByDay(input: MonthInput): Promise<DayCount[]> {
+    return invoke("count_memos_by_day", { input });
+  },
+
+  countTodosByDay(input: DateRangeInput): Promise<TodoDayCount[]> {
+    return invoke("count_todos_by_day", { input });
+  },
+
+  getStatistics(input: StatisticsInput = {}): Promise<Statistics> {
+    return invoke("get_statistics", { input });
+  },
+
+  getSettings(): Promise<AppSettings> {
+    return invoke("get_settings");
+  },
+
+  updateSettings(input: UpdateSettingsInput): Promise<AppSettings> {
+    return invoke("update_settings", { input });
+  },
+
+  getHttpApiStatus(): Promise<HttpApiStatus> {
+    return invoke("get_http_api_status");
+  },
+
+  /** HTTP API 켜기/끄기/포트 변경(서버 재시작). 시작 실패는 반환값의 error로 */
+  setHttpApi(input: SetHttpApiInput): Promise<HttpApiStatus> {
+    return invoke("set_http_api", { input });
+  },
+
+  regenerateHttpApiToken(): Promise<HttpApiStatus> {
+    return invoke("regenerate_http_api_token");
+  },
+
+  exportSettings(): Promise<SettingsFile> {
+    return invoke("export_settings");
+  },
+
+  importSettings(input: SettingsFile): Promise<AppSettings> {
+    return invoke("import_settings", { input });
+  },
+
+  /** DB 점검(읽기 전용). 무결성/외래키/순서 값/고아 메모/날짜/빈 필드 */
+  checkDatabase(): Promise<IntegrityReport> {
+    return invoke("check_database");
+  },
+
+  /** 고칠 수 있는 문제를 한 트랜잭션으로 복구하고 변경 내역을 반환 */
+  repairDatabase(): Promise<RepairReport> {
+    return invoke("repair_database");
+  },
 } as const;
 
 
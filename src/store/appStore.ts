@@ -7,13 +7,16 @@ import type {
   CreateCategoryInput,
   CreateMemoInput,
   MoveMemoInput,
+  OpenJournalEntryInput,
   ReorderCategoriesInput,
   ReorderMemosInput,
   SetCategoryCollapsedInput,
   SetCategoryArchivedInput,
+  SetMemoArchivedInput,
   SetBackgroundColorInput,
   UpdateCategoryInput,
   UpdateMemoInput,
+  UpdateMemoResult,
 } from "../types";
 
 type AppState = {
@@ -35,16 +38,35 @@ type AppState = {
   reorderMemos(input: ReorderMemosInput): Promise<void>;
 
   createMemo(input: CreateMemoInput): Promise<void>;
-  updateMemo(input: UpdateMemoInput): Promise<void>;
+  /** 충돌이면 아무것도 저장되지 않고 결과의 server가 현재 저장본 */
+  updateMemo(input: UpdateMemoInput): Promise<UpdateMemoResult>;
   deleteMemo(id: string): Promise<void>;
   moveMemo(input: MoveMemoInput): Promise<void>;
+  setMemoArchived(input: SetMemoArchivedInput): Promise<void>;
+  /** 일지 카테고리의 오늘(또는 지정한 날짜) 메모를 없으면 만듦. 실패는 error에 표시 */
+  openJournalEntry(input: OpenJournalEntryInput): Promise<void>;
 };
 
+// invoke는 AppError 객체로 거부하므로 message를 우선 표시
+function errorMessage(e: unknown) {
+  const message = (e as { message?: unknown } | null)?.message;
+  return typeof message === "string" ? message : String(e);
+}
+
 export const useAppStore = create<AppState>((set, get) => ({
   loading: false,
   error: null,
   categories: [],
-  settings: { background_color: "" },
+  settings: {
+    background_color: "",
+    theme: "system",
+    font_size: 14,
+    default_category_id: null,
+    editor_spellcheck: true,
+    http_api_enabled: false,
+    http_api_port: 27183,
+    http_api_token: "",
+  },
 
   refresh: async () => {
     set({ loading: true, error: null });
@@ -55,7 +77,7 @@ export const useAppStore = create<AppState>((set, get) => ({
       ]);
       set({ categories, settings, loading: false });
     } catch (e) {
-      set({ loading: false, error: String(e) });
+      set({ loading: false, error: errorMessage(e) });
     }
   },
 
@@ -130,11 +152,12 @@ export const useAppStore = create<AppState>((set, get) => ({
     } catch {}
   },
   updateMemo: async (input) => {
-    await api.updateMemo(input);
+    const result = await api.updateMemo(input);
     await get().refresh();
     try {
       await emit("ideanode:data_changed");
     } catch {}
+    return result;
   },
   deleteMemo: async (id) => {
     await api.deleteMemo(id);
@@ -150,6 +173,26 @@ export const useAppStore = create<AppState>((set, get) => ({
       await emit("ideanode:data_changed");
     } catch {}
   },
+  setMemoArchived: async (input) => {
+    await api.setMemoArchived(input);
+    await get().refresh();
+    try {
+      await emit("ideanode:data_changed");
+    } catch {}
+  },
+  openJournalEntry: async (input) => {
+    // 화면을 열 때 자동으로 불리므로 호출한 쪽 대신 여기서 오류를 표시
+    try {
+      await api.openJournalEntry(input);
+    } catch (e) {
+      set({ error: errorMessage(e) });
+      return;
+    }
+    await get().refresh();
+    try {
+      await emit("ideanode:data_changed");
+    } catch {}
+  },
 }));
 
 
@@ -1,14 +1,35 @@
 export type ID = string;
 
+/**
+ * normal: 일반, todo: 체크리스트, journal: 날짜별 일지(하루 한 메모, 날짜순 정렬)
+ * smart: 저장된 검색(읽기 전용, 목록 조회 시에만 포함)
+ */
+export type CategoryKind = "normal" | "todo" | "journal" | "smart";
+
+/** manual: 직접 정렬(rank). 나머지는 목록 조회 시 백엔드가 정렬 */
+export type SortMode = "manual" | "date_asc" | "date_desc" | "title" | "updated" | "created";
+
 export type Category = {
   id: ID;
   emoji: string;
   title: string;
   color: string;
+  /** Index in rank order among categories with the same `archived`; derived, not stored. */
   position: number;
+  rank: string;
   archived: boolean;
+  kind: CategoryKind;
+  /** kind === "todo" 과 같음 */
   is_todo: boolean;
   is_collapsed: boolean;
+  /** 새 메모가 시작할 템플릿 */
+  default_template_id: ID | null;
+  sort_mode: SortMode;
+  /** 완료된 할 일을 맨 아래로 */
+  done_last: boolean;
+  /** 완료 후 며칠 지나면 보관할지, null이면 보관하지 않음 */
+  auto_archive_days: number | null;
+  version: number;
   created_at: number;
   updated_at: number;
 };
@@ -22,7 +43,20 @@ export type Memo = {
   date_ymd: string;
   content_md: string;
   todo_done: boolean;
+  /** todo_done이 true가 된 시각(ms), 미완료면 null */
+  completed_at: number | null;
+  /** archived_at !== null 과 같음. 보관된 메모는 보드에 나오지 않음 */
+  archived: boolean;
+  /** 보관된 시각(ms) */
+  archived_at: number | null;
+  /** 카테고리 맨 위에 고정(고정된 메모끼리는 직접 정렬 순서) */
+  pinned: boolean;
+  /** 즐겨찾기 */
+  starred: boolean;
+  /** Index in rank order among memos of the category with the same `archived`; derived, not stored. */
   position: number;
+  rank: string;
+  version: number;
   created_at: number;
   updated_at: number;
 };
@@ -36,7 +70,9 @@ export type CreateCategoryInput = {
   emoji?: string;
   title: string;
   color: string;
-  is_todo: boolean;
+  is_todo?: boolean;
+  /** 지정하면 is_todo보다 우선 */
+  kind?: CategoryKind;
 };
 
 export type UpdateCategoryInput = {
@@ -44,13 +80,26 @@ export type UpdateCategoryInput = {
   emoji: string;
   title: string;
   color: string;
+  /** 생략하면 현재 종류 유지 */
+  kind?: CategoryKind;
+  expected_version?: number;
 };
 
+export type UpdateCategoryResult =
+  | { status: "saved"; category: Category }
+  | { status: "conflict"; server: Category };
+
 export type SetCategoryCollapsedInput = {
   id: ID;
   is_collapsed: boolean;
 };
 
+export type SetCategorySortInput = {
+  id: ID;
+  sort_mode: SortMode;
+  done_last?: boolean;
+};
+
 export type SetCategoryArchivedInput = {
   id: ID;
   archived: boolean;
@@ -65,8 +114,50 @@ export type ReorderMemosInput = {
   ordered_ids: ID[];
 };
 
+export type Theme = "system" | "light" | "dark";
+
+/** 모든 설정값(미설정 항목은 기본값). 키/타입/기본값은 src-tauri/src/settings.rs의 SETTINGS */
 export type AppSettings = {
+  /** 빈 문자열이면 앱 기본 배경 */
   background_color: string;
+  theme: Theme;
+  font_size: number;
+  default_category_id: ID | null;
+  editor_spellcheck: boolean;
+  /** 로컬 HTTP API(127.0.0.1) 사용. 켜고 끄기는 setHttpApi로 */
+  http_api_enabled: boolean;
+  http_api_port: number;
+  /** HTTP API Bearer 토큰(처음 켤 때 생성) */
+  http_api_token: string;
+};
+
+export type HttpApiStatus = {
+  enabled: boolean;
+  running: boolean;
+  /** 실행 중일 때 예) http://127.0.0.1:27183 */
+  url: string | null;
+  port: number;
+  token: string;
+  /** 켜져 있지만 실행되지 못한 이유(포트 사용 중 등) */
+  error: string | null;
+};
+
+export type SetHttpApiInput = {
+  enabled: boolean;
+  /** 생략하면 현재 포트 유지 */
+  port?: number | null;
+};
+
+/** 부분 수정: 지정한 키만 바뀌고, null이면 기본값으로 되돌림 */
+export type UpdateSettingsInput = {
+  values: Partial<{ [K in keyof AppSettings]: AppSettings[K] | null }>;
+};
+
+/** 설정 내보내기/가져오기 파일. http_api_token은 내보내지도 가져오지도 않음 */
+export type SettingsFile = {
+  format: "ideanode-settings";
+  version: number;
+  settings: Partial<AppSettings>;
 };
 
 export type SetBackgroundColorInput = {
@@ -90,11 +181,354 @@ export type UpdateMemoInput = {
   date_ymd: string;
   content_md: string;
   todo_done: boolean;
+  expected_version?: number;
 };
 
-export type MoveMemoInput = {
+export type UpdateMemoResult =
+  | { status: "saved"; memo: Memo }
+  | { status: "merged"; memo: Memo }
+  | { status: "conflict"; server: Memo; base_version: number; fields: string[] };
+
+/** 대상 카테고리 안의 위치. after_id > before_id > index 순으로 적용, 모두 없으면 끝 */
+export type MemoSlot = {
+  after_id?: ID;
+  before_id?: ID;
+  index?: number;
+};
+
+export type MoveMemoInput = MemoSlot & {
   memo_id: ID;
   to_category_id: ID;
 };
 
+export type MoveMemoToInput = MemoSlot & {
+  memo_id: ID;
+  category_id: ID;
+};
+
+export type MoveMemosInput = {
+  memo_ids: ID[];
+  to_category_id: ID;
+};
+
+export type DeleteMemosInput = {
+  memo_ids: ID[];
+};
+
+export type SetMemosTodoDoneInput = {
+  memo_ids: ID[];
+  todo_done: boolean;
+};
+
+export type SetMemosColorInput = {
+  memo_ids: ID[];
+  color: string;
+};
+
+export type SetMemosDateInput = {
+  memo_ids: ID[];
+  date_ymd: string;
+};
+
+/** 일괄 작업의 항목별 결과. 실패한 항목은 롤백되고 error에 사유가 담긴다 */
+export type BulkItemResult = {
+  id: ID;
+  ok: boolean;
+  error: AppError | null;
+};
+
+export type BulkReport = {
+  succeeded: number;
+  failed: number;
+  items: BulkItemResult[];
+};
+
+export type DuplicateMemoInput = {
+  id: ID;
+  reset_todo?: boolean;
+};
+
+export type DuplicateCategoryInput = {
+  id: ID;
+  reset_todo?: boolean;
+};
+
+export type Template = {
+  id: ID;
+  name: string;
+  emoji: string;
+  title: string;
+  color: string;
+  content_md: string;
+  /** 사용자에게 물어볼 커스텀 플레이스홀더 이름 */
+  prompts: string[];
+  created_at: number;
+  updated_at: number;
+};
+
+export type SaveMemoAsTemplateInput = {
+  memo_id: ID;
+  name: string;
+};
+
+export type CreateMemoFromTemplateInput = {
+  template_id: ID;
+  category_id: ID;
+  date_ymd?: string;
+  values?: Record<string, string>;
+};
+
+export type SetCategoryDefaultTemplateInput = {
+  category_id: ID;
+  template_id: ID | null;
+};
+
+/** 일지 메모는 이 입력으로 열 때만 만들어짐(목록 조회는 만들지 않음) */
+export type OpenJournalEntryInput = {
+  category_id: ID;
+  /** 생략하면 오늘 */
+  date_ymd?: string;
+};
+
+/** 시작/끝 포함 날짜 범위(YYYY-MM-DD) */
+export type DateRangeInput = {
+  from_ymd: string;
+  to_ymd: string;
+  include_archived?: boolean;
+};
+
+export type MonthInput = {
+  /** YYYY-MM */
+  month: string;
+  include_archived?: boolean;
+};
+
+export type DayCount = {
+  date_ymd: string;
+  count: number;
+};
+
+export type TodoDayCount = {
+  date_ymd: string;
+  open: number;
+  done: number;
+};
+
+export type SetCategoryAutoArchiveInput = {
+  id: ID;
+  /** null이면 자동 보관 끔 */
+  days: number | null;
+};
+
+export type SetMemoPinnedInput = {
+  id: ID;
+  pinned: boolean;
+};
+
+export type SetMemoStarredInput = {
+  id: ID;
+  starred: boolean;
+};
+
+export type SetMemoArchivedInput = {
+  id: ID;
+  archived: boolean;
+};
+
+export type ListArchiveInput = {
+  /** 카테고리 제목, 메모 제목/본문에서 찾음(대소문자 무시) */
+  query?: string;
+};
+
+/**
+ * 검색식: 공백으로 구분한 조건을 모두 만족하는 메모. `-`를 앞에 붙이면 제외
+ * 예) tag:infra done:false date:>=2026-01-01 cat:"Backend" color:#ff0000 "정확한 문구" -제외어
+ * 필드: tag, done, pinned, starred, archived(true/false), date(>=, <=, >, <, a..b / today-7 등),
+ * cat(카테고리 제목 또는 id), color, title. archived 조건이 없으면 보관된 항목은 제외
+ */
+export type QueryMemosInput = {
+  query: string;
+  limit?: number | null;
+};
+
+export type Archive = {
+  /** 보관된 카테고리(메모 전체 포함) */
+  categories: CategoryWithMemos[];
+  /** 보관되지 않은 카테고리의 보관된 메모, 카테고리별로 묶음 */
+  memos: CategoryWithMemos[];
+};
+
+export type RecentlyCompletedInput = {
+  /** 며칠 전까지 볼지, 기본 7 */
+  days?: number;
+  limit?: number;
+};
+
+export type StatisticsInput = {
+  /** weekly에 담을 주 수(이번 주 포함), 기본 12 */
+  weeks?: number;
+  include_archived?: boolean;
+};
+
+/** week_start(월요일, 로컬)부터 한 주간의 활동 */
+export type WeekActivity = {
+  week_start: string;
+  created: number;
+  updated: number;
+  completed: number;
+};
+
+export type StatsSummary = {
+  memo_count: number;
+  word_count: number;
+  char_count: number;
+  todo_total: number;
+  todo_done: number;
+  todo_completion_rate: number | null;
+  weekly: WeekActivity[];
+  longest_streak_days: number;
+};
+
+export type CategoryStats = StatsSummary & {
+  category_id: ID;
+  title: string;
+};
+
+export type Statistics = {
+  overall: StatsSummary;
+  categories: CategoryStats[];
+};
+
+export type AppErrorCode =
+  | "not_found"
+  | "validation"
+  | "precondition_failed"
+  | "constraint_violation"
+  | "schema"
+  | "locked"
+  | "database"
+  | "io"
+  | "internal";
+
+export type FieldError = {
+  field: string;
+  code:
+    | "invalid_color"
+    | "invalid_date"
+    | "too_long"
+    | "required"
+    | "invalid_emoji"
+    // 검색식(query_memos)
+    | "unknown_field"
+    | "missing_value"
+    | "missing_term"
+    | "invalid_value"
+    | "unterminated_quote"
+    | "unexpected_character";
+  message: string;
+  /** 입력 문자열 안의 문제 위치(UTF-16 단위라 JS 문자열 인덱스와 같음, end 미포함). 검색식 오류에서만 */
+  span?: { start: number; end: number };
+};
+
+/** Shape of every rejected `invoke` promise. */
+export type AppError = {
+  code: AppErrorCode;
+  entity: "category" | "memo" | "setting" | null;
+  ids: ID[];
+  /**
+   * Sub-code for `precondition_failed`, e.g. "category_archived" or "not_a_permutation",
+   * and for `schema`: "newer_version" | "migration_changed" | "schema_mismatch".
+   */
+  reason: string | null;
+  fields: FieldError[];
+  message: string;
+  detail: string;
+};
 
+
+/** 스마트 카테고리 조건. 지정한 항목은 모두 만족해야 함(목록 항목은 하나라도, tags는 전부) */
+export type SmartFilter = {
+  /** 제목/본문의 #태그 */
+  tags?: string[];
+  /** 제목/본문에 포함(대소문자 무시) */
+  text?: string | null;
+  /** YYYY-MM-DD 또는 today/week_start/week_end/month_start/month_end(+N/-N) */
+  date_from?: string | null;
+  date_to?: string | null;
+  /** 할 일 카테고리의 완료/미완료 메모만 */
+  done?: boolean | null;
+  colors?: string[];
+  /** 비어 있으면 전체 카테고리 */
+  category_ids?: ID[];
+};
+
+export type SmartCategory = {
+  id: ID;
+  emoji: string;
+  title: string;
+  color: string;
+  filter: SmartFilter;
+  position: number;
+  rank: string;
+  created_at: number;
+  updated_at: number;
+};
+
+export type CreateSmartCategoryInput = {
+  emoji?: string | null;
+  title: string;
+  color: string;
+  filter: SmartFilter;
+};
+
+export type UpdateSmartCategoryInput = {
+  id: ID;
+  emoji: string;
+  title: string;
+  color: string;
+  filter: SmartFilter;
+};
+
+export type IntegrityIssue = {
+  kind:
+    | "integrity"
+    | "foreign_key"
+    | "duplicate_rank"
+    | "invalid_rank"
+    | "orphan_memo"
+    | "invalid_date"
+    | "empty_field";
+  /** 문제가 있는 테이블(categories/memos 등). integrity는 "" */
+  table: string;
+  ids: ID[];
+  message: string;
+  /** 로그용 상세(SQLite 메시지 등) */
+  detail: string;
+  /** repair_database로 고칠 수 있는지 */
+  repairable: boolean;
+};
+
+export type IntegrityReport = {
+  ok: boolean;
+  issues: IntegrityIssue[];
+};
+
+export type RepairChange = {
+  action:
+    | "reindex"
+    | "rerank"
+    | "create_category"
+    | "move_memo"
+    | "delete_revision"
+    | "delete_activity"
+    | "clear_default_template";
+  table: string;
+  ids: ID[];
+  message: string;
+};
+
+export type RepairReport = {
+  changes: RepairChange[];
+  /** 복구 후에도 남은 문제(날짜/빈 필드 등 자동으로 고치지 않는 것) */
+  remaining: IntegrityIssue[];
+};
@@ -1,8 +1,9 @@
-import { useEffect, useMemo, useRef, useState } from "react";
+import { useCallback, useEffect, useRef, useState } from "react";
 import { ArrowRightFromLine, Palette, X } from "lucide-react";
 import { listen } from "@tauri-apps/api/event";
 import { useAppStore } from "../store/appStore";
-import type { CategoryWithMemos } from "../types";
+import { api } from "../lib/tauri";
+import type { Archive, CategoryWithMemos } from "../types";
 import { Modal } from "../components/Modal";
 import { BACKGROUND_COLOR_PRESETS, ColorPicker } from "../components/ColorPicker";
 
@@ -17,7 +18,10 @@ function ArchiveCategoryRow({
   onToggleExpanded: () => void;
   onRestore: () => void;
 }) {
-  const memos = item.memos.slice().sort((a, b) => a.position - b.position);
+  // 보관된 카테고리에는 보관/미보관 메모가 섞여 있어 position이 아닌 rank 순서로 정렬
+  const memos = item.memos
+    .slice()
+    .sort((a, b) => (a.rank < b.rank ? -1 : a.rank > b.rank ? 1 : 0));
   return (
     <div className={`archiveRow ${expanded ? "expanded" : ""}`}>
       <div className="archiveRowHeader">
@@ -76,21 +80,85 @@ function ArchiveCategoryRow({
   );
 }
 
+function ArchivedMemoGroup({
+  item,
+  onRestoreMemo,
+}: {
+  item: CategoryWithMemos;
+  onRestoreMemo: (id: string, title: string) => void;
+}) {
+  return (
+    <div className="archiveRow">
+      <div className="archiveRowHeader">
+        <div className="archiveRowMain archiveGroupHeader">
+          {item.category.emoji?.length ? <span className="categoryEmoji">{item.category.emoji}</span> : null}
+          <span className="archiveRowTitle" style={{ color: item.category.color }}>
+            {item.category.title}
+          </span>
+          <span className="archiveRowMeta">{item.memos.length}</span>
+        </div>
+      </div>
+
+      <div className="archiveMemoList">
+        {item.memos.map((m) => (
+          <div key={m.id} className="archiveMemoRow" title={m.title}>
+            {item.category.is_todo ? (
+              <input className="todoCheckbox" type="checkbox" checked={!!m.todo_done} disabled />
+            ) : m.emoji?.length ? (
+              <span className="memoEmoji">{m.emoji}</span>
+            ) : null}
+            <span className={`archiveMemoTitle ${item.category.is_todo && m.todo_done ? "memoDone" : ""}`}>
+              {m.title}
+            </span>
+            <button
+              className="iconOnlyBtn archiveRestoreBtn archiveMemoRestoreBtn"
+              type="button"
+              onClick={() => onRestoreMemo(m.id, m.title)}
+              aria-label="메모 꺼내기"
+              title="꺼내기"
+            >
+              <ArrowRightFromLine size={16} />
+            </button>
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+}
+
+const EMPTY_ARCHIVE: Archive = { categories: [], memos: [] };
+
 export default function ArchiveWindow() {
-  const { categories, refresh, setCategoryArchived, settings, setBackgroundColorLocal, saveBackgroundColor } =
+  const { refresh, setCategoryArchived, setMemoArchived, settings, setBackgroundColorLocal, saveBackgroundColor } =
     useAppStore();
   const [query, setQuery] = useState("");
-  const [confirmRestore, setConfirmRestore] = useState<null | { id: string; title: string }>(null);
+  const [archive, setArchive] = useState<Archive>(EMPTY_ARCHIVE);
+  const [confirmRestore, setConfirmRestore] = useState<null | {
+    kind: "category" | "memo";
+    id: string;
+    title: string;
+  }>(null);
   const [expandedId, setExpandedId] = useState<string | null>(null);
   const [bgOpen, setBgOpen] = useState(false);
   const bgWrapRef = useRef<HTMLDivElement | null>(null);
   const bgDebounceRef = useRef<number | null>(null);
 
+  const loadArchive = useCallback(async () => {
+    try {
+      setArchive(await api.listArchive({ query: query.trim() }));
+    } catch (e) {
+      console.error("archive load error", e);
+    }
+  }, [query]);
+  const loadArchiveRef = useRef(loadArchive);
+  loadArchiveRef.current = loadArchive;
+
   useEffect(() => {
     refresh();
     let unlisten: (() => void) | null = null;
     listen("ideanode:data_changed", async () => {
       await refresh();
+      await loadArchiveRef.current();
     }).then((fn) => {
       unlisten = fn;
     });
@@ -99,6 +167,13 @@ export default function ArchiveWindow() {
     };
   }, [refresh]);
 
+  useEffect(() => {
+    const t = window.setTimeout(() => {
+      loadArchive();
+    }, 150);
+    return () => window.clearTimeout(t);
+  }, [loadArchive]);
+
   useEffect(() => {
     const defaultBg = "#0b1020";
     const next = settings.background_color?.trim().length ? settings.background_color : defaultBg;
@@ -123,15 +198,8 @@ export default function ArchiveWindow() {
     };
   }, [bgOpen]);
 
-  const archived = useMemo(() => {
-    const all = categories.filter((c) => c.category.archived).sort((a, b) => a.category.position - b.category.position);
-    const q = query.trim().toLowerCase();
-    if (!q) return all;
-    return all.filter((c) => {
-      if (c.category.title.toLowerCase().includes(q)) return true;
-      return c.memos.some((m) => m.title.toLowerCase().includes(q));
-    });
-  }, [categories, query]);
+  const archived = archive.categories;
+  const archivedMemoCount = archive.memos.reduce((n, g) => n + g.memos.length, 0);
 
   return (
     <main className="archiveWindowRoot">
@@ -183,7 +251,7 @@ export default function ArchiveWindow() {
             </div>
           ) : null}
         </div>
-        <div className="archiveCount">{archived.length}</div>
+        <div className="archiveCount">{archived.length + archivedMemoCount}</div>
       </div>
 
       <Modal
@@ -202,8 +270,13 @@ export default function ArchiveWindow() {
               onClick={async () => {
                 if (!confirmRestore) return;
                 try {
-                  await setCategoryArchived({ id: confirmRestore.id, archived: false });
-                  setExpandedId((prev) => (prev === confirmRestore.id ? null : prev));
+                  if (confirmRestore.kind === "category") {
+                    await setCategoryArchived({ id: confirmRestore.id, archived: false });
+                    setExpandedId((prev) => (prev === confirmRestore.id ? null : prev));
+                  } else {
+                    await setMemoArchived({ id: confirmRestore.id, archived: false });
+                  }
+                  await loadArchive();
                 } finally {
                   setConfirmRestore(null);
                 }
@@ -214,12 +287,14 @@ export default function ArchiveWindow() {
           </div>
         }
       >
-        <div style={{ padding: 8, color: "rgba(255,255,255,0.8)" }}>카테고리를 복원하시겠습니까?</div>
+        <div style={{ padding: 8, color: "rgba(255,255,255,0.8)" }}>
+          {confirmRestore?.kind === "memo" ? "메모를 원래 자리로 복원하시겠습니까?" : "카테고리를 복원하시겠습니까?"}
+        </div>
       </Modal>
 
-      {archived.length === 0 ? (
+      {archived.length === 0 && archive.memos.length === 0 ? (
         <div className="archiveEmpty">
-          <div className="archiveEmptyTitle">{query.trim() ? "검색 결과가 없습니다" : "보관된 카테고리가 없습니다"}</div>
+          <div className="archiveEmptyTitle">{query.trim() ? "검색 결과가 없습니다" : "보관된 항목이 없습니다"}</div>
           <div className="archiveEmptyDesc">
             {query.trim() ? "다른 키워드로 검색해보세요." : "보관 기능은 카테고리 편집창 우측의 보관 아이콘에서 사용할 수 있어요."}
           </div>
@@ -233,10 +308,25 @@ export default function ArchiveWindow() {
             item={item}
             expanded={expandedId === item.category.id}
             onToggleExpanded={() => setExpandedId((prev) => (prev === item.category.id ? null : item.category.id))}
-            onRestore={() => setConfirmRestore({ id: item.category.id, title: item.category.title })}
+            onRestore={() => setConfirmRestore({ kind: "category", id: item.category.id, title: item.category.title })}
           />
         ))}
       </div>
+
+      {archive.memos.length ? (
+        <>
+          <div className="archiveSectionTitle">보관된 메모</div>
+          <div className="archiveList">
+            {archive.memos.map((item) => (
+              <ArchivedMemoGroup
+                key={item.category.id}
+                item={item}
+                onRestoreMemo={(id, title) => setConfirmRestore({ kind: "memo", id, title })}
+              />
+            ))}
+          </div>
+        </>
+      ) : null}
     </main>
   );
 }
@@ -11,6 +11,7 @@ import { EmojiPicker } from "../components/EmojiPicker";
 import { api } from "../lib/tauri";
 import { formatYmdShort } from "../lib/date";
 import { useAppStore } from "../store/appStore";
+import { MemoConflictBanner } from "../features/memos/MemoConflictBanner";
 import type { Memo } from "../types";
 
 type Mode =
@@ -96,6 +97,12 @@ export default function MemoWindow() {
     content_md: string;
     todo_done: boolean;
   } | null>(null);
+  // 편집 내용의 기준이 된 서버 버전. 저장할 때 expected_version으로 보내면 그사이
+  // 다른 창에서 바뀐 부분과 서버에서 병합되고, 겹치면 conflict가 돌아온다.
+  const baseVersionRef = useRef<number | null>(null);
+  const [conflict, setConflict] = useState<{ server: Memo; fields: string[] } | null>(null);
+  const conflictRef = useRef(conflict);
+  conflictRef.current = conflict;
 
   const editor = useEditor({
     extensions: [
@@ -109,7 +116,33 @@ export default function MemoWindow() {
     onUpdate: ({ editor }) => setContent(editor.getHTML()),
   });
 
+  const memoFields = (memo: Memo) => ({
+    id: memo.id,
+    emoji: memo.emoji ?? "",
+    title: memo.title,
+    color: memo.color,
+    date_ymd: memo.date_ymd,
+    content_md: memo.content_md,
+    todo_done: !!memo.todo_done,
+  });
+
+  /** 서버 쪽 메모(병합 결과/충돌 시 저장본)를 편집기에 반영 */
+  const applyMemo = (memo: Memo) => {
+    setEmoji(memo.emoji ?? "");
+    setTitle(memo.title);
+    setColor(memo.color);
+    setDateYmd(memo.date_ymd);
+    setContent(memo.content_md);
+    if (editor && editor.getHTML() !== memo.content_md) {
+      editor.commands.setContent(memo.content_md?.length ? memo.content_md : "<p></p>");
+    }
+    lastSavedRef.current = memoFields(memo);
+    baseVersionRef.current = memo.version;
+  };
+
   useEffect(() => {
+    // 아직 저장 안 된 편집이 있으면 다른 창의 변경으로 덮어쓰지 않는다(다음 저장 때 병합).
+    if (mode?.kind === "edit" && lastSavedRef.current?.id === mode.memo.id && isDirty()) return;
     setEmoji(initial.emoji);
     setTitle(initial.title);
     setColor(initial.color);
@@ -118,8 +151,12 @@ export default function MemoWindow() {
     setColorOpen(false);
     setEmojiOpen(false);
     setDraftMemoId(null);
-    lastSavedRef.current = null;
-    if (editor) editor.commands.setContent(initial.content_md?.length ? initial.content_md : "<p></p>");
+    setConflict(null);
+    lastSavedRef.current = mode?.kind === "edit" ? memoFields(mode.memo) : null;
+    baseVersionRef.current = mode?.kind === "edit" ? mode.memo.version : null;
+    if (editor && editor.getHTML() !== initial.content_md) {
+      editor.commands.setContent(initial.content_md?.length ? initial.content_md : "<p></p>");
+    }
     // eslint-disable-next-line react-hooks/exhaustive-deps
   }, [initial.emoji, initial.title, initial.color, initial.date_ymd, initial.content_md]);
 
@@ -139,15 +176,8 @@ export default function MemoWindow() {
         })) as Memo;
         if (cancelled) return;
         setDraftMemoId(created.id);
-        lastSavedRef.current = {
-          id: created.id,
-          emoji: created.emoji ?? "",
-          title: created.title,
-          color: created.color,
-          date_ymd: created.date_ymd,
-          content_md: created.content_md,
-          todo_done: !!created.todo_done,
-        };
+        lastSavedRef.current = memoFields(created);
+        baseVersionRef.current = created.version;
         // 메인 창/다른 창에 새 메모가 즉시 보이도록
         try {
           await emit("ideanode:data_changed");
@@ -184,12 +214,11 @@ export default function MemoWindow() {
     };
   };
 
-  const flushSave = async () => {
+  const isDirty = () => {
     const snap = getSnapshot();
-    if (!snap) return;
     const last = lastSavedRef.current;
-    if (
-      last &&
+    if (!snap || !last) return false;
+    return !(
       last.id === snap.id &&
       last.emoji === snap.emoji &&
       last.title === snap.title &&
@@ -197,11 +226,17 @@ export default function MemoWindow() {
       last.date_ymd === snap.date_ymd &&
       last.content_md === snap.content_md &&
       last.todo_done === snap.todo_done
-    ) {
-      return;
-    }
+    );
+  };
+
+  /** 저장 후 닫아도 되면 true. 충돌이 나면 배너를 띄우고 false */
+  const flushSave = async (): Promise<boolean> => {
+    if (conflictRef.current) return false;
+    const snap = getSnapshot();
+    if (!snap) return true;
+    if (lastSavedRef.current && !isDirty()) return true;
     try {
-      await api.updateMemo({
+      const result = await api.updateMemo({
         id: snap.id,
         emoji: snap.emoji,
         title: snap.title,
@@ -209,8 +244,18 @@ export default function MemoWindow() {
         date_ymd: snap.date_ymd,
         content_md: snap.content_md,
         todo_done: snap.todo_done,
+        expected_version: baseVersionRef.current ?? undefined,
       });
-      lastSavedRef.current = snap;
+      if (result.status === "conflict") {
+        setConflict({ server: result.server, fields: result.fields });
+        return false;
+      }
+      if (result.status === "merged") {
+        applyMemo(result.memo);
+      } else {
+        lastSavedRef.current = snap;
+        baseVersionRef.current = result.memo.version;
+      }
       try {
         await emit("ideanode:data_changed");
       } catch {
@@ -219,6 +264,21 @@ export default function MemoWindow() {
     } catch {
       // ignore
     }
+    return true;
+  };
+
+  const resolveConflict = async (keepMine: boolean) => {
+    const current = conflictRef.current;
+    if (!current) return;
+    conflictRef.current = null;
+    setConflict(null);
+    if (keepMine) {
+      // 저장본을 기준으로 다시 저장하면 병합 없이 내 내용이 그대로 기록된다.
+      baseVersionRef.current = current.server.version;
+      await flushSave();
+    } else {
+      applyMemo(current.server);
+    }
   };
 
   useEffect(() => {
@@ -243,7 +303,7 @@ export default function MemoWindow() {
     };
     const onKeyDown = async (e: KeyboardEvent) => {
       if (e.key === "Escape") {
-        await flushSave();
+        if (!(await flushSave())) return;
         try {
           await emit("ideanode:data_changed");
         } catch {}
@@ -315,6 +375,14 @@ export default function MemoWindow() {
         </div>
       </div>
 
+      {conflict && (
+        <MemoConflictBanner
+          fields={conflict.fields}
+          onUseServer={() => resolveConflict(false)}
+          onKeepMine={() => resolveConflict(true)}
+        />
+      )}
+
       <div className="memoWindowBody">
         <EditorContent editor={editor} />
       </div>